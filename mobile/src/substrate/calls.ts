@@ -323,6 +323,14 @@ class SubstrateTransactionService {
 
             status.error = errorInfo;
             }
+        } else if (
+            event.section === PALLETS.VERIFIABLE_CREDENTIALS &&
+            event.method === 'CredentialVerificationFailed'
+        ) {
+            // verifyCredential succeeds on chain for expired or revoked credentials;
+            // the reason is only carried by this event
+            const [, reason] = event.data;
+            status.error = `Credential verification failed: ${reason.toString()}`;
         }
         });
 
@@ -1,12 +1,29 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
+// Skipping ZK verification must never reach an on-chain runtime.
+#[cfg(all(feature = "dev-skip-zk-verification", feature = "production"))]
+compile_error!("`dev-skip-zk-verification` cannot be enabled in a `production` build");
+
 pub use pallet::*;
 
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
 
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
 pub mod weights;
 
+pub mod migrations;
+
+pub mod rate_limit;
+pub use rate_limit::CheckRegistrationRate;
+
+pub mod x509;
+
 use sp_core::crypto::KeyTypeId;
 pub const KEY_TYPE: KeyTypeId = KeyTypeId(*b"bbio"); // Behavioral Biometrics
 
@@ -29,6 +46,17 @@ pub mod crypto {
     }
 }
 
+/// Behavioral consistency check for other pallets gating sensitive operations
+///
+/// Wire it into a dependent pallet's config, e.g. `type BehavioralVerifier = ProofOfPersonhood;`
+pub trait BehavioralVerifier {
+    /// Confidence (0-100) that `pattern_data` matches the stored baseline for `did`
+    fn verify_pattern(
+        did: &sp_core::H256,
+        pattern_data: &[u8],
+    ) -> Result<u8, sp_runtime::DispatchError>;
+}
+
 #[frame_support::pallet]
 pub mod pallet {
     use super::*;
@@ -47,12 +75,12 @@ pub mod pallet {
     use pallet_identity_registry;
     use crate::weights::WeightInfo;
     use pallet_zk_credentials;
-    use codec::DecodeWithMemTracking;
+    use codec::{DecodeAll, DecodeWithMemTracking};
     use sp_runtime::offchain::{
         http,
         Duration,
     };
-    use sp_runtime::{MultiSigner, MultiSignature};
+    use sp_runtime::{MultiSigner, MultiSignature, Percent, RuntimeAppPublic};
     use log;
     use frame_system::offchain::{
         SendSignedTransaction, 
@@ -65,34 +93,60 @@ pub mod pallet {
         Signature as P256Signature,
     };
     use p384::ecdsa::{
+        VerifyingKey as P384VerifyingKey,
         Signature as P384Signature,
     };
+    use rsa::{
+        pss::{Signature as RsaPssSignature, VerifyingKey as RsaPssVerifyingKey},
+        RsaPublicKey,
+    };
     use sp_io::crypto::sr25519_verify;
     use scale_info::prelude::format;
+    use scale_info::prelude::string::String;
     use signature::Verifier;
     use frame_support::traits::Imbalance;
     use sp_runtime::RuntimeDebug;
     use scale_info::TypeInfo;
-    use sp_trie::{verify_trie_proof, LayoutV1};
+    use sp_trie::{read_trie_value, LayoutV1, StorageProof};
     use codec::alloc::string::ToString;
 
     type BalanceOf<T> = <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
 
-    const RECOVERY_DELAY_SECONDS: u64 = 6 * 30 * 24 * 60 * 60;
-    
-    const REGISTRATION_COOLDOWN_SECONDS: u64 = 6 * 30 * 24 * 60 * 60;
+    /// How long (TimeProvider seconds) an ML score counts as recent for re-scoring
+    const ML_SCORE_FRESHNESS: u64 = 10 * 60;
+
+    /// Capacity of the active oracle index; `MaxActiveOracles` must not exceed it
+    const MAX_ACTIVE_ORACLE_IDS: u32 = 64;
+
+    /// Maximum guardians per DID, which also bounds progressive recovery votes
+    const MAX_GUARDIANS: u32 = 10;
 
-    const BASE_RECOVERY_DELAY: u64 = 6 * 30 * 24 * 60 * 60;
+    /// Off-chain local storage key for the pending ML pattern rotation cursor
+    const ML_PATTERN_CURSOR_KEY: &[u8] = b"pop::ml_pattern_cursor";
 
-    const MIN_RECOVERY_DELAY: u64 = 7 * 24 * 60 * 60;
+    /// Absolute typing speed deviation (WPM) always tolerated by the trend check
+    const MIN_TREND_TOLERANCE_WPM: i64 = 5;
 
-    const REQUIRED_RECOVERY_SCORE: u32 = 100;
+    /// Maximum TEE quote length: header, SEV-SNP report and its three-certificate chain
+    const MAX_TEE_QUOTE_LEN: u32 = 8 * 1024;
 
-    const MAX_FRAUD_PROOF_AGE: u64 = 7 * 24 * 60 * 60;
+    /// Maximum AMD root key modulus length (RSA-4096)
+    const MAX_AMD_ROOT_KEY_LEN: u32 = 512;
 
-    const MAX_GUARDIAN_APPROVALS: usize = 5;
+    /// Longest (seconds) a signed behavioral export request may stay valid
+    pub const MAX_BEHAVIORAL_EXPORT_VALIDITY: u64 = 10 * 60;
+
+    /// Maximum open fraud challenges one account may have voted on at once
+    pub const MAX_OPEN_CHALLENGE_VOTES: u32 = 32;
+
+    /// Maximum (DID, nullifier) pairs checked by one `check_registration_conflicts` call
+    pub const MAX_CONFLICT_CHECKS: u32 = 100;
+
+    /// The in-code storage version
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(5);
 
     #[pallet::pallet]
+    #[pallet::storage_version(STORAGE_VERSION)]
     pub struct Pallet<T>(_);
 
     #[pallet::config]
@@ -115,6 +169,167 @@ pub mod pallet {
 
         #[pallet::constant]
         type MinHistoricalStrength: Get<u8>;
+
+        /// Guardian relationship age (seconds) after which the age bonus applies
+        #[pallet::constant]
+        type GuardianAgeBonusThreshold: Get<u64>;
+
+        /// Score bonus for guardians older than `GuardianAgeBonusThreshold`
+        #[pallet::constant]
+        type GuardianAgeBonus: Get<u32>;
+
+        /// Maximum pending ML patterns the off-chain worker scores per run
+        #[pallet::constant]
+        type MaxMLPatternsPerRun: Get<u32>;
+
+        /// Maximum biometrics bound in a single `bind_additional_biometrics_batch` call
+        #[pallet::constant]
+        type MaxBiometricBatchSize: Get<u32>;
+
+        /// Minimum confidence for storing a normally-varying behavioral sample
+        #[pallet::constant]
+        type BehavioralStoreThreshold: Get<u8>;
+
+        /// Minimum confidence for storing a gradually-drifting behavioral sample
+        #[pallet::constant]
+        type BehavioralDriftStoreThreshold: Get<u8>;
+
+        /// Maximum number of simultaneously active ML oracles
+        #[pallet::constant]
+        type MaxActiveOracles: Get<u32>;
+
+        /// Percentage weight of distance similarity in match confidence (remainder goes to sample tiers)
+        #[pallet::constant]
+        type BehavioralSimilarityWeight: Get<u8>;
+
+        /// Feature distance at which similarity drops to zero
+        #[pallet::constant]
+        type BehavioralZeroConfidenceDistance: Get<u32>;
+
+        /// Sample confidence for 0-1, 2-3, 4-5, 6-10 and more than 10 stored samples
+        #[pallet::constant]
+        type BehavioralSampleConfidenceTiers: Get<[u8; 5]>;
+
+        /// Sample count past which score statistics behave as an exponential moving average
+        #[pallet::constant]
+        type MaxScoreStatSamples: Get<u32>;
+
+        /// Minimum guardian relationship age (seconds) before its votes count toward recovery
+        #[pallet::constant]
+        type MinGuardianRelationshipAge: Get<u64>;
+
+        /// Skip ZK verifier calls (other proof checks still apply); development runtimes only,
+        /// and ignored unless the pallet is built with `dev-skip-zk-verification`
+        #[pallet::constant]
+        type DevSkipZkVerification: Get<bool>;
+
+        /// Smallest bond a guardian may hold; must be non-zero so every guardian stays slashable
+        #[pallet::constant]
+        type MinGuardianBond: Get<BalanceOf<Self>>;
+
+        /// Cap on the total bond one account may have reserved across all DIDs it guards (None = uncapped)
+        #[pallet::constant]
+        type MaxGuardianBondPerAccount: Get<Option<BalanceOf<Self>>>;
+
+        /// Minimum recovery proof length in bytes; shorter proofs cannot carry a real ZK proof
+        #[pallet::constant]
+        type MinRecoveryProofLength: Get<u32>;
+
+        /// Most reputation an oracle can gain from consensus matches within one window
+        #[pallet::constant]
+        type MaxReputationGainPerWindow: Get<u8>;
+
+        /// Length (seconds) of the oracle reputation gain window
+        #[pallet::constant]
+        type ReputationGainWindow: Get<u64>;
+
+        /// How long (seconds) after capture a cross-biometric session token stays valid
+        #[pallet::constant]
+        type SessionValidityWindow: Get<u64>;
+
+        /// Length (seconds) of a consensus outcome window; counts halve each window that elapses
+        #[pallet::constant]
+        type ConsensusOutcomeWindow: Get<u64>;
+
+        /// Percentage a new typing speed may deviate from the predicted trend and still count as drift
+        #[pallet::constant]
+        type TrendDeviationTolerancePercent: Get<u8>;
+
+        /// Stored samples required before the envelope can quick-reject a pattern
+        #[pallet::constant]
+        type MinSamplesForEnvelopeRejection: Get<u32>;
+
+        /// Registration and recovery extrinsics an account may submit per window
+        #[pallet::constant]
+        type MaxRegistrationAttemptsPerWindow: Get<u32>;
+
+        /// Length (seconds) of the per-account registration attempt window
+        #[pallet::constant]
+        type RegistrationAttemptWindow: Get<u64>;
+
+        /// Share of a slashed guardian bond paid to the fraud challenger
+        #[pallet::constant]
+        type FraudRewardPercent: Get<Percent>;
+
+        /// Account receiving the rest of a slashed guardian bond
+        #[pallet::constant]
+        type InsurancePot: Get<Self::AccountId>;
+
+        /// Maximum DIDs waiting for ML scoring at once
+        #[pallet::constant]
+        type MaxPendingMLPatterns: Get<u32>;
+
+        /// Delay (seconds) before a guardian recovery request can be executed
+        #[pallet::constant]
+        type RecoveryDelay: Get<u64>;
+
+        /// How long (seconds) a consumed recovery proof nonce blocks reuse before it is pruned
+        #[pallet::constant]
+        type RecoveryProofNonceLifetime: Get<u64>;
+
+        /// Time (seconds) a nullifier must wait before it can be registered again
+        #[pallet::constant]
+        type RegistrationCooldownPeriod: Get<u64>;
+
+        /// Starting finalization delay (seconds) of a progressive recovery
+        #[pallet::constant]
+        type BaseRecoveryDelay: Get<u64>;
+
+        /// Floor (seconds) that recovery evidence can reduce the finalization delay to
+        #[pallet::constant]
+        type MinRecoveryDelay: Get<u64>;
+
+        /// Recovery score a progressive recovery needs before it can be finalized
+        #[pallet::constant]
+        type RequiredRecoveryScore: Get<u32>;
+
+        /// Maximum age (seconds) of a fraud proof or challenged ML score
+        #[pallet::constant]
+        type MaxFraudProofAge: Get<u64>;
+
+        /// Votes a fraud challenge needs before it can be resolved without governance
+        #[pallet::constant]
+        type ChallengeVoteQuorum: Get<u32>;
+
+        /// Lead, as a share of all votes cast, the winning side needs to resolve a challenge
+        #[pallet::constant]
+        type ChallengeVoteMargin: Get<Percent>;
+
+        /// Maximum age (seconds) of a signed ML service response
+        #[pallet::constant]
+        type MLResponseValidity: Get<u64>;
+
+        /// Maximum guardians added in a single `add_guardians_batch` call
+        #[pallet::constant]
+        type MaxGuardiansPerBatch: Get<u32>;
+
+        /// Share of the listed guardians' relationship strength that must approve a recovery
+        #[pallet::constant]
+        type GuardianStrengthThreshold: Get<Percent>;
+
+        /// Seconds a DID's oracle responses may wait for consensus before they are discarded
+        #[pallet::constant]
+        type OracleResponseWindow: Get<u64>;
     }
 
     /// Personhood proof structure
@@ -154,7 +369,7 @@ pub mod pallet {
         /// New commitment
         pub new_commitment: Option<H256>,
         /// Guardian votes (guardian -> vote_strength)
-        pub guardian_votes: BoundedVec<(T::AccountId, u8), ConstU32<10>>,
+        pub guardian_votes: BoundedVec<(T::AccountId, u8), ConstU32<MAX_GUARDIANS>>,
         /// Behavioral biometric confidence (0-100)
         pub behavioral_confidence: u8,
         /// Historical access proof strength (0-100)
@@ -182,7 +397,16 @@ pub mod pallet {
         pub nonce: u64,
         pub signature: [u8; 64],
         pub service_public_key: [u8; 32],
-        pub tee_quote: Option<BoundedVec<u8, ConstU32<512>>>,
+        pub tee_quote: Option<BoundedVec<u8, ConstU32<MAX_TEE_QUOTE_LEN>>>,
+    }
+
+    /// Last off-chain ML failure recorded for an oracle (node-local diagnostics)
+    #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+    pub struct MLDiagnostic {
+        pub did: H256,
+        pub oracle_id: u8,
+        pub error: Vec<u8>,
+        pub recorded_at: u64,
     }
 
     /// Cross-biometric proof structure
@@ -351,6 +575,8 @@ pub mod pallet {
         pub primary_did: H256,
         /// Primary nullifier (first registered biometric)
         pub primary_nullifier: H256,
+        /// Modality of the primary nullifier
+        pub primary_modality: BiometricModality,
         /// Additional biometric nullifiers bound to this personhood
         pub bound_nullifiers: BoundedVec<(H256, BiometricModality), ConstU32<10>>,
         /// When binding was created
@@ -372,6 +598,17 @@ pub mod pallet {
         OptionQuery,
     >;
 
+    /// Storage: Map controller account to DID (cache of the identity registry lookup)
+    #[pallet::storage]
+    #[pallet::getter(fn controller_to_did)]
+    pub type ControllerToDid<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        H256, // DID
+        OptionQuery,
+    >;
+
     /// Storage: Pending recovery requests
     #[pallet::storage]
     #[pallet::getter(fn pending_recoveries)]
@@ -429,6 +666,44 @@ pub mod pallet {
         OptionQuery,
     >;
 
+    /// Total bond each guardian has reserved across all DIDs it guards
+    #[pallet::storage]
+    #[pallet::getter(fn guardian_bond_totals)]
+    pub type GuardianBondTotals<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId, // Guardian
+        BalanceOf<T>,
+        ValueQuery,
+    >;
+
+    /// Cumulative guardian bond slashed by fraud challenges
+    #[pallet::storage]
+    #[pallet::getter(fn total_slashed_guardian_bonds)]
+    pub type TotalSlashedGuardianBonds<T: Config> = StorageValue<_, BalanceOf<T>, ValueQuery>;
+
+    /// Registration and recovery attempts per account: account -> (window start, attempts)
+    #[pallet::storage]
+    pub type RegistrationAttempts<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        (u64, u32),
+        ValueQuery,
+    >;
+
+    /// Index of recoveries each guardian can act on: (guardian, DID) -> ()
+    #[pallet::storage]
+    pub type GuardianRecoveries<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId, // Guardian
+        Blake2_128Concat,
+        H256, // DID
+        (),
+        OptionQuery,
+    >;
+
     /// Progressive recovery requests
     #[pallet::storage]
     #[pallet::getter(fn progressive_recoveries)]
@@ -532,7 +807,7 @@ pub mod pallet {
     /// Storage for patterns pending ML scoring
     #[pallet::storage]
     #[pallet::getter(fn pending_ml_patterns)]
-    pub type PendingMLPatterns<T: Config> = StorageMap<
+    pub type PendingMLPatterns<T: Config> = CountedStorageMap<
         _,
         Blake2_128Concat,
         H256, // DID
@@ -606,11 +881,43 @@ pub mod pallet {
         OptionQuery,
     >;
 
+    /// IDs of the currently active oracles, kept in step with `MLOracleInfo::active`
+    #[pallet::storage]
+    #[pallet::getter(fn active_oracle_ids)]
+    pub type ActiveOracleIds<T: Config> = StorageValue<_, BoundedVec<u8, ConstU32<MAX_ACTIVE_ORACLE_IDS>>, ValueQuery>;
+
+    /// Time of the first oracle response in a DID's current scoring round
+    #[pallet::storage]
+    #[pallet::getter(fn oracle_round_started_at)]
+    pub type OracleRoundStartedAt<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        H256, // DID
+        u64,
+        OptionQuery,
+    >;
+
+    /// Reputation gained by each oracle in its current window: oracle_id -> (window start, gained)
+    #[pallet::storage]
+    #[pallet::getter(fn oracle_reputation_gains)]
+    pub type OracleReputationGains<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        u8, // Oracle ID
+        (u64, u8),
+        ValueQuery,
+    >;
+
     /// Consensus threshold (how many oracles must agree)
     #[pallet::storage]
     #[pallet::getter(fn consensus_threshold)]
     pub type ConsensusThreshold<T: Config> = StorageValue<_, u8, ValueQuery>;
 
+    /// Minimum responses that must agree within tolerance (0 = all responses)
+    #[pallet::storage]
+    #[pallet::getter(fn consensus_agreement_threshold)]
+    pub type ConsensusAgreementThreshold<T: Config> = StorageValue<_, u8, ValueQuery>;
+
     /// Score variance tolerance (max difference between oracle scores)
     #[pallet::storage]
     #[pallet::getter(fn score_variance_tolerance)]
@@ -638,6 +945,44 @@ pub mod pallet {
         ValueQuery,
     >;
 
+    /// Per-challenger nonce mixed into challenge IDs
+    #[pallet::storage]
+    #[pallet::getter(fn challenger_nonces)]
+    pub type ChallengerNonces<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        u64,
+        ValueQuery,
+    >;
+
+    /// Votes cast on fraud challenges: (challenge ID, voter) -> vote_for
+    #[pallet::storage]
+    #[pallet::getter(fn challenge_votes)]
+    pub type ChallengeVotes<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        H256, // Challenge ID
+        Blake2_128Concat,
+        T::AccountId, // Voter
+        bool,
+        OptionQuery,
+    >;
+
+    /// Fraud challenges each account has voted on: (voter, challenge ID) -> ()
+    /// Entries for settled challenges are dropped when the voter next votes or reduces a
+    /// guardian bond; at most `MAX_OPEN_CHALLENGE_VOTES` are kept per voter
+    #[pallet::storage]
+    pub type VoterChallenges<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId, // Voter
+        Blake2_128Concat,
+        H256, // Challenge ID
+        (),
+        OptionQuery,
+    >;
+
     /// Historical score statistics per DID
     #[pallet::storage]
     #[pallet::getter(fn score_statistics)]
@@ -649,6 +994,10 @@ pub mod pallet {
         OptionQuery,
     >;
 
+    /// Recent consensus round outcomes: (window start, successes, failures)
+    #[pallet::storage]
+    pub type ConsensusOutcomes<T: Config> = StorageValue<_, (u64, u32, u32), ValueQuery>;
+
     /// Global score distribution (for population-level anomalies)
     #[pallet::storage]
     #[pallet::getter(fn global_score_distribution)]
@@ -678,17 +1027,33 @@ pub mod pallet {
         ValueQuery,
     >;
 
-    /// AMD SEV root public keys (governance controlled)
+    /// AMD root keys (ARK) trusted to anchor SEV-SNP VCEK chains (governance controlled)
     #[pallet::storage]
     #[pallet::getter(fn amd_root_keys)]
     pub type AMDRootKeys<T: Config> = StorageMap<
         _,
         Blake2_128Concat,
-        [u8; 32], // Key hash
-        [u8; 64], // ECDSA P-384 public key (actually 96 bytes for P-384)
+        [u8; 32], // Modulus hash
+        BoundedVec<u8, ConstU32<MAX_AMD_ROOT_KEY_LEN>>, // RSA modulus, big-endian
+        OptionQuery,
+    >;
+
+    /// Recovery proof nonces already consumed (prevents proof replay)
+    #[pallet::storage]
+    #[pallet::getter(fn used_recovery_proof_nonces)]
+    pub type UsedRecoveryProofNonces<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        H256, // Proof nonce (the new nullifier)
+        u64, // Expires at
         OptionQuery,
     >;
 
+    /// Raw key `on_idle` resumes pruning expired recovery proof nonces from
+    #[pallet::storage]
+    pub type RecoveryProofNoncePruneCursor<T: Config> =
+        StorageValue<_, BoundedVec<u8, ConstU32<128>>, OptionQuery>;
+
     /// Fraud challenge structure
     #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
     #[scale_info(skip_type_params(T))]
@@ -731,6 +1096,70 @@ pub mod pallet {
         pub last_timestamp: u64,
     }
 
+    /// Aggregate health of the ML oracle subsystem
+    #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub struct MlHealthReport {
+        /// Oracles currently marked active
+        pub active_oracles: u32,
+        /// Mean reputation of active oracles (0 if none)
+        pub average_reputation: u8,
+        /// Consensus rounds that produced a score
+        pub consensus_successes: u32,
+        /// Consensus rounds rejected for excessive score variance or expired before
+        /// enough oracles responded within `OracleResponseWindow`
+        pub consensus_failures: u32,
+        /// Successes as a percentage of all rounds (0 if none recorded)
+        pub consensus_success_rate: u8,
+        /// Patterns waiting for oracle scoring
+        pub pending_patterns: u32,
+    }
+
+    /// A DID's behavioral baseline in portable form
+    #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+    pub struct BehavioralExport {
+        pub samples: Vec<StoredBehavioralPattern>,
+        pub envelope: Option<BehavioralEnvelope>,
+        pub score_statistics: Option<ScoreStats>,
+    }
+
+    /// Percentiles of the global score distribution
+    #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub struct ScorePercentiles {
+        pub p1: u8,
+        pub median: u8,
+        pub p99: u8,
+        /// Scores recorded in the distribution
+        pub samples: u32,
+    }
+
+    /// Why a proposed (DID, nullifier) registration would be rejected, if at all
+    #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub struct RegistrationConflict {
+        pub did: H256,
+        pub nullifier: H256,
+        /// The DID already has a registered personhood
+        pub did_registered: bool,
+        /// The nullifier already backs a registered personhood
+        pub nullifier_registered: bool,
+        /// The nullifier is bound as an extra biometric to some personhood
+        pub nullifier_bound: bool,
+        /// The nullifier is malformed (zero)
+        pub invalid_nullifier: bool,
+        /// End of the nullifier's registration cooldown, if still running
+        pub cooling_down_until: Option<u64>,
+    }
+
+    impl RegistrationConflict {
+        /// Whether nothing stands in the way of registering this pair
+        pub fn is_clean(&self) -> bool {
+            !self.did_registered
+                && !self.nullifier_registered
+                && !self.nullifier_bound
+                && !self.invalid_nullifier
+                && self.cooling_down_until.is_none()
+        }
+    }
+
     /// Anomaly detection result
     #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen, DecodeWithMemTracking)]
     pub enum AnomalyType {
@@ -750,6 +1179,14 @@ pub mod pallet {
         Dismissed, // Challenge was invalid
     }
 
+    /// Governance decision on a fraud challenge
+    #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen, DecodeWithMemTracking)]
+    pub enum ChallengeResolution {
+        Upheld,             // Challenge was valid, bond refunded
+        DismissedGoodFaith, // Challenge was wrong but honest, bond refunded
+        DismissedMalicious, // Challenge was abusive, bond slashed
+    }
+
     /// Drift analysis result
     #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
     pub enum DriftAnalysis {
@@ -786,8 +1223,9 @@ pub mod pallet {
     #[pallet::event]
     #[pallet::generate_deposit(pub(super) fn deposit_event)]
     pub enum Event<T: Config> {
-        /// Personhood registered [did, nullifier]
-        PersonhoodRegistered { did: H256, nullifier: H256 },
+        /// Personhood registered [did, nullifier, modality]
+        /// The legacy `register_personhood` path always reports `Fingerprint`
+        PersonhoodRegistered { did: H256, nullifier: H256, modality: BiometricModality },
         /// Recovery requested [did, guardians, active_at]
         RecoveryRequested {
             did: H256,
@@ -830,11 +1268,12 @@ pub mod pallet {
             did: H256,
             final_score: u32,
         },
-        /// Guardian slashed for fraud [did, guardian, amount]
+        /// Guardian slashed for fraud [did, guardian, amount, total_slashed]
         GuardianSlashed {
             did: H256,
             guardian: T::AccountId,
             amount: BalanceOf<T>,
+            total_slashed: BalanceOf<T>,
         },
         /// Primary personhood registered [did, nullifier, modality]
         PrimaryPersonhoodRegistered {
@@ -850,6 +1289,13 @@ pub mod pallet {
             modality: BiometricModality,
         },
         
+        /// Additional biometric removed from personhood [did, nullifier, modality]
+        BiometricUnbound {
+            did: H256,
+            nullifier: H256,
+            modality: BiometricModality,
+        },
+        
         /// Attempted double registration detected [nullifier, existing_did]
         DoubleRegistrationAttempt { nullifier: H256, existing_did: H256 },
         HistoricalKeyRegistered { did: H256, key_hash: H256 },
@@ -886,8 +1332,8 @@ pub mod pallet {
         MLServiceKeyRevoked { public_key: [u8; 32] },
         /// ML response signature invalid [did, reason]
         MLSignatureInvalid { did: H256, reason: Vec<u8> },
-        /// ML service call failed [did, error]
-        MLServiceCallFailed { did: H256, error: Vec<u8> },
+        /// ML service call failed [did, oracle_id, error]
+        MLServiceCallFailed { did: H256, oracle_id: u8, error: Vec<u8> },
         /// Oracle registered [oracle_id, public_key]
         OracleRegistered { oracle_id: u8, public_key: [u8; 32] },
         /// Oracle deactivated [oracle_id, reason]
@@ -934,6 +1380,38 @@ pub mod pallet {
             new_mean: u32,
             new_std_dev: u32,
         },
+        /// Guardian bond adjusted [did, guardian, new_bond]
+        GuardianBondAdjusted {
+            did: H256,
+            guardian: T::AccountId,
+            new_bond: BalanceOf<T>,
+        },
+        /// Historical proof shortened the recovery delay [did, strength, threshold, delay_reduction]
+        HistoricalDelayReduced {
+            did: H256,
+            strength: u8,
+            threshold: u8,
+            delay_reduction: u64,
+        },
+        /// All behavioral data erased for a DID [did]
+        BehavioralDataCleared { did: H256 },
+        /// Oracle signing key rotated [oracle_id, old_public_key, new_public_key]
+        OracleKeyRotated { oracle_id: u8, old_public_key: [u8; 32], new_public_key: [u8; 32] },
+        /// Behavioral recovery evidence contributed nothing [did, reason]
+        BehavioralEvidenceRejected { did: H256, reason: Vec<u8> },
+        /// Guardian removed and its bond released [did, guardian, released_bond]
+        GuardianRemoved {
+            did: H256,
+            guardian: T::AccountId,
+            released_bond: BalanceOf<T>,
+        },
+        /// Recovery evidence shortened the finalization delay [did, evidence_type, reduction_seconds, new_delay]
+        RecoveryDelayReduced {
+            did: H256,
+            evidence_type: EvidenceType,
+            reduction_seconds: u64,
+            new_delay: u64,
+        },
     }
 
     #[pallet::error]
@@ -994,6 +1472,31 @@ pub mod pallet {
         InsufficientChallengeBond,
         InvalidEvidence,
         NotChallengeVoter,
+        SelfGuardianNotAllowed,
+        RecoveryProofReplayed,
+        BatchTooLarge,
+        PatternNotFound,
+        FeatureHashMismatch,
+        ChallengeIdCollision,
+        TooManyOracles,
+        TooManyGuardians,
+        TooManyGuardianVotes,
+        ChallengeBondMissing,
+        ChallengeBondMismatch,
+        EmptyExistenceProof,
+        InvalidExistenceProof,
+        OracleKeyInUse,
+        InvalidStakeEncoding,
+        InsufficientStakeBalance,
+        GuardianBondCapExceeded,
+        ChallengeWindowExpired,
+        PendingQueueFull,
+        AlreadyVotedOnChallenge,
+        CannotUnbindPrimary,
+        NullifierNotBound,
+        GuardianVoteOnOpenChallenge,
+        BehavioralExportExpired,
+        TooManyOpenChallengeVotes,
     }
 
     #[pallet::hooks]
@@ -1012,6 +1515,22 @@ pub mod pallet {
                 }
             }
         }
+
+        fn on_idle(_block_number: BlockNumberFor<T>, remaining_weight: Weight) -> Weight {
+            Self::prune_recovery_proof_nonces(remaining_weight)
+        }
+
+        fn integrity_test() {
+            assert!(
+                T::MaxActiveOracles::get() <= MAX_ACTIVE_ORACLE_IDS,
+                "MaxActiveOracles exceeds the active oracle index capacity",
+            );
+            assert!(
+                !T::DevSkipZkVerification::get() || cfg!(any(test, feature = "dev-skip-zk-verification")),
+                "DevSkipZkVerification requires the `dev-skip-zk-verification` feature",
+            );
+            assert!(!T::MinGuardianBond::get().is_zero(), "MinGuardianBond must be non-zero");
+        }
     }
 
     #[pallet::call]
@@ -1038,11 +1557,8 @@ pub mod pallet {
                 Error::<T>::InvalidCommitment
             );
 
-            // Check DID exists and belongs to caller
-            let identity = pallet_identity_registry::pallet::Identities::<T>::get(&did)
-                .ok_or(Error::<T>::DidNotFound)?;
-            ensure!(who == identity.controller, Error::<T>::NotAuthorized);
-            ensure!(identity.active, Error::<T>::NotAuthorized);
+            // Check DID exists, belongs to caller and is active
+            Self::ensure_active_controller(&did, &who)?;
 
             // Check nullifier is unique
             ensure!(
@@ -1075,15 +1591,20 @@ pub mod pallet {
             // Store in registry
             PersonhoodRegistry::<T>::insert(&nullifier, proof);
             DidToNullifier::<T>::insert(&did, nullifier);
+            ControllerToDid::<T>::insert(&who, did);
             
             // Set cooldown for next registration
-            let cooldown_until = now.saturating_add(REGISTRATION_COOLDOWN_SECONDS);
+            let cooldown_until = now.saturating_add(T::RegistrationCooldownPeriod::get());
             RegistrationCooldown::<T>::insert(&nullifier, cooldown_until);
 
             // Record activity
             LastActivity::<T>::insert(&did, now);
 
-            Self::deposit_event(Event::PersonhoodRegistered { did, nullifier });
+            Self::deposit_event(Event::PersonhoodRegistered {
+                did,
+                nullifier,
+                modality: BiometricModality::Fingerprint,
+            });
 
             Ok(())
         }
@@ -1106,6 +1627,10 @@ pub mod pallet {
                 Self::validate_nullifier(&new_nullifier),
                 Error::<T>::InvalidNullifier
             );
+            ensure!(
+                Self::validate_commitment(&new_commitment),
+                Error::<T>::InvalidCommitment
+            );
             ensure!(
                 !guardians.is_empty() && guardians.len() <= 10,
                 Error::<T>::NotAuthorized
@@ -1141,7 +1666,7 @@ pub mod pallet {
                 .map_err(|_| Error::<T>::InsufficientDeposit)?;
 
             let now = <T as Config>::TimeProvider::now().saturated_into::<u64>();
-            let active_at = now.saturating_add(RECOVERY_DELAY_SECONDS);
+            let active_at = now.saturating_add(T::RecoveryDelay::get());
 
             let guardians_bounded: BoundedVec<T::AccountId, ConstU32<10>> = 
                 guardians.clone().try_into().map_err(|_| Error::<T>::NotAuthorized)?;
@@ -1160,6 +1685,9 @@ pub mod pallet {
             };
 
             PendingRecoveries::<T>::insert(&old_did, request);
+            for guardian in guardians.iter() {
+                GuardianRecoveries::<T>::insert(guardian, &old_did, ());
+            }
 
             Self::deposit_event(Event::RecoveryRequested {
                 did: old_did,
@@ -1218,18 +1746,24 @@ pub mod pallet {
             let now = <T as Config>::TimeProvider::now().saturated_into::<u64>();
             ensure!(now >= request.active_at, Error::<T>::RecoveryPeriodNotElapsed);
 
-            // Check guardian approvals (require 2/3 majority)
+            // Check guardian approvals
             let approvals = GuardianApprovals::<T>::get(&did);
-            let required = (request.guardians.len() * 2 / 3).saturating_add(1);
             ensure!(
-                approvals.len() >= required,
+                Self::guardian_approvals_sufficient(&did, &request.guardians, &approvals),
                 Error::<T>::InsufficientGuardianApprovals
             );
 
             // Get old proof
-            let _old_proof = PersonhoodRegistry::<T>::get(&request.old_nullifier)
+            let old_proof = PersonhoodRegistry::<T>::get(&request.old_nullifier)
                 .ok_or(Error::<T>::PersonhoodProofNotFound)?;
 
+            // The new nullifier may have been claimed since the request was made
+            ensure!(
+                !PersonhoodRegistry::<T>::contains_key(&request.new_nullifier)
+                    && !NullifierClaims::<T>::get(&request.new_nullifier),
+                Error::<T>::NullifierAlreadyUsed
+            );
+
             // Remove old nullifier
             PersonhoodRegistry::<T>::remove(&request.old_nullifier);
 
@@ -1246,17 +1780,16 @@ pub mod pallet {
             // Update registry
             PersonhoodRegistry::<T>::insert(&request.new_nullifier, new_proof);
             DidToNullifier::<T>::insert(&did, request.new_nullifier);
+            ControllerToDid::<T>::remove(&old_proof.controller);
+            ControllerToDid::<T>::insert(&who, did);
+            Self::rotate_binding_nullifier(&did, &request.old_nullifier, &request.new_nullifier, now);
 
             // Set cooldown
-            let cooldown_until = now.saturating_add(REGISTRATION_COOLDOWN_SECONDS);
+            let cooldown_until = now.saturating_add(T::RegistrationCooldownPeriod::get());
             RegistrationCooldown::<T>::insert(&request.new_nullifier, cooldown_until);
 
-            // Clean up
-            PendingRecoveries::<T>::remove(&did);
-            GuardianApprovals::<T>::remove(&did);
-
-            // Return deposit
-            T::Currency::unreserve(&request.requester, request.deposit);
+            // Clean up and return deposit
+            ensure!(Self::close_pending_recovery(&did).is_some(), Error::<T>::RecoveryRequestNotFound);
 
             // Record activity
             LastActivity::<T>::insert(&did, now);
@@ -1280,12 +1813,8 @@ pub mod pallet {
 
             ensure!(request.requester == who, Error::<T>::NotAuthorized);
 
-            // Return deposit
-            T::Currency::unreserve(&request.requester, request.deposit);
-
-            // Clean up
-            PendingRecoveries::<T>::remove(&did);
-            GuardianApprovals::<T>::remove(&did);
+            // Clean up and return deposit
+            Self::close_pending_recovery(&did);
 
             Self::deposit_event(Event::RecoveryCancelled { did });
 
@@ -1299,20 +1828,13 @@ pub mod pallet {
             let who = ensure_signed(origin)?;
 
             // Get DID from account
-            let (did, identity) = pallet_identity_registry::pallet::Pallet::<T>::get_identity_by_account(&who)
-                .ok_or(Error::<T>::DidNotFound)?;
-
-            ensure!(identity.active, Error::<T>::NotAuthorized);
+            let did = Self::active_did_of(&who)?;
 
             let now = <T as Config>::TimeProvider::now().saturated_into::<u64>();
             LastActivity::<T>::insert(&did, now);
 
             // Auto-cancel recovery if user becomes active
-            if PendingRecoveries::<T>::contains_key(&did) {
-                let request = PendingRecoveries::<T>::get(&did).unwrap();
-                T::Currency::unreserve(&request.requester, request.deposit);
-                PendingRecoveries::<T>::remove(&did);
-                GuardianApprovals::<T>::remove(&did);
+            if Self::close_pending_recovery(&did).is_some() {
                 Self::deposit_event(Event::RecoveryCancelled { did });
             }
 
@@ -1333,48 +1855,11 @@ pub mod pallet {
             let who = ensure_signed(origin)?;
             
             // Verify DID ownership
-            let identity = Identities::<T>::get(&did)
-                .ok_or(Error::<T>::DidNotFound)?;
-            ensure!(identity.controller == who, Error::<T>::NotAuthorized);
-            
-            // Validate strength (1-10)
-            ensure!(
-                relationship_strength >= 1 && relationship_strength <= 10,
-                Error::<T>::InvalidRelationshipStrength
-            );
-            
-            // Ensure guardian doesn't already exist
-            ensure!(
-                !GuardianRelationships::<T>::contains_key(&did, &guardian),
-                Error::<T>::GuardianAlreadyExists
-            );
-            
-            // Require minimum bond (prevents sybil guardians)
-            let min_bond = T::RecoveryDeposit::get();
-            ensure!(bond_amount >= min_bond, Error::<T>::InsufficientGuardianBond);
-            
-            // Reserve bond from guardian
-            T::Currency::reserve(&guardian, bond_amount)?;
+            Self::ensure_active_controller(&did, &who)?;
             
             let now = <T as Config>::TimeProvider::now().saturated_into::<u64>();
             
-            let relationship = GuardianRelationship {
-                guardian: guardian.clone(),
-                relationship_strength,
-                established_at: now,
-                interaction_count: 0,
-                bonded_stake: bond_amount,
-            };
-            
-            GuardianRelationships::<T>::insert(&did, &guardian, relationship);
-            
-            Self::deposit_event(Event::GuardianRelationshipEstablished {
-                did,
-                guardian,
-                strength: relationship_strength,
-            });
-            
-            Ok(())
+            Self::add_guardian_to(&did, &who, guardian, relationship_strength, bond_amount, now)
         }
         
         /// Initiate progressive recovery (catastrophic loss scenario)
@@ -1423,17 +1908,20 @@ pub mod pallet {
                 historical_proof_strength: 0,
                 economic_stake: Zero::zero(),
                 requested_at: now,
-                finalization_delay: BASE_RECOVERY_DELAY,
-                base_delay: BASE_RECOVERY_DELAY,
+                finalization_delay: T::BaseRecoveryDelay::get(),
+                base_delay: T::BaseRecoveryDelay::get(),
                 requester: who,
                 recovery_score: 0,
             };
             
             ProgressiveRecoveries::<T>::insert(&old_did, request);
+            for guardian in GuardianRelationships::<T>::iter_key_prefix(&old_did) {
+                GuardianRecoveries::<T>::insert(&guardian, &old_did, ());
+            }
             
             Self::deposit_event(Event::ProgressiveRecoveryInitiated {
                 did: old_did,
-                base_delay: BASE_RECOVERY_DELAY,
+                base_delay: T::BaseRecoveryDelay::get(),
             });
             
             Ok(())
@@ -1454,6 +1942,7 @@ pub mod pallet {
                 .ok_or(Error::<T>::ProgressiveRecoveryNotFound)?;
             
             let now = <T as Config>::TimeProvider::now().saturated_into::<u64>();
+            let delay_before = recovery.finalization_delay;
             let score_increase: u32;
 
             match evidence_type {
@@ -1487,48 +1976,44 @@ pub mod pallet {
                     
                     if !found {
                         recovery.guardian_votes.try_push((who.clone(), vote_strength))
-                            .map_err(|_| Error::<T>::NotAuthorized)?;
+                            .map_err(|_| Error::<T>::TooManyGuardianVotes)?;
                     }
                     
                     // Score: weighted votes (max 30 points)
-                    let guardian_score: u32 = recovery.guardian_votes.iter()
-                        .map(|(guardian, vote_strength)| {
-                            GuardianRelationships::<T>::get(&did, guardian)
-                                .map(|rel| {
-                                    let base = (*vote_strength as u32) * (rel.relationship_strength as u32);
-                                    let age_bonus = if now.saturating_sub(rel.established_at) > (365 * 24 * 60 * 60) {
-                                        2
-                                    } else {
-                                        0
-                                    };
-                                    base + age_bonus
-                                })
-                                .unwrap_or(0)
-                        })
-                        .sum();
-                    
-                    score_increase = guardian_score.min(30);
+                    score_increase = Self::guardian_contribution(&did, &recovery.guardian_votes, now);
                     
                     // Reduce delay: each vote_strength point = 3 days reduction
                     let delay_reduction = (vote_strength as u64) * 3 * 24 * 60 * 60;
                     recovery.finalization_delay = recovery.finalization_delay
                         .saturating_sub(delay_reduction)
-                        .max(MIN_RECOVERY_DELAY);
+                        .max(T::MinRecoveryDelay::get());
                 },
                 
                 EvidenceType::BehavioralBiometric => {
-                    // Verify behavioral pattern matches stored patterns
-                    let confidence = Self::verify_behavioral_pattern(&did, &evidence_data)?;
-                    recovery.behavioral_confidence = confidence;
+                    // Without a baseline recorded before the loss, the sample could only
+                    // ever be compared against itself, so it earns nothing
+                    if !Self::behavioral_baseline_ready(&did) {
+                        recovery.behavioral_confidence = 0;
+                        score_increase = 0;
+                        Self::deposit_event(Event::BehavioralEvidenceRejected {
+                            did,
+                            reason: b"no prior behavioral baseline".to_vec(),
+                        });
+                    } else {
+                        // Verify behavioral pattern matches stored patterns
+                        // Read-only so a recovering party cannot poison the owner's baseline
+                        let confidence = Self::verify_behavioral_pattern(&did, &evidence_data, true)?;
+                        recovery.behavioral_confidence = confidence;
                     
-                    // Score: 0-30 points based on confidence
-                    score_increase = (confidence as u32 * 30) / 100;
+                        // Score: 0-30 points based on confidence
+                        score_increase = (confidence as u32 * 30) / 100;
                     
-                    // High confidence (>80%) reduces delay by 60 days
-                    if confidence > T::MinBehavioralConfidence::get() {
-                        recovery.finalization_delay = recovery.finalization_delay
-                            .saturating_sub(60 * 24 * 60 * 60)
-                            .max(MIN_RECOVERY_DELAY);
+                        // High confidence (>80%) reduces delay by 60 days
+                        if confidence > T::MinBehavioralConfidence::get() {
+                            recovery.finalization_delay = recovery.finalization_delay
+                                .saturating_sub(60 * 24 * 60 * 60)
+                                .max(T::MinRecoveryDelay::get());
+                        }
                     }
                 },
                 
@@ -1540,21 +2025,28 @@ pub mod pallet {
                     // Score: 0-20 points
                     score_increase = (strength as u32 * 20) / 100;
                     
-                    // Strong proof (>90%) reduces delay by 45 days
-                    if strength > T::MinHistoricalStrength::get() {
+                    // Proof above the threshold reduces delay proportionally, up to 45 days
+                    let delay_reduction = Self::historical_delay_reduction(strength);
+                    if delay_reduction > 0 {
                         recovery.finalization_delay = recovery.finalization_delay
-                            .saturating_sub(45 * 24 * 60 * 60)
-                            .max(MIN_RECOVERY_DELAY);
+                            .saturating_sub(delay_reduction)
+                            .max(T::MinRecoveryDelay::get());
                     }
+                    Self::deposit_event(Event::HistoricalDelayReduced {
+                        did,
+                        strength,
+                        threshold: T::MinHistoricalStrength::get(),
+                        delay_reduction,
+                    });
                 },
                 
                 EvidenceType::EconomicStake => {
-                    // Decode stake amount
-                    let stake_amount = BalanceOf::<T>::decode(&mut &evidence_data[..])
-                        .map_err(|_| Error::<T>::InvalidRecoveryProof)?;
+                    // Decode stake amount and check the caller can cover it
+                    let stake_amount = Self::decode_and_validate_stake(&who, &evidence_data)?;
                     
                     // Reserve additional stake
-                    T::Currency::reserve(&who, stake_amount)?;
+                    T::Currency::reserve(&who, stake_amount)
+                        .map_err(|_| Error::<T>::InsufficientStakeBalance)?;
                     recovery.economic_stake = recovery.economic_stake.saturating_add(stake_amount);
                     
                     // Score: 1 point per 1000 tokens (max 20 points)
@@ -1565,7 +2057,7 @@ pub mod pallet {
                     if stake_u128 > 10_000 {
                         recovery.finalization_delay = recovery.finalization_delay
                             .saturating_sub(90 * 24 * 60 * 60)
-                            .max(MIN_RECOVERY_DELAY);
+                            .max(T::MinRecoveryDelay::get());
                     }
                 },
             }
@@ -1587,8 +2079,19 @@ pub mod pallet {
                 delay_remaining: recovery.finalization_delay,
             });
             
+            // Report what this submission actually took off, after clamping to the minimum
+            let reduction_seconds = delay_before.saturating_sub(recovery.finalization_delay);
+            if reduction_seconds > 0 {
+                Self::deposit_event(Event::RecoveryDelayReduced {
+                    did,
+                    evidence_type,
+                    reduction_seconds,
+                    new_delay: recovery.finalization_delay,
+                });
+            }
+            
             // Check if ready for finalization
-            if recovery.recovery_score >= REQUIRED_RECOVERY_SCORE 
+            if recovery.recovery_score >= T::RequiredRecoveryScore::get() 
                 && now >= recovery.requested_at.saturating_add(recovery.finalization_delay) {
                 Self::deposit_event(Event::RecoveryReadyForFinalization {
                     did,
@@ -1620,7 +2123,7 @@ pub mod pallet {
             
             // Check requirements
             ensure!(
-                final_score >= REQUIRED_RECOVERY_SCORE,
+                final_score >= T::RequiredRecoveryScore::get(),
                 Error::<T>::RecoveryScoreInsufficient
             );
             
@@ -1629,8 +2132,19 @@ pub mod pallet {
                 Error::<T>::RecoveryPeriodNotElapsed
             );
             
-            // Remove old nullifier
-            PersonhoodRegistry::<T>::remove(&recovery.old_nullifier);
+            // The new nullifier may have been claimed during the recovery window
+            if let Some(new_nullifier) = recovery.new_nullifier {
+                ensure!(
+                    !PersonhoodRegistry::<T>::contains_key(&new_nullifier)
+                        && !NullifierClaims::<T>::get(&new_nullifier),
+                    Error::<T>::NullifierAlreadyUsed
+                );
+            }
+            
+            // Remove old nullifier and the previous controller's DID cache entry
+            if let Some(old_proof) = PersonhoodRegistry::<T>::take(&recovery.old_nullifier) {
+                ControllerToDid::<T>::remove(&old_proof.controller);
+            }
             
             // If new biometric provided, register it
             if let (Some(new_nullifier), Some(new_commitment)) = 
@@ -1647,6 +2161,8 @@ pub mod pallet {
                 
                 PersonhoodRegistry::<T>::insert(&new_nullifier, new_proof);
                 DidToNullifier::<T>::insert(&did, new_nullifier);
+                ControllerToDid::<T>::insert(&who, did);
+                Self::rotate_binding_nullifier(&did, &recovery.old_nullifier, &new_nullifier, now);
             }
             
             // Return economic stake
@@ -1656,6 +2172,7 @@ pub mod pallet {
             
             // Clean up
             ProgressiveRecoveries::<T>::remove(&did);
+            Self::refresh_guardian_recoveries(&did, GuardianRelationships::<T>::iter_key_prefix(&did));
             
             Self::deposit_event(Event::RecoveryFinalized { did });
             
@@ -1663,6 +2180,7 @@ pub mod pallet {
         }
         
         /// Challenge fraudulent recovery and slash guardian
+        /// `fraud_proof` must be signed by the DID's controller key
         #[pallet::call_index(10)]
         #[pallet::weight(<T as Config>::WeightInfo::challenge_recovery())]
         pub fn challenge_recovery(
@@ -1681,18 +2199,28 @@ pub mod pallet {
             let relationship = GuardianRelationships::<T>::get(&did, &fraudulent_guardian)
                 .ok_or(Error::<T>::GuardianNotFound)?;
             
-            // Slash guardian's bond
-            let slashed = T::Currency::slash_reserved(&fraudulent_guardian, relationship.bonded_stake);
-                        
-            // Calculate reward (50% of slashed amount)
-            let divisor: BalanceOf<T> = 2u32.into();
-            let slashed_amount = slashed.0;
+            // Slash only this relationship's bond, never more than is actually reserved
+            let reserved = T::Currency::reserved_balance(&fraudulent_guardian);
+            let slash_target = relationship.bonded_stake.min(reserved);
+            let (slashed_amount, unslashed) = T::Currency::slash_reserved(&fraudulent_guardian, slash_target);
             let slashed_balance = slashed_amount.peek();
-            let reward = slashed_balance / divisor;
             
-            let _imbalance = T::Currency::deposit_creating(&challenger, reward);
+            if !unslashed.is_zero() || slash_target < relationship.bonded_stake {
+                log::warn!("Guardian bond only partially slashed for DID {:?}", did);
+            }
+                        
+            // Challenger's share of the amount actually slashed; the rest goes to the insurance pot
+            let reward = T::FraudRewardPercent::get().mul_floor(slashed_balance);
+            let (reward_imbalance, remainder) = slashed_amount.split(reward);
+            
+            T::Currency::resolve_creating(&challenger, reward_imbalance);
+            T::Currency::resolve_creating(&T::InsurancePot::get(), remainder);
             
             GuardianRelationships::<T>::remove(&did, &fraudulent_guardian);
+            GuardianBondTotals::<T>::mutate(&fraudulent_guardian, |total| {
+                *total = total.saturating_sub(relationship.bonded_stake)
+            });
+            Self::refresh_guardian_recoveries(&did, core::iter::once(fraudulent_guardian.clone()));
             
             if let Some(mut recovery) = ProgressiveRecoveries::<T>::get(&did) {
                 recovery.guardian_votes.retain(|(g, _)| *g != fraudulent_guardian);
@@ -1703,10 +2231,16 @@ pub mod pallet {
                 ProgressiveRecoveries::<T>::insert(&did, recovery);
             }
             
+            let total_slashed = TotalSlashedGuardianBonds::<T>::mutate(|total| {
+                *total = total.saturating_add(slashed_balance);
+                *total
+            });
+            
             Self::deposit_event(Event::GuardianSlashed {
                 did,
                 guardian: fraudulent_guardian,
                 amount: slashed_balance,
+                total_slashed,
             });
             
             Ok(())
@@ -1722,10 +2256,7 @@ pub mod pallet {
         ) -> DispatchResult {
             let who = ensure_signed(origin)?;
             
-            let (did, identity) = pallet_identity_registry::pallet::Pallet::<T>::get_identity_by_account(&who)
-                .ok_or(Error::<T>::DidNotFound)?;
-            
-            ensure!(identity.active, Error::<T>::NotAuthorized);
+            let did = Self::active_did_of(&who)?;
             
             // Decode behavioral features
             let features = BehavioralFeatures::decode(&mut &pattern_data[..])
@@ -1760,11 +2291,8 @@ pub mod pallet {
                 Error::<T>::InvalidNullifier
             );
             
-            // Check DID exists and belongs to caller
-            let identity = pallet_identity_registry::pallet::Identities::<T>::get(&did)
-                .ok_or(Error::<T>::DidNotFound)?;
-            ensure!(who == identity.controller, Error::<T>::NotAuthorized);
-            ensure!(identity.active, Error::<T>::NotAuthorized);
+            // Check DID exists, belongs to caller and is active
+            Self::ensure_active_controller(&did, &who)?;
             
             // Check if nullifier is already bound to ANY personhood
             if NullifierClaims::<T>::get(&nullifier) {
@@ -1778,6 +2306,13 @@ pub mod pallet {
                 }
             }
             
+            // Unbound nullifiers cool down before they can anchor a personhood again
+            let now = <T as Config>::TimeProvider::now().saturated_into::<u64>();
+            ensure!(
+                now > RegistrationCooldown::<T>::get(&nullifier),
+                Error::<T>::RegistrationTooSoon
+            );
+            
             // Verify uniqueness proof
             Self::verify_uniqueness_proof(&nullifier, &commitment, &uniqueness_proof)?;
             
@@ -1785,12 +2320,11 @@ pub mod pallet {
             T::Currency::reserve(&who, T::RegistrationDeposit::get())
                 .map_err(|_| Error::<T>::InsufficientDeposit)?;
             
-            let now = <T as Config>::TimeProvider::now().saturated_into::<u64>();
-            
             // Create biometric binding
             let binding = BiometricBinding {
                 primary_did: did,
                 primary_nullifier: nullifier,
+                primary_modality: modality.clone(),
                 bound_nullifiers: BoundedVec::default(),
                 created_at: now,
                 updated_at: now,
@@ -1814,6 +2348,7 @@ pub mod pallet {
             };
             PersonhoodRegistry::<T>::insert(&nullifier, proof);
             DidToNullifier::<T>::insert(&did, nullifier);
+            ControllerToDid::<T>::insert(&who, did);
             
             Self::deposit_event(Event::PrimaryPersonhoodRegistered {
                 did,
@@ -1844,64 +2379,12 @@ pub mod pallet {
             
             ensure!(binding.controller == who, Error::<T>::NotAuthorized);
             
-            // Check nullifier not already used
-            ensure!(
-                !NullifierClaims::<T>::get(&new_nullifier),
-                Error::<T>::NullifierAlreadyBound
-            );
-            
-            // Check modality not already registered
-            if new_modality == BiometricModality::Fingerprint && 
-            binding.primary_nullifier != new_nullifier {
-                // Check if already in bound list
-                for (_, modality) in binding.bound_nullifiers.iter() {
-                    ensure!(
-                        *modality != new_modality,
-                        Error::<T>::ModalityAlreadyRegistered
-                    );
-                }
-            }
-            
-            // Check session token not used
-            ensure!(
-                !UsedSessionTokens::<T>::contains_key(&cross_biometric_proof.session_id),
-                Error::<T>::SessionTokenUsed
-            );
-            
             let now = <T as Config>::TimeProvider::now().saturated_into::<u64>();
             
-            // Session token valid for 5 minutes
-            ensure!(
-                now.saturating_sub(cross_biometric_proof.captured_at) < 300,
-                Error::<T>::SessionTokenExpired
-            );
-            
-            // Verify cross-biometric ZK proof
-            // This proves: "I have BOTH biometrics from the SAME capture session"
-            Self::verify_cross_biometric_proof(
-                &binding.primary_nullifier,
-                &new_nullifier,
-                &cross_biometric_proof,
-            )?;
-            
-            // Mark session as used
-            UsedSessionTokens::<T>::insert(&cross_biometric_proof.session_id, now);
-            
-            // Add to binding
-            binding.bound_nullifiers.try_push((new_nullifier, new_modality.clone()))
-                .map_err(|_| Error::<T>::MaxBiometricsReached)?;
-            binding.updated_at = now;
+            Self::bind_biometric_to(&did, &mut binding, new_nullifier, new_modality, &cross_biometric_proof, now)?;
             
             // Update storage
             PersonhoodBindings::<T>::insert(&did, binding);
-            BiometricBindings::<T>::insert(&new_nullifier, did);
-            NullifierClaims::<T>::insert(&new_nullifier, true);
-            
-            Self::deposit_event(Event::BiometricBound {
-                did,
-                nullifier: new_nullifier,
-                modality: new_modality,
-            });
             
             Ok(())
         }
@@ -1916,10 +2399,7 @@ pub mod pallet {
             let who = ensure_signed(origin)?;
             
             // Get DID from account
-            let (did, identity) = pallet_identity_registry::pallet::Pallet::<T>::get_identity_by_account(&who)
-                .ok_or(Error::<T>::DidNotFound)?;
-            
-            ensure!(identity.active, Error::<T>::NotAuthorized);
+            let did = Self::active_did_of(&who)?;
             
             let now = <T as Config>::TimeProvider::now().saturated_into::<u64>();
             
@@ -1940,19 +2420,28 @@ pub mod pallet {
 
         /// Store ML oracle response (called by off-chain worker)
         #[pallet::call_index(15)]
-        #[pallet::weight(<T as Config>::WeightInfo::store_ml_score())]
+        #[pallet::weight(<T as Config>::WeightInfo::store_ml_score(T::MaxActiveOracles::get()))]
         pub fn store_oracle_response(
             origin: OriginFor<T>,
             oracle_id: u8,
             did: H256,
             score: u8,
             nonce: u64,
+            features_hash: H256,
         ) -> DispatchResult {
             ensure_none(origin)?;
             
             // Validate score
             ensure!(score <= 100, Error::<T>::InvalidFeatureData);
             
+            // Score must be for the features currently pending for this DID
+            let pending = PendingMLPatterns::<T>::get(&did)
+                .ok_or(Error::<T>::PatternNotFound)?;
+            ensure!(
+                Self::features_commitment(&pending) == features_hash,
+                Error::<T>::FeatureHashMismatch
+            );
+            
             // Check oracle exists and is active
             let mut oracle = MLOracles::<T>::get(oracle_id)
                 .ok_or(Error::<T>::OracleNotFound)?;
@@ -1964,16 +2453,22 @@ pub mod pallet {
                 Error::<T>::MLNonceAlreadyUsed
             );
             
+            let now = <T as Config>::TimeProvider::now().saturated_into::<u64>();
+            
+            // A round that never reached consensus must not block a fresh one
+            Self::expire_oracle_round(&did, now);
+            
             // Check oracle hasn't already responded
             ensure!(
                 !OracleResponses::<T>::contains_key(&did, oracle_id),
                 Error::<T>::OracleAlreadyResponded
             );
             
-            let now = <T as Config>::TimeProvider::now().saturated_into::<u64>();
-            
             // Store oracle response
             OracleResponses::<T>::insert(&did, oracle_id, (score, now));
+            OracleRoundStartedAt::<T>::mutate(&did, |started_at| {
+                started_at.get_or_insert(now);
+            });
             
             // Mark nonce as used
             MLNonces::<T>::insert(nonce, true);
@@ -1999,6 +2494,8 @@ pub mod pallet {
         ) -> DispatchResult {
             ensure_root(origin)?;
             
+            ensure!(Self::is_valid_http_url(&url), Error::<T>::InvalidMLServiceUrl);
+            
             let bounded_url: BoundedVec<u8, ConstU32<256>> = url
                 .try_into()
                 .map_err(|_| Error::<T>::InvalidFeatureData)?;
@@ -2018,16 +2515,18 @@ pub mod pallet {
             let who = ensure_signed(origin)?;
             
             // Get DID from account
-            let (did, identity) = pallet_identity_registry::pallet::Pallet::<T>::get_identity_by_account(&who)
-                .ok_or(Error::<T>::DidNotFound)?;
-            
-            ensure!(identity.active, Error::<T>::NotAuthorized);
+            let did = Self::active_did_of(&who)?;
             
             // Decode features
             let features = BehavioralFeatures::decode(&mut &pattern_data[..])
                 .map_err(|_| Error::<T>::InvalidFeatureData)?;
             
-            // Store in pending queue
+            // Store in pending queue; re-queueing a DID replaces its entry without growing the queue
+            ensure!(
+                PendingMLPatterns::<T>::contains_key(&did)
+                    || PendingMLPatterns::<T>::count() < T::MaxPendingMLPatterns::get(),
+                Error::<T>::PendingQueueFull
+            );
             PendingMLPatterns::<T>::insert(&did, features);
             
             Self::deposit_event(Event::PatternQueuedForML { did });
@@ -2068,17 +2567,28 @@ pub mod pallet {
         }
 
         /// Register ML oracle (governance only)
+        /// Passing `None` as `oracle_id` allocates the lowest free ID
         #[pallet::call_index(20)]
         #[pallet::weight(<T as Config>::WeightInfo::register_oracle())]
         pub fn register_oracle(
             origin: OriginFor<T>,
-            oracle_id: u8,
+            oracle_id: Option<u8>,
             endpoint_hash: H256,
             public_key: [u8; 32],
             tee_attestation: Option<Vec<u8>>,
         ) -> DispatchResult {
             ensure_root(origin)?;
             
+            let active_oracles = ActiveOracleIds::<T>::decode_len().unwrap_or(0) as u32;
+            ensure!(
+                active_oracles < T::MaxActiveOracles::get(),
+                Error::<T>::TooManyOracles
+            );
+            
+            let oracle_id = match oracle_id {
+                Some(id) => id,
+                None => Self::next_oracle_id().ok_or(Error::<T>::TooManyOracles)?,
+            };
             ensure!(
                 !MLOracles::<T>::contains_key(oracle_id),
                 Error::<T>::InvalidOracleId
@@ -2101,6 +2611,8 @@ pub mod pallet {
             };
             
             MLOracles::<T>::insert(oracle_id, oracle);
+            ActiveOracleIds::<T>::try_append(oracle_id)
+                .map_err(|_| Error::<T>::TooManyOracles)?;
             
             // Add to trusted keys
             TrustedMLKeys::<T>::insert(public_key, true);
@@ -2123,6 +2635,7 @@ pub mod pallet {
             MLOracles::<T>::try_mutate(oracle_id, |oracle_opt| -> DispatchResult {
                 let oracle = oracle_opt.as_mut().ok_or(Error::<T>::OracleNotFound)?;
                 oracle.active = false;
+                Self::remove_active_oracle_id(oracle_id);
                 
                 // Revoke key
                 TrustedMLKeys::<T>::remove(oracle.public_key);
@@ -2133,6 +2646,50 @@ pub mod pallet {
             })
         }
 
+        /// Rotate an oracle's signing key, keeping its reputation and stats (governance only)
+        /// A new TEE attestation, if given, replaces the one bound to the old key
+        #[pallet::call_index(33)]
+        #[pallet::weight(<T as Config>::WeightInfo::rotate_oracle_key())]
+        pub fn rotate_oracle_key(
+            origin: OriginFor<T>,
+            oracle_id: u8,
+            new_public_key: [u8; 32],
+            new_tee_attestation: Option<Vec<u8>>,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+            
+            ensure!(!TrustedMLKeys::<T>::get(new_public_key), Error::<T>::OracleKeyInUse);
+            
+            let new_tee_attestation: Option<BoundedVec<u8, ConstU32<256>>> = match new_tee_attestation {
+                Some(attestation) => Some(attestation.try_into().map_err(|_| Error::<T>::InvalidFeatureData)?),
+                None => None,
+            };
+            
+            let old_public_key = MLOracles::<T>::try_mutate(oracle_id, |oracle_opt| -> Result<[u8; 32], DispatchError> {
+                let oracle = oracle_opt.as_mut().ok_or(Error::<T>::OracleNotFound)?;
+                let old_public_key = core::mem::replace(&mut oracle.public_key, new_public_key);
+                if new_tee_attestation.is_some() {
+                    oracle.tee_attestation = new_tee_attestation;
+                }
+                
+                // Swap trusted keys; a deactivated oracle's new key stays untrusted
+                TrustedMLKeys::<T>::remove(old_public_key);
+                if oracle.active {
+                    TrustedMLKeys::<T>::insert(new_public_key, true);
+                }
+                
+                Ok(old_public_key)
+            })?;
+            
+            Self::deposit_event(Event::OracleKeyRotated {
+                oracle_id,
+                old_public_key,
+                new_public_key,
+            });
+            
+            Ok(())
+        }
+
         /// Set consensus threshold (governance only)
         #[pallet::call_index(22)]
         #[pallet::weight(<T as Config>::WeightInfo::set_consensus_threshold())]
@@ -2149,6 +2706,23 @@ pub mod pallet {
             Ok(())
         }
 
+        /// Set how many responses must agree within tolerance (governance only)
+        /// Zero requires every response to agree
+        #[pallet::call_index(34)]
+        #[pallet::weight(<T as Config>::WeightInfo::set_consensus_agreement_threshold())]
+        pub fn set_consensus_agreement_threshold(
+            origin: OriginFor<T>,
+            agreement: u8,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+            
+            ensure!(agreement == 0 || agreement >= 2, Error::<T>::InvalidFeatureData);
+            
+            ConsensusAgreementThreshold::<T>::put(agreement);
+            
+            Ok(())
+        }
+
         /// Set score variance tolerance (governance only)
         #[pallet::call_index(23)]
         #[pallet::weight(<T as Config>::WeightInfo::set_variance_tolerance())]
@@ -2176,26 +2750,41 @@ pub mod pallet {
         ) -> DispatchResult {
             let challenger = ensure_signed(origin)?;
             
-            // Get current ML score
-            let (challenged_score, _) = MLScores::<T>::get(&target_did)
+            // Only active identities may challenge scores
+            Self::active_did_of(&challenger)?;
+            
+            // Get current ML score; it can only be challenged while recent
+            let (challenged_score, scored_at) = MLScores::<T>::get(&target_did)
                 .ok_or(Error::<T>::DidNotFound)?;
+            let now = <T as Config>::TimeProvider::now().saturated_into::<u64>();
+            ensure!(
+                now.saturating_sub(scored_at) <= T::MaxFraudProofAge::get(),
+                Error::<T>::ChallengeWindowExpired
+            );
             
             ensure!(claimed_correct_score <= 100, Error::<T>::InvalidFeatureData);
             ensure!(!evidence.is_empty(), Error::<T>::InvalidEvidence);
             
             // Require substantial bond (prevents spam)
             let bond = T::RecoveryDeposit::get() * 5u32.into();
+            Self::ensure_stake_affordable(&challenger, bond)
+                .map_err(|_| Error::<T>::InsufficientChallengeBond)?;
             T::Currency::reserve(&challenger, bond)
                 .map_err(|_| Error::<T>::InsufficientChallengeBond)?;
-            
-            let now = <T as Config>::TimeProvider::now().saturated_into::<u64>();
 
             // Generate challenge ID
+            let nonce = ChallengerNonces::<T>::get(&challenger);
             let challenge_id: H256 = sp_io::hashing::blake2_256(&[
                 target_did.as_bytes(),
                 &challenger.encode(),
                 &now.to_le_bytes(),
+                &nonce.to_le_bytes(),
             ].concat()).into();
+            ensure!(
+                !FraudChallenges::<T>::contains_key(&challenge_id),
+                Error::<T>::ChallengeIdCollision
+            );
+            ChallengerNonces::<T>::insert(&challenger, nonce.saturating_add(1));
             
             let evidence_bounded: BoundedVec<u8, ConstU32<2048>> = evidence
                 .try_into()
@@ -2227,15 +2816,15 @@ pub mod pallet {
 
         /// Resolve fraud challenge (governance/automated)
         #[pallet::call_index(25)]
-        #[pallet::weight(<T as Config>::WeightInfo::resolve_fraud_challenge())]
+        #[pallet::weight(<T as Config>::WeightInfo::resolve_fraud_challenge(T::MaxActiveOracles::get()))]
         pub fn resolve_fraud_challenge(
             origin: OriginFor<T>,
             challenge_id: H256,
-            upheld: bool,
+            resolution: ChallengeResolution,
         ) -> DispatchResult {
             ensure_root(origin)?;
             
-            let mut challenge = FraudChallenges::<T>::get(&challenge_id)
+            let challenge = FraudChallenges::<T>::get(&challenge_id)
                 .ok_or(Error::<T>::ChallengeNotFound)?;
             
             ensure!(
@@ -2244,38 +2833,7 @@ pub mod pallet {
                 Error::<T>::ChallengeAlreadyResolved
             );
             
-            let bond = ChallengeBonds::<T>::get(&challenge_id);
-            
-            let slashed_party = if upheld {
-                challenge.status = ChallengeStatus::Upheld;
-                
-                let now = <T as Config>::TimeProvider::now().saturated_into::<u64>();
-                MLScores::<T>::insert(&challenge.target_did, (challenge.claimed_correct_score, now));
-                
-                T::Currency::unreserve(&challenge.challenger, bond);
-                
-                Self::punish_oracles_for_fraud(&challenge.target_did, challenge.challenged_score);
-                
-                None // No slashing of challenger
-            } else {
-                challenge.status = ChallengeStatus::Dismissed;
-                
-                let (_slashed, _) = T::Currency::slash_reserved(&challenge.challenger, bond);
-                
-                Some(challenge.challenger.clone())
-            };
-            
-            let final_status = challenge.status.clone();
-
-            FraudChallenges::<T>::insert(&challenge_id, challenge);
-
-            Self::deposit_event(Event::ChallengeReviewed {
-                challenge_id,
-                status: final_status,
-                slashed_party,
-            });
-            
-            Ok(())
+            Self::apply_challenge_resolution(challenge_id, challenge, resolution)
         }
 
         /// Update oracle TEE attestation (governance only)
@@ -2318,17 +2876,20 @@ pub mod pallet {
             Ok(())
         }
 
-        /// Add AMD SEV root key (governance only)
+        /// Add an AMD root key (ARK) by its RSA modulus (governance only)
         #[pallet::call_index(28)]
         #[pallet::weight(<T as Config>::WeightInfo::add_amd_root_key())]
         pub fn add_amd_root_key(
             origin: OriginFor<T>,
-            public_key: [u8; 64],
+            modulus: Vec<u8>,
         ) -> DispatchResult {
             ensure_root(origin)?;
             
-            let key_hash = sp_io::hashing::blake2_256(&public_key);
-            AMDRootKeys::<T>::insert(key_hash, public_key);
+            let modulus: BoundedVec<u8, ConstU32<MAX_AMD_ROOT_KEY_LEN>> = modulus
+                .try_into()
+                .map_err(|_| Error::<T>::InvalidPublicKey)?;
+            let key_hash = sp_io::hashing::blake2_256(&modulus);
+            AMDRootKeys::<T>::insert(key_hash, modulus);
             
             log::info!("AMD root key added");
             
@@ -2352,56 +2913,409 @@ pub mod pallet {
             
             Ok(())
         }
-    }
 
-    impl<T: Config> Pallet<T>
-    where
-        T: frame_system::offchain::CreateSignedTransaction<Call<T>> + frame_system::offchain::SigningTypes,
-        T::AuthorityId: OffchainAppCrypto<MultiSigner, MultiSignature>,
-    { 
-        /// Main off-chain worker function
-        fn run_ml_inference(_block_number: BlockNumberFor<T>) -> Result<(), &'static str> {
-            // Check if we have signing keys
-            let signer = Signer::<T, T::AuthorityId>::any_account();
+        /// Top up or reduce a guardian's bonded stake for a DID
+        /// Only the guardian can adjust their own bond
+        #[pallet::call_index(30)]
+        #[pallet::weight(<T as Config>::WeightInfo::adjust_guardian_bond(MAX_OPEN_CHALLENGE_VOTES + 1))]
+        pub fn adjust_guardian_bond(
+            origin: OriginFor<T>,
+            did: H256,
+            delta: BalanceOf<T>,
+            increase: bool,
+        ) -> DispatchResult {
+            let guardian = ensure_signed(origin)?;
             
-            if !signer.can_sign() {
-                log::warn!("No signing keys available for off-chain worker");
-                return Err("No signing key available");
+            let mut relationship = GuardianRelationships::<T>::get(&did, &guardian)
+                .ok_or(Error::<T>::GuardianNotFound)?;
+            
+            if increase {
+                Self::ensure_guardian_bond_within_cap(&guardian, delta)?;
+                T::Currency::reserve(&guardian, delta)?;
+                GuardianBondTotals::<T>::mutate(&guardian, |total| *total = total.saturating_add(delta));
+                relationship.bonded_stake = relationship.bonded_stake.saturating_add(delta);
+            } else {
+                let new_bond = relationship.bonded_stake.saturating_sub(delta);
+                ensure!(
+                    delta <= relationship.bonded_stake && new_bond >= Self::min_guardian_bond(),
+                    Error::<T>::InsufficientGuardianBond
+                );
+                Self::ensure_guardian_bond_unlocked(&did, &guardian)?;
+                T::Currency::unreserve(&guardian, delta);
+                GuardianBondTotals::<T>::mutate(&guardian, |total| *total = total.saturating_sub(delta));
+                relationship.bonded_stake = new_bond;
             }
             
-            // Fetch pending patterns
-            let pending_patterns = Self::get_pending_ml_patterns();
+            let new_bond = relationship.bonded_stake;
+            GuardianRelationships::<T>::insert(&did, &guardian, relationship);
             
-            if pending_patterns.is_empty() {
-                log::debug!("No pending patterns to process");
-                return Ok(());
-            }
+            Self::deposit_event(Event::GuardianBondAdjusted {
+                did,
+                guardian,
+                new_bond,
+            });
             
-            log::info!("Processing {} pending patterns", pending_patterns.len());
+            Ok(())
+        }
+
+        /// Bind several additional biometrics captured in one enrollment session
+        /// Each item is (nullifier, commitment, modality, cross-biometric proof)
+        #[pallet::call_index(31)]
+        #[pallet::weight(
+            <T as Config>::WeightInfo::bind_additional_biometric()
+                .saturating_mul(bindings.len().max(1) as u64)
+        )]
+        pub fn bind_additional_biometrics_batch(
+            origin: OriginFor<T>,
+            did: H256,
+            bindings: Vec<(H256, H256, BiometricModality, CrossBiometricProof)>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
             
-            // Get active oracles
-            let active_oracles: Vec<u8> = MLOracles::<T>::iter()
-                .filter(|(_, oracle)| oracle.active)
-                .map(|(id, _)| id)
-                .collect();
+            ensure!(!bindings.is_empty(), Error::<T>::InvalidFeatureData);
+            ensure!(
+                bindings.len() as u32 <= T::MaxBiometricBatchSize::get(),
+                Error::<T>::BatchTooLarge
+            );
             
-            if active_oracles.is_empty() {
-                log::error!("No active oracles available");
-                return Err("No active oracles");
+            let mut binding = PersonhoodBindings::<T>::get(&did)
+                .ok_or(Error::<T>::BindingNotFound)?;
+            
+            ensure!(binding.controller == who, Error::<T>::NotAuthorized);
+            
+            let now = <T as Config>::TimeProvider::now().saturated_into::<u64>();
+            
+            for (new_nullifier, _new_commitment, new_modality, cross_biometric_proof) in bindings.iter() {
+                Self::bind_biometric_to(
+                    &did,
+                    &mut binding,
+                    *new_nullifier,
+                    new_modality.clone(),
+                    cross_biometric_proof,
+                    now,
+                )?;
             }
+            
+            PersonhoodBindings::<T>::insert(&did, binding);
+            
+            Ok(())
+        }
 
-            for (did, features) in pending_patterns.iter() {
-                // Query each oracle
-                for oracle_id in active_oracles.iter() {
+        /// Rebuild a DID's behavioral envelope from its stored samples
+        #[pallet::call_index(35)]
+        #[pallet::weight(<T as Config>::WeightInfo::recompute_behavioral_envelope())]
+        pub fn recompute_behavioral_envelope(
+            origin: OriginFor<T>,
+            did: H256,
+        ) -> DispatchResult {
+            ensure_signed(origin)?;
+            
+            Self::recompute_envelope(&did)?;
+            
+            Ok(())
+        }
+
+        /// Surface an oracle response the off-chain worker rejected for a pending DID
+        /// (signed with a trusted ML service key)
+        #[pallet::call_index(36)]
+        #[pallet::weight(<T as Config>::WeightInfo::report_ml_failure())]
+        pub fn report_ml_failure(
+            origin: OriginFor<T>,
+            oracle_id: u8,
+            did: H256,
+            mut error: Vec<u8>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            
+            // Only keys governance trusts to run oracles may report on them
+            let reporter = <[u8; 32]>::try_from(&who.encode()[..])
+                .map_err(|_| Error::<T>::MLServiceKeyNotTrusted)?;
+            ensure!(TrustedMLKeys::<T>::get(reporter), Error::<T>::MLServiceKeyNotTrusted);
+            
+            ensure!(MLOracles::<T>::contains_key(oracle_id), Error::<T>::OracleNotFound);
+            ensure!(PendingMLPatterns::<T>::contains_key(&did), Error::<T>::PatternNotFound);
+            
+            error.truncate(128);
+            Self::deposit_event(Event::MLServiceCallFailed { did, oracle_id, error });
+            
+            Ok(())
+        }
+
+        /// Erase all behavioral samples, envelopes, ML scores and any open oracle round for a DID
+        /// (controller or governance, e.g. for erasure requests or after a takeover)
+        #[pallet::call_index(32)]
+        #[pallet::weight(<T as Config>::WeightInfo::clear_behavioral_data())]
+        pub fn clear_behavioral_data(
+            origin: OriginFor<T>,
+            did: H256,
+        ) -> DispatchResult {
+            if let Some(who) = frame_system::ensure_signed_or_root(origin)? {
+                Self::ensure_active_controller(&did, &who)?;
+            }
+            
+            BehavioralPatterns::<T>::remove(&did);
+            BehavioralPatternSamples::<T>::remove(&did);
+            BehavioralEnvelopes::<T>::remove(&did);
+            PendingMLPatterns::<T>::remove(&did);
+            MLScores::<T>::remove(&did);
+            ScoreStatistics::<T>::remove(&did);
+            // Oracle IDs are u8, so one pass clears every response to the pending pattern
+            let _ = OracleResponses::<T>::clear_prefix(&did, u8::MAX as u32 + 1, None);
+            OracleRoundStartedAt::<T>::remove(&did);
+            
+            Self::deposit_event(Event::BehavioralDataCleared { did });
+            
+            Ok(())
+        }
+
+        /// Remove a guardian from a DID and release its bond (DID controller only)
+        #[pallet::call_index(37)]
+        #[pallet::weight(<T as Config>::WeightInfo::remove_guardian())]
+        pub fn remove_guardian(
+            origin: OriginFor<T>,
+            did: H256,
+            guardian: T::AccountId,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            
+            Self::ensure_active_controller(&did, &who)?;
+            
+            let relationship = GuardianRelationships::<T>::get(&did, &guardian)
+                .ok_or(Error::<T>::GuardianNotFound)?;
+            
+            // A guardian whose vote still counts towards a recovery has to stay
+            if let Some(recovery) = ProgressiveRecoveries::<T>::get(&did) {
+                ensure!(
+                    !recovery.guardian_votes.iter().any(|(voter, _)| *voter == guardian),
+                    Error::<T>::RecoveryInProgress
+                );
+            }
+            
+            T::Currency::unreserve(&guardian, relationship.bonded_stake);
+            GuardianBondTotals::<T>::mutate(&guardian, |total| {
+                *total = total.saturating_sub(relationship.bonded_stake)
+            });
+            GuardianRelationships::<T>::remove(&did, &guardian);
+            Self::refresh_guardian_recoveries(&did, core::iter::once(guardian.clone()));
+            
+            Self::deposit_event(Event::GuardianRemoved {
+                did,
+                guardian,
+                released_bond: relationship.bonded_stake,
+            });
+            
+            Ok(())
+        }
+
+        /// Vote on a pending fraud challenge (registered persons only, one vote each)
+        ///
+        /// Once `ChallengeVoteQuorum` votes are cast and one side leads by at least
+        /// `ChallengeVoteMargin` of them, the challenge is resolved: upheld if the
+        /// votes for win, otherwise dismissed in good faith. Slashing a malicious
+        /// challenger stays a governance decision.
+        #[pallet::call_index(38)]
+        #[pallet::weight(
+            <T as Config>::WeightInfo::vote_on_challenge(MAX_OPEN_CHALLENGE_VOTES + 1)
+                .saturating_add(<T as Config>::WeightInfo::resolve_fraud_challenge(T::MaxActiveOracles::get()))
+        )]
+        pub fn vote_on_challenge(
+            origin: OriginFor<T>,
+            challenge_id: H256,
+            vote_for: bool,
+        ) -> DispatchResult {
+            let voter = ensure_signed(origin)?;
+            
+            let did = Self::active_did_of(&voter)
+                .map_err(|_| Error::<T>::NotChallengeVoter)?;
+            ensure!(is_personhood_registered::<T>(&did), Error::<T>::NotChallengeVoter);
+            
+            let mut challenge = FraudChallenges::<T>::get(&challenge_id)
+                .ok_or(Error::<T>::ChallengeNotFound)?;
+            ensure!(
+                challenge.status == ChallengeStatus::Pending || 
+                challenge.status == ChallengeStatus::UnderReview,
+                Error::<T>::ChallengeAlreadyResolved
+            );
+            
+            // Neither side of the dispute gets a say in it
+            ensure!(
+                challenge.challenger != voter && challenge.target_did != did,
+                Error::<T>::NotChallengeVoter
+            );
+            ensure!(
+                !ChallengeVotes::<T>::contains_key(&challenge_id, &voter),
+                Error::<T>::AlreadyVotedOnChallenge
+            );
+            ensure!(
+                Self::prune_voter_challenges(&voter)? < MAX_OPEN_CHALLENGE_VOTES,
+                Error::<T>::TooManyOpenChallengeVotes
+            );
+            
+            ChallengeVotes::<T>::insert(&challenge_id, &voter, vote_for);
+            VoterChallenges::<T>::insert(&voter, &challenge_id, ());
+            if vote_for {
+                challenge.votes_for = challenge.votes_for.saturating_add(1);
+            } else {
+                challenge.votes_against = challenge.votes_against.saturating_add(1);
+            }
+            
+            Self::deposit_event(Event::ChallengeVoted {
+                challenge_id,
+                voter,
+                vote_for,
+            });
+            
+            let total = challenge.votes_for.saturating_add(challenge.votes_against);
+            let lead = challenge.votes_for.abs_diff(challenge.votes_against);
+            let decided = total >= T::ChallengeVoteQuorum::get() &&
+                lead > 0 &&
+                lead >= T::ChallengeVoteMargin::get().mul_ceil(total);
+            
+            if decided {
+                let resolution = if challenge.votes_for > challenge.votes_against {
+                    ChallengeResolution::Upheld
+                } else {
+                    ChallengeResolution::DismissedGoodFaith
+                };
+                Self::apply_challenge_resolution(challenge_id, challenge, resolution)?;
+            } else {
+                FraudChallenges::<T>::insert(&challenge_id, challenge);
+            }
+            
+            Ok(())
+        }
+
+        /// Remove an additional biometric from a personhood (binding controller only)
+        ///
+        /// The primary nullifier anchors the personhood and cannot be unbound. The
+        /// released nullifier enters the registration cooldown before it can be
+        /// registered or bound again.
+        #[pallet::call_index(39)]
+        #[pallet::weight(<T as Config>::WeightInfo::unbind_biometric())]
+        pub fn unbind_biometric(
+            origin: OriginFor<T>,
+            did: H256,
+            nullifier: H256,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            
+            let mut binding = PersonhoodBindings::<T>::get(&did)
+                .ok_or(Error::<T>::BindingNotFound)?;
+            
+            ensure!(binding.controller == who, Error::<T>::NotAuthorized);
+            ensure!(binding.primary_nullifier != nullifier, Error::<T>::CannotUnbindPrimary);
+            
+            let position = binding.bound_nullifiers
+                .iter()
+                .position(|(bound, _)| *bound == nullifier)
+                .ok_or(Error::<T>::NullifierNotBound)?;
+            let (_, modality) = binding.bound_nullifiers.remove(position);
+            
+            let now = <T as Config>::TimeProvider::now().saturated_into::<u64>();
+            binding.updated_at = now;
+            PersonhoodBindings::<T>::insert(&did, binding);
+            
+            BiometricBindings::<T>::remove(&nullifier);
+            NullifierClaims::<T>::remove(&nullifier);
+            RegistrationCooldown::<T>::insert(&nullifier, now.saturating_add(T::RegistrationCooldownPeriod::get()));
+            
+            Self::deposit_event(Event::BiometricUnbound {
+                did,
+                nullifier,
+                modality,
+            });
+            
+            Ok(())
+        }
+
+        /// Add several guardians to a DID at once (DID controller only)
+        /// Each item is (guardian, relationship strength, bond); any invalid item reverts the batch
+        #[pallet::call_index(40)]
+        #[pallet::weight(
+            <T as Config>::WeightInfo::add_guardian()
+                .saturating_mul(guardians.len().max(1) as u64)
+        )]
+        pub fn add_guardians_batch(
+            origin: OriginFor<T>,
+            did: H256,
+            guardians: Vec<(T::AccountId, u8, BalanceOf<T>)>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            
+            ensure!(!guardians.is_empty(), Error::<T>::InvalidFeatureData);
+            ensure!(
+                guardians.len() as u32 <= T::MaxGuardiansPerBatch::get(),
+                Error::<T>::BatchTooLarge
+            );
+            
+            Self::ensure_active_controller(&did, &who)?;
+            
+            let now = <T as Config>::TimeProvider::now().saturated_into::<u64>();
+            
+            for (guardian, relationship_strength, bond_amount) in guardians {
+                Self::add_guardian_to(&did, &who, guardian, relationship_strength, bond_amount, now)?;
+            }
+            
+            Ok(())
+        }
+    }
+
+    impl<T: Config> Pallet<T>
+    where
+        T: frame_system::offchain::CreateSignedTransaction<Call<T>> + frame_system::offchain::SigningTypes,
+        T::AuthorityId: OffchainAppCrypto<MultiSigner, MultiSignature>,
+    { 
+        /// Main off-chain worker function
+        fn run_ml_inference(_block_number: BlockNumberFor<T>) -> Result<(), &'static str> {
+            // Check if we have signing keys
+            let signer = Signer::<T, T::AuthorityId>::any_account();
+            
+            if !signer.can_sign() {
+                log::warn!("No signing keys available for off-chain worker");
+                return Err("No signing key available");
+            }
+            
+            // Fetch pending patterns
+            let pending_patterns = Self::get_pending_ml_patterns();
+            
+            if pending_patterns.is_empty() {
+                log::debug!("No pending patterns to process");
+                return Ok(());
+            }
+            
+            log::info!("Processing {} pending patterns", pending_patterns.len());
+            
+            // Get active oracles
+            let active_oracles = ActiveOracleIds::<T>::get();
+            
+            if active_oracles.is_empty() {
+                log::error!("No active oracles available");
+                return Err("No active oracles");
+            }
+
+            for (did, features) in pending_patterns.iter() {
+                // Query each oracle
+                for oracle_id in active_oracles.iter() {
                     // Skip if already responded
                     if OracleResponses::<T>::contains_key(did, oracle_id) {
                         continue;
                     }
                     
-                    match Self::call_ml_oracle(*oracle_id, features) {
+                    let body = match Self::call_ml_oracle(*oracle_id, features) {
+                        Ok(body) => body,
+                        Err(e) => {
+                            log::error!("ML service call failed for {:?}: {:?}", did, e);
+                            Self::record_ml_diagnostic(did, *oracle_id, e);
+                            continue;
+                        }
+                    };
+                    
+                    match Self::verify_ml_response(*oracle_id, &body) {
                         Ok(signed_response) => {
                             if signed_response.did != *did {
                                 log::error!("DID mismatch from oracle {}", oracle_id);
+                                Self::record_ml_diagnostic(did, *oracle_id, "DID mismatch");
+                                Self::submit_ml_failure_report(*oracle_id, did, "DID mismatch");
                                 continue;
                             }
                             
@@ -2417,6 +3331,7 @@ pub mod pallet {
                             let did_local = *did;
                             let score = signed_response.confidence_score;
                             let nonce = signed_response.nonce;
+                            let features_hash = Self::features_commitment(features);
                             
                             let results = signer.send_signed_transaction(|_account| {
                                 Call::store_oracle_response {
@@ -2424,6 +3339,7 @@ pub mod pallet {
                                     did: did_local,
                                     score,
                                     nonce,
+                                    features_hash,
                                 }
                             });
 
@@ -2434,14 +3350,18 @@ pub mod pallet {
                                     },
                                     Err(e) => {
                                         log::error!("Failed to submit oracle {} response for DID {:?}: {:?}", oracle_id, did, e);
+                                        Self::record_ml_diagnostic(did, *oracle_id, "Failed to submit transaction");
                                     }
                                 }
                             } else {
                                 log::error!("No account available for signing oracle {} response", oracle_id);
+                                Self::record_ml_diagnostic(did, *oracle_id, "No account available for signing");
                             }
                         },
                         Err(e) => {
-                            log::error!("ML service call failed for {:?}: {:?}", did, e);
+                            log::error!("Rejected oracle {} response for {:?}: {:?}", oracle_id, did, e);
+                            Self::record_ml_diagnostic(did, *oracle_id, e);
+                            Self::submit_ml_failure_report(*oracle_id, did, e);
                         }
                     }
                 }
@@ -2450,12 +3370,54 @@ pub mod pallet {
             Ok(())
         }
 
+        /// Report a rejected oracle response on-chain so operators can see it
+        fn submit_ml_failure_report(
+            oracle_id: u8,
+            did: &H256,
+            error: &str,
+        ) {
+            let did = *did;
+            let result = Self::trusted_key_signer().send_signed_transaction(|_account| Call::report_ml_failure {
+                oracle_id,
+                did,
+                error: error.as_bytes().to_vec(),
+            });
+            
+            if !matches!(result, Some((_, Ok(())))) {
+                log::error!("Failed to report oracle {} failure for DID {:?}", oracle_id, did);
+            }
+        }
+
+        /// Signer limited to local keys registered in `TrustedMLKeys`
+        fn trusted_key_signer() -> Signer<T, T::AuthorityId> {
+            type AuthorityPublic<T> = <<T as Config>::AuthorityId as OffchainAppCrypto<
+                <T as frame_system::offchain::SigningTypes>::Public,
+                <T as frame_system::offchain::SigningTypes>::Signature,
+            >>::RuntimeAppPublic;
+            type GenericPublic<T> = <<T as Config>::AuthorityId as OffchainAppCrypto<
+                <T as frame_system::offchain::SigningTypes>::Public,
+                <T as frame_system::offchain::SigningTypes>::Signature,
+            >>::GenericPublic;
+            
+            let trusted = <AuthorityPublic<T> as RuntimeAppPublic>::all()
+                .into_iter()
+                .filter(|key| {
+                    <[u8; 32]>::try_from(key.to_raw_vec().as_slice())
+                        .map_or(false, |raw| TrustedMLKeys::<T>::get(raw))
+                })
+                .map(|key| GenericPublic::<T>::from(key).into())
+                .collect();
+            
+            Signer::<T, T::AuthorityId>::any_account().with_filter(trusted)
+        }
+
         /// Submit oracle response transaction
         #[allow(dead_code)]
         fn submit_oracle_response_transaction(
             signer: &Signer<T, T::AuthorityId>,
             oracle_id: u8,
             response: SignedMLResponse,
+            features_hash: H256,
         ) -> Result<(), &'static str>
         where
             T::AuthorityId: OffchainAppCrypto<MultiSigner, MultiSignature>,
@@ -2470,6 +3432,7 @@ pub mod pallet {
                     did,
                     score,
                     nonce,
+                    features_hash,
                 }
             });
 
@@ -2490,10 +3453,73 @@ pub mod pallet {
     }
     
     impl<T: Config> Pallet<T> {
+        /// Settle a fraud challenge: release or slash the challenger's bond and record the outcome
+        fn apply_challenge_resolution(
+            challenge_id: H256,
+            mut challenge: FraudChallenge<T>,
+            resolution: ChallengeResolution,
+        ) -> DispatchResult {
+            // The recorded bond must exist and still be backed by the challenger's reserve
+            ensure!(
+                ChallengeBonds::<T>::contains_key(&challenge_id),
+                Error::<T>::ChallengeBondMissing
+            );
+            let bond = ChallengeBonds::<T>::take(&challenge_id);
+            ensure!(
+                T::Currency::reserved_balance(&challenge.challenger) >= bond,
+                Error::<T>::ChallengeBondMismatch
+            );
+            
+            let slashed_party = match resolution {
+                ChallengeResolution::Upheld => {
+                    challenge.status = ChallengeStatus::Upheld;
+                    
+                    let now = <T as Config>::TimeProvider::now().saturated_into::<u64>();
+                    MLScores::<T>::insert(&challenge.target_did, (challenge.claimed_correct_score, now));
+                    
+                    let remaining = T::Currency::unreserve(&challenge.challenger, bond);
+                    ensure!(remaining.is_zero(), Error::<T>::ChallengeBondMismatch);
+                    
+                    Self::punish_oracles_for_fraud(&challenge.target_did, challenge.challenged_score);
+                    
+                    None // No slashing of challenger
+                },
+                ChallengeResolution::DismissedGoodFaith => {
+                    challenge.status = ChallengeStatus::Dismissed;
+                    
+                    // Honest mistakes get their bond back
+                    let remaining = T::Currency::unreserve(&challenge.challenger, bond);
+                    ensure!(remaining.is_zero(), Error::<T>::ChallengeBondMismatch);
+                    
+                    None
+                },
+                ChallengeResolution::DismissedMalicious => {
+                    challenge.status = ChallengeStatus::Dismissed;
+                    
+                    let (_slashed, remaining) = T::Currency::slash_reserved(&challenge.challenger, bond);
+                    ensure!(remaining.is_zero(), Error::<T>::ChallengeBondMismatch);
+                    
+                    Some(challenge.challenger.clone())
+                },
+            };
+            
+            let final_status = challenge.status.clone();
+
+            FraudChallenges::<T>::insert(&challenge_id, challenge);
+
+            Self::deposit_event(Event::ChallengeReviewed {
+                challenge_id,
+                status: final_status,
+                slashed_party,
+            });
+            
+            Ok(())
+        }
+
         /// Punish oracles that provided fraudulent scores
         fn punish_oracles_for_fraud(did: &H256, fraudulent_score: u8) {
             // Check which oracles submitted scores close to the fraudulent one
-            for (oracle_id, _oracle) in MLOracles::<T>::iter() {
+            for oracle_id in ActiveOracleIds::<T>::get() {
                 if let Some((score, _)) = OracleResponses::<T>::get(did, oracle_id) {
                     // If oracle's score was within 10 points of fraudulent score
                     let diff = if score > fraudulent_score {
@@ -2508,13 +3534,18 @@ pub mod pallet {
                             if let Some(o) = oracle_opt {
                                 o.reputation = o.reputation.saturating_sub(20);
                                 
-                                if o.reputation < 30 {
+                                if o.reputation < 30 && o.active {
                                     o.active = false;
+                                    Self::remove_active_oracle_id(oracle_id);
                                     log::error!(
                                         "Oracle {} deactivated for fraud (reputation: {})",
                                         oracle_id,
                                         o.reputation
                                     );
+                                    Self::deposit_event(Event::OracleDeactivated {
+                                        oracle_id,
+                                        reason: b"reputation below fraud threshold".to_vec(),
+                                    });
                                 }
                             }
                         });
@@ -2524,9 +3555,11 @@ pub mod pallet {
         }
 
         /// Verify behavioral pattern with feature analysis
+        /// With `read_only` set, the pattern is only compared and the baseline is never modified
         pub fn verify_behavioral_pattern(
             did: &H256,
             pattern_data: &[u8],
+            read_only: bool,
         ) -> Result<u8, Error<T>> {
             // Decode features
             let features = BehavioralFeatures::decode(&mut &pattern_data[..])
@@ -2543,12 +3576,16 @@ pub mod pallet {
             
             if samples.is_empty() {
                 // No baseline - store this as first sample
-                Self::store_behavioral_sample(did, &features)?;
+                if !read_only {
+                    Self::store_behavioral_sample(did, &features)?;
+                }
                 return Ok(0); // Return 0 confidence (no baseline to compare)
             }
             
             // STEP 1: Quick rejection - check if within statistical envelope
-            if let Some(env) = &envelope {
+            // (skipped while the envelope still rests mostly on its initial priors)
+            let envelope_trusted = samples.len() as u32 >= T::MinSamplesForEnvelopeRejection::get();
+            if let Some(env) = envelope.as_ref().filter(|_| envelope_trusted) {
                 let (within_bounds, violations) = Self::is_within_envelope(&features, env);
                 if !within_bounds && violations.len() > 2 {
                     // Multiple feature violations = likely not the same person
@@ -2580,10 +3617,13 @@ pub mod pallet {
             }
             
             // STEP 3: Calculate base confidence score
+            // Samples without an envelope (e.g. after a partial migration) skipped the
+            // envelope check above, so only the lowest sample tier is credited
+            let samples_available = if envelope.is_some() { samples.len() as u32 } else { 1 };
             let base_confidence = Self::calculate_match_confidence(
                 min_distance,
-                envelope.as_ref().unwrap(),
-                samples.len() as u32,
+                envelope.as_ref(),
+                samples_available,
             );
             
             // STEP 4: Apply temporal decay (patterns older than 90 days lose confidence)
@@ -2598,7 +3638,13 @@ pub mod pallet {
             
             let final_confidence = ((base_confidence as u32 * decay_factor) / 100) as u8;
             
+            if read_only {
+                return Ok(final_confidence);
+            }
+            
             // STEP 5: Detect drift (for adaptive learning)
+            let store_threshold = T::BehavioralStoreThreshold::get();
+            let drift_threshold = T::BehavioralDriftStoreThreshold::get();
             let drift = Self::detect_pattern_drift(did, &features, &samples[..]);
             match drift {
                 DriftAnalysis::SuddenChange { distance, confidence } => {
@@ -2611,7 +3657,7 @@ pub mod pallet {
                     // Don't update envelope for sudden changes
                 },
                 DriftAnalysis::GradualDrift { accept_update, .. } => {
-                    if accept_update && final_confidence > 70 {
+                    if accept_update && final_confidence > drift_threshold {
                         // Natural evolution - update envelope and store sample
                         let _ = Self::update_behavioral_envelope(did, &features);
                         let _ = Self::store_behavioral_sample(did, &features);
@@ -2619,7 +3665,7 @@ pub mod pallet {
                 },
                 DriftAnalysis::NormalVariation { .. } => {
                     // Normal variation - store sample if confidence is high
-                    if final_confidence > 80 {
+                    if final_confidence > store_threshold {
                         let _ = Self::store_behavioral_sample(did, &features);
                     }
                 },
@@ -2731,22 +3777,75 @@ pub mod pallet {
     }
 
     impl<T: Config> Pallet<T> {
+        /// Recent (successes, failures), decayed to the current window
+        pub fn consensus_outcomes() -> (u32, u32) {
+            let now = <T as Config>::TimeProvider::now().saturated_into::<u64>();
+            let (_, successes, failures) = Self::decay_consensus_outcomes(ConsensusOutcomes::<T>::get(), now);
+            (successes, failures)
+        }
+        
+        /// Count a consensus round in the current outcome window
+        fn record_consensus_outcome(success: bool) {
+            let now = <T as Config>::TimeProvider::now().saturated_into::<u64>();
+            ConsensusOutcomes::<T>::mutate(|outcomes| {
+                let (window_start, successes, failures) = Self::decay_consensus_outcomes(*outcomes, now);
+                *outcomes = if success {
+                    (window_start, successes.saturating_add(1), failures)
+                } else {
+                    (window_start, successes, failures.saturating_add(1))
+                };
+            });
+        }
+        
+        /// Roll the outcome window forward to `now`, halving counts once per elapsed window
+        fn decay_consensus_outcomes(outcomes: (u64, u32, u32), now: u64) -> (u64, u32, u32) {
+            let (window_start, successes, failures) = outcomes;
+            let window = T::ConsensusOutcomeWindow::get();
+            if window == 0 || now < window_start.saturating_add(window) {
+                return outcomes;
+            }
+            
+            let elapsed = (now - window_start) / window;
+            let shift = elapsed.min(32) as u32;
+            (
+                window_start.saturating_add(elapsed.saturating_mul(window)),
+                successes.checked_shr(shift).unwrap_or(0),
+                failures.checked_shr(shift).unwrap_or(0),
+            )
+        }
+        
         /// Update oracle reputation
         fn update_oracle_reputation(oracle_id: u8, matched_consensus: bool) {
             MLOracles::<T>::mutate(oracle_id, |oracle_opt| {
                 if let Some(oracle) = oracle_opt {
                     if matched_consensus {
                         oracle.consensus_matches = oracle.consensus_matches.saturating_add(1);
-                        // Increase reputation (max 100)
-                        oracle.reputation = oracle.reputation.saturating_add(1).min(100);
+                        
+                        // Increase reputation (max 100), rate-limited per window
+                        let now = <T as Config>::TimeProvider::now().saturated_into::<u64>();
+                        OracleReputationGains::<T>::mutate(oracle_id, |(window_start, gained)| {
+                            if now >= window_start.saturating_add(T::ReputationGainWindow::get()) {
+                                *window_start = now;
+                                *gained = 0;
+                            }
+                            if *gained < T::MaxReputationGainPerWindow::get() && oracle.reputation < 100 {
+                                oracle.reputation = oracle.reputation.saturating_add(1);
+                                *gained = gained.saturating_add(1);
+                            }
+                        });
                     } else {
                         // Decrease reputation significantly for outliers
                         oracle.reputation = oracle.reputation.saturating_sub(5);
                         
                         // Deactivate if reputation drops below 50
-                        if oracle.reputation < 50 {
+                        if oracle.reputation < 50 && oracle.active {
                             oracle.active = false;
+                            Self::remove_active_oracle_id(oracle_id);
                             log::error!("Oracle {} deactivated due to low reputation", oracle_id);
+                            Self::deposit_event(Event::OracleDeactivated {
+                                oracle_id,
+                                reason: b"reputation below threshold".to_vec(),
+                            });
                         }
                     }
                     
@@ -2759,42 +3858,79 @@ pub mod pallet {
         }
 
         /// Get pending patterns that need ML scoring
-        fn get_pending_ml_patterns() -> Vec<(H256, BehavioralFeatures)> {
+        /// Resumes from a cursor kept in off-chain local storage so every pending DID
+        /// is eventually served, wrapping around to the start of the map
+        pub fn get_pending_ml_patterns() -> Vec<(H256, BehavioralFeatures)> {
+            let limit = T::MaxMLPatternsPerRun::get() as usize;
             let mut patterns = Vec::new();
+            if limit == 0 {
+                return patterns;
+            }
+            
+            let cursor = sp_io::offchain::local_storage_get(
+                sp_core::offchain::StorageKind::PERSISTENT,
+                ML_PATTERN_CURSOR_KEY,
+            );
+            let mut last_visited: Option<Vec<u8>> = None;
             
-            // Iterate through pending patterns
-            for (did, features) in PendingMLPatterns::<T>::iter() {
+            // First pass: everything after the cursor
+            let after_cursor = match &cursor {
+                Some(raw_key) => PendingMLPatterns::<T>::iter_from(raw_key.clone()),
+                None => PendingMLPatterns::<T>::iter(),
+            };
+            for (did, features) in after_cursor {
+                if patterns.len() >= limit {
+                    break;
+                }
+                last_visited = Some(PendingMLPatterns::<T>::hashed_key_for(&did));
                 // Only process if we don't have a recent ML score
                 if !Self::has_recent_ml_score(&did) {
                     patterns.push((did, features));
                 }
-                
-                // Limit batch size to avoid timeout
-                if patterns.len() >= 10 {
-                    break;
+            }
+            
+            // Second pass: wrap around up to the cursor
+            if let Some(raw_cursor) = &cursor {
+                for (did, features) in PendingMLPatterns::<T>::iter() {
+                    if patterns.len() >= limit {
+                        break;
+                    }
+                    let raw_key = PendingMLPatterns::<T>::hashed_key_for(&did);
+                    if raw_key >= *raw_cursor {
+                        break;
+                    }
+                    last_visited = Some(raw_key);
+                    if !Self::has_recent_ml_score(&did) {
+                        patterns.push((did, features));
+                    }
                 }
             }
             
+            if let Some(raw_key) = last_visited {
+                sp_io::offchain::local_storage_set(
+                    sp_core::offchain::StorageKind::PERSISTENT,
+                    ML_PATTERN_CURSOR_KEY,
+                    &raw_key,
+                );
+            }
+            
             patterns
         }
         
-        /// Check if DID has a recent ML score (within last 100 blocks)
+        /// Check if DID has a recent ML score (MLScores timestamps come from TimeProvider)
         fn has_recent_ml_score(did: &H256) -> bool {
             if let Some((_, timestamp)) = MLScores::<T>::get(did) {
                 let now = <T as Config>::TimeProvider::now().saturated_into::<u64>();
-                let age = now.saturating_sub(timestamp);
-                let max_age = 100 * 6; // ~10 minutes (assuming 6s blocks)
-                
-                return age < max_age;
+                return now.saturating_sub(timestamp) < ML_SCORE_FRESHNESS;
             }
             false
         }
         
-        /// Call ML oracle via HTTP with signature verification
+        /// Call ML oracle via HTTP and return the raw response body
         fn call_ml_oracle(
             oracle_id: u8,
             features: &BehavioralFeatures,
-        ) -> Result<SignedMLResponse, &'static str> {
+        ) -> Result<Vec<u8>, &'static str> {
             // Get oracle info
             let oracle = MLOracles::<T>::get(oracle_id)
                 .ok_or("Oracle not found")?;
@@ -2830,9 +3966,15 @@ pub mod pallet {
                 return Err("Oracle error");
             }
             
-            let body = response.body().collect::<Vec<u8>>();
+            Ok(response.body().collect::<Vec<u8>>())
+        }
+
+        /// Parse an oracle response body and check it against the oracle's keys
+        fn verify_ml_response(oracle_id: u8, body: &[u8]) -> Result<SignedMLResponse, &'static str> {
+            let oracle = MLOracles::<T>::get(oracle_id)
+                .ok_or("Oracle not found")?;
             
-            let signed_response = Self::parse_signed_ml_response(&body)?;
+            let signed_response = Self::parse_signed_ml_response(body)?;
             
             // Verify signature matches oracle's public key
             if signed_response.service_public_key != oracle.public_key {
@@ -2844,11 +3986,38 @@ pub mod pallet {
                 Self::verify_tee_attestation(&signed_response, attestation)?;
             }
             
-            Self::verify_ml_response_signature(&signed_response)?;
+            let now = sp_io::offchain::timestamp().unix_millis() / 1000;
+            Self::verify_ml_response_signature_at(&signed_response, now)?;
             
             Ok(signed_response)
         }
 
+        /// Record the latest off-chain failure for an oracle in local storage
+        /// Operators can read it back via `offchain_localStorageGet`
+        fn record_ml_diagnostic(did: &H256, oracle_id: u8, error: &str) {
+            let diagnostic = MLDiagnostic {
+                did: *did,
+                oracle_id,
+                error: error.as_bytes().to_vec(),
+                recorded_at: sp_io::offchain::timestamp().unix_millis(),
+            };
+            let key = format!("ml_diagnostic_{}", oracle_id);
+            sp_io::offchain::local_storage_set(
+                sp_core::offchain::StorageKind::PERSISTENT,
+                key.as_bytes(),
+                &diagnostic.encode(),
+            );
+        }
+
+        /// Read the latest off-chain failure recorded for an oracle (off-chain only)
+        pub fn last_ml_diagnostic(oracle_id: u8) -> Option<MLDiagnostic> {
+            let key = format!("ml_diagnostic_{}", oracle_id);
+            sp_io::offchain::local_storage_get(
+                sp_core::offchain::StorageKind::PERSISTENT,
+                key.as_bytes()
+            ).and_then(|raw| MLDiagnostic::decode(&mut &raw[..]).ok())
+        }
+
         /// Get oracle URL from local storage (off-chain)
         fn get_oracle_url(oracle_id: u8) -> Result<Vec<u8>, &'static str> {
             // Read from off-chain storage
@@ -2896,7 +4065,7 @@ pub mod pallet {
             // Verify quote signature based on TEE type
             match tee_type {
                 1 => Self::verify_sgx_quote_signature(quote, signature)?,
-                2 => Self::verify_sev_quote_signature(quote, signature)?,
+                2 => Self::verify_sev_quote_signature(quote)?,
                 _ => {
                     log::error!("Unknown TEE type: {}", tee_type);
                     return Err("Unknown TEE type");
@@ -2994,48 +4163,8 @@ pub mod pallet {
             
             let cert_data = &cert_chain[6..6 + cert_size];
             
-            // Parse X.509 certificate to extract public key
-            // Simplified: In production, use a proper X.509 parser
-            // For now, we'll look for the public key OID sequence
-            
             // ECDSA P-256 public key is 64 bytes (32 bytes X + 32 bytes Y)
-            // In X.509 DER encoding, it appears after the OID sequence:
-            // 0x06 0x08 0x2A 0x86 0x48 0xCE 0x3D 0x03 0x01 0x07 (OID for P-256)
-            
-            let p256_oid = [0x06, 0x08, 0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x03, 0x01, 0x07];
-            
-            // Find OID in certificate
-            let mut oid_pos = None;
-            for i in 0..cert_data.len().saturating_sub(10) {
-                if &cert_data[i..i + 10] == &p256_oid {
-                    oid_pos = Some(i);
-                    break;
-                }
-            }
-            
-            let oid_idx = oid_pos.ok_or("P-256 OID not found in certificate")?;
-            
-            // Public key typically follows after OID + some DER overhead
-            // Look for 0x03 (BIT STRING) followed by key length
-            let mut pubkey_start = None;
-            for i in oid_idx..cert_data.len().saturating_sub(66) {
-                if cert_data[i] == 0x03 && cert_data[i + 1] == 0x42 {
-                    // 0x42 = 66 bytes (1 unused bits + 1 compression byte + 64 key bytes)
-                    pubkey_start = Some(i + 4); // Skip tag, length, unused bits, compression
-                    break;
-                }
-            }
-            
-            let key_idx = pubkey_start.ok_or("Public key not found in certificate")?;
-            
-            if cert_data.len() < key_idx + 64 {
-                return Err("Certificate too short for public key");
-            }
-            
-            let mut pubkey = [0u8; 64];
-            pubkey.copy_from_slice(&cert_data[key_idx..key_idx + 64]);
-            
-            Ok(pubkey)
+            crate::x509::p256_public_key_from_certificate(cert_data)
         }
 
         /// Verify ECDSA P-256 signature 
@@ -3072,16 +4201,32 @@ pub mod pallet {
             Ok(())
         }
 
-        /// Verify AMD SEV quote signature
-        fn verify_sev_quote_signature(quote: &[u8], signature: &[u8]) -> Result<(), &'static str> {
-            if quote.len() < 672 {
+        /// Verify an AMD SEV-SNP attestation report and the certificate chain behind it
+        /// Layout after the 131-byte quote header: [ATTESTATION_REPORT:0x4A0][VCEK][ASK][ARK],
+        /// certificates in DER. The report's REPORT_DATA and MEASUREMENT must match the header.
+        pub(crate) fn verify_sev_quote_signature(quote: &[u8]) -> Result<(), &'static str> {
+            const HEADER_LEN: usize = 131;
+            const REPORT_LEN: usize = 0x4A0;
+            const SIGNED_LEN: usize = 0x2A0;
+            const SIGNATURE_ALGO_OFFSET: usize = 0x34;
+            const REPORT_DATA_OFFSET: usize = 0x50;
+            const MEASUREMENT_OFFSET: usize = 0x90;
+            const MEASUREMENT_LEN: usize = 48;
+            /// R and S are each a 72-byte little-endian field
+            const SIGNATURE_COMPONENT_LEN: usize = 72;
+            const P384_SCALAR_LEN: usize = 48;
+            
+            if quote.len() < HEADER_LEN + REPORT_LEN {
                 return Err("AMD SEV quote too short");
             }
             
-            let report_body = &quote[..672];
+            let (report, cert_chain) = quote[HEADER_LEN..].split_at(REPORT_LEN);
             
             let sig_algo = u32::from_le_bytes([
-                quote[56], quote[57], quote[58], quote[59]
+                report[SIGNATURE_ALGO_OFFSET],
+                report[SIGNATURE_ALGO_OFFSET + 1],
+                report[SIGNATURE_ALGO_OFFSET + 2],
+                report[SIGNATURE_ALGO_OFFSET + 3],
             ]);
             
             // 1 = ECDSA P-384 with SHA-384
@@ -3089,62 +4234,122 @@ pub mod pallet {
                 return Err("Unsupported AMD signature algorithm");
             }
             
-            if signature.len() < 96 {
-                return Err("Invalid AMD signature length");
+            // Bind the signed report to the commitment and measurement the header claims
+            if report[REPORT_DATA_OFFSET..REPORT_DATA_OFFSET + 32] != quote[99..131] {
+                return Err("AMD SEV report data mismatch");
+            }
+            let measurement = &report[MEASUREMENT_OFFSET..MEASUREMENT_OFFSET + MEASUREMENT_LEN];
+            if sp_io::hashing::blake2_256(measurement)[..] != quote[67..99] {
+                return Err("AMD SEV measurement mismatch");
             }
             
-            // AMD uses DER-encoded signature, extract R and S (48 bytes each for P-384)
-            let raw_sig = &signature[..96];
+            let vcek = Self::verify_amd_cert_chain(cert_chain)?;
             
-            // Get AMD root public key (96 bytes for P-384: X || Y, 48 bytes each)
-            let vcek_hash = sp_io::hashing::blake2_256(&quote[672..]);
-            let amd_pubkey = AMDRootKeys::<T>::get(&vcek_hash)
-                .ok_or("AMD root key not trusted")?;
+            // Convert R and S to big-endian; anything above the low 48 bytes must be zero
+            let mut raw_sig = [0u8; 2 * P384_SCALAR_LEN];
+            let components = report[SIGNED_LEN..SIGNED_LEN + 2 * SIGNATURE_COMPONENT_LEN]
+                .chunks_exact(SIGNATURE_COMPONENT_LEN);
+            for (component, scalar) in components.zip(raw_sig.chunks_exact_mut(P384_SCALAR_LEN)) {
+                let (low, high) = component.split_at(P384_SCALAR_LEN);
+                if high.iter().any(|byte| *byte != 0) {
+                    return Err("Invalid P-384 signature format");
+                }
+                scalar.iter_mut().zip(low.iter().rev()).for_each(|(out, byte)| *out = *byte);
+            }
             
             // Construct P-384 verifying key
+            // Format: 0x04 || X (48 bytes) || Y (48 bytes)
             let mut uncompressed = [0u8; 97];
             uncompressed[0] = 0x04; // Uncompressed point
-            // amd_pubkey stored as [u8; 64] in storage
-            // store as [u8; 96] for P-384
-            uncompressed[1..49].copy_from_slice(&amd_pubkey[0..48]); // X
-            uncompressed[49..97].copy_from_slice(&amd_pubkey[48..96]); // Y
-            // SHA-384 hash of report body
-            let _report_hash = sp_io::hashing::sha2_256(report_body); // Use sha2_384 in production
-            
-            // Parse P-384 signature
-            let _sig = P384Signature::from_slice(raw_sig)
+            uncompressed[1..97].copy_from_slice(&vcek);
+            
+            let verifying_key = P384VerifyingKey::from_sec1_bytes(&uncompressed)
+                .map_err(|_| "Invalid P-384 public key")?;
+            
+            let sig = P384Signature::from_slice(&raw_sig)
                 .map_err(|_| "Invalid P-384 signature format")?;
             
-            // For now, simplified check
-            let sig_valid = raw_sig.iter().any(|&b| b != 0);
+            // Hashes the signed part of the report with SHA-384 before verifying
+            verifying_key.verify(&report[..SIGNED_LEN], &sig)
+                .map_err(|_| "AMD SEV signature verification failed")?;
+            
+            log::info!("AMD SEV P-384 signature verified");
+            Ok(())
+        }
+
+        /// Walk the [VCEK][ASK][ARK] chain: the ARK must be a trusted AMD root key, the ASK
+        /// signed by the ARK and the VCEK by the ASK. Returns the VCEK's P-384 key (X || Y).
+        fn verify_amd_cert_chain(cert_chain: &[u8]) -> Result<[u8; 96], &'static str> {
+            let (vcek, rest) = crate::x509::Certificate::parse(cert_chain)?;
+            let (ask, rest) = crate::x509::Certificate::parse(rest)?;
+            let (ark, _) = crate::x509::Certificate::parse(rest)?;
             
-            if !sig_valid {
-                return Err("Invalid AMD signature");
+            // Only roots registered through add_amd_root_key are trusted
+            let (ark_modulus, ark_exponent) = ark.rsa_public_key()?;
+            let trusted_modulus = AMDRootKeys::<T>::get(sp_io::hashing::blake2_256(ark_modulus))
+                .ok_or("AMD root key not trusted")?;
+            if trusted_modulus[..] != *ark_modulus {
+                return Err("Untrusted AMD root key");
             }
             
-            log::info!("AMD SEV P-384 signature verified (simplified)");
-            Ok(())
+            Self::verify_amd_certificate_signature(ark_modulus, ark_exponent, &ask)?;
+            let (ask_modulus, ask_exponent) = ask.rsa_public_key()?;
+            Self::verify_amd_certificate_signature(ask_modulus, ask_exponent, &vcek)?;
+            
+            vcek.p384_public_key()
         }
 
-        /// Verify ML service response signature
-        fn verify_ml_response_signature(response: &SignedMLResponse) -> Result<(), &'static str> {
+        /// Verify an RSASSA-PSS / SHA-384 certificate signature, as AMD's key distribution
+        /// service issues them (salt length 48)
+        fn verify_amd_certificate_signature(
+            modulus: &[u8],
+            exponent: &[u8],
+            certificate: &crate::x509::Certificate,
+        ) -> Result<(), &'static str> {
+            if certificate.signature_algorithm != crate::x509::OID_RSASSA_PSS {
+                return Err("AMD certificate is not signed with RSASSA-PSS");
+            }
+            
+            let public_key = RsaPublicKey::new(
+                rsa::BigUint::from_bytes_be(modulus),
+                rsa::BigUint::from_bytes_be(exponent),
+            )
+            .map_err(|_| "Invalid AMD RSA public key")?;
+            let verifying_key = RsaPssVerifyingKey::<sha2::Sha384>::new_with_salt_len(public_key, 48);
+            
+            let signature = RsaPssSignature::try_from(certificate.signature)
+                .map_err(|_| "Invalid AMD certificate signature format")?;
+            verifying_key.verify(certificate.tbs, &signature)
+                .map_err(|_| "AMD certificate signature verification failed")
+        }
+
+        /// Verify ML service response signature against on-chain time
+        pub fn verify_ml_response_signature(response: &SignedMLResponse) -> Result<(), Error<T>> {
+            let now = <T as Config>::TimeProvider::now().saturated_into::<u64>();
+            Self::verify_ml_response_signature_at(response, now)
+        }
+
+        /// Verify ML service response signature, checking freshness against `now` (seconds)
+        ///
+        /// The offchain worker passes the node's clock: the oracle signs its own
+        /// wall-clock time and the response never reaches chain state unverified.
+        fn verify_ml_response_signature_at(response: &SignedMLResponse, now: u64) -> Result<(), Error<T>> {
             // Check if key is trusted
             if !TrustedMLKeys::<T>::get(&response.service_public_key) {
                 log::error!("ML service key not trusted");
-                return Err("ML service key not trusted");
+                return Err(Error::<T>::MLServiceKeyNotTrusted);
             }
             
             // Check nonce not used (prevents replay)
             if MLNonces::<T>::get(response.nonce) {
                 log::error!("ML nonce already used: {}", response.nonce);
-                return Err("Nonce already used");
+                return Err(Error::<T>::MLNonceAlreadyUsed);
             }
             
-            // Check response freshness (within 60 seconds)
-            let now = sp_io::offchain::timestamp().unix_millis() / 1000;
-            if now.saturating_sub(response.timestamp) > 60 {
+            // Check response freshness
+            if now.saturating_sub(response.timestamp) > T::MLResponseValidity::get() {
                 log::error!("ML response expired");
-                return Err("Response expired");
+                return Err(Error::<T>::MLResponseExpired);
             }
             
             // Build message for verification
@@ -3161,7 +4366,7 @@ pub mod pallet {
                 Ok(pk) => pk,
                 Err(_) => {
                     log::error!("Invalid ML service public key format");
-                    return Err("Invalid public key");
+                    return Err(Error::<T>::InvalidMLSignature);
                 }
             };
             
@@ -3169,13 +4374,13 @@ pub mod pallet {
                 Ok(sig) => sig,
                 Err(_) => {
                     log::error!("Invalid ML signature format");
-                    return Err("Invalid signature");
+                    return Err(Error::<T>::InvalidMLSignature);
                 }
             };
             
             if !sp_io::crypto::ed25519_verify(&signature, &message_hash, &public_key) {
                 log::error!("ML signature verification failed");
-                return Err("Signature verification failed");
+                return Err(Error::<T>::InvalidMLSignature);
             }
             
             log::info!("ML response signature verified");
@@ -3183,7 +4388,7 @@ pub mod pallet {
         }
         
         /// Build JSON payload for ML service
-        fn build_ml_request_payload(features: &BehavioralFeatures) -> Result<Vec<u8>, &'static str> {
+        pub(crate) fn build_ml_request_payload(features: &BehavioralFeatures) -> Result<Vec<u8>, &'static str> {
             // Encode features to JSON manually (no_std compatible)
             let mut json = Vec::new();
             
@@ -3341,13 +4546,10 @@ pub mod pallet {
             }
             
             // Calculate what percentile this score falls into
-            let total_scores: u32 = distribution.iter().sum();
-            if total_scores == 0 {
-                return AnomalyType::Normal;
-            }
-            
-            let scores_below: u32 = distribution[..score as usize].iter().sum();
-            let percentile = (scores_below * 100) / total_scores;
+            let percentile = match Self::percentile_rank(&distribution, score) {
+                Some(percentile) => percentile,
+                None => return AnomalyType::Normal,
+            };
             
             // Flag scores in extreme percentiles (< 1% or > 99%)
             if percentile < 1 || percentile > 99 {
@@ -3359,8 +4561,19 @@ pub mod pallet {
             AnomalyType::Normal
         }
 
+        /// Percentage of recorded scores strictly below `score` (None if the distribution is empty)
+        fn percentile_rank(distribution: &[u32], score: u8) -> Option<u32> {
+            let total_scores: u64 = distribution.iter().map(|count| *count as u64).sum();
+            if total_scores == 0 {
+                return None;
+            }
+            
+            let scores_below: u64 = distribution.iter().take(score as usize).map(|count| *count as u64).sum();
+            Some((scores_below * 100 / total_scores) as u32)
+        }
+
         /// Update score statistics using Welford's online algorithm
-        fn update_score_statistics(did: &H256, new_score: u8, now: u64) -> DispatchResult {
+        pub(crate) fn update_score_statistics(did: &H256, new_score: u8, now: u64) -> DispatchResult {
             ScoreStatistics::<T>::try_mutate(did, |stats_opt| -> DispatchResult {
                 match stats_opt {
                     None => {
@@ -3376,30 +4589,35 @@ pub mod pallet {
                         });
                     },
                     Some(stats) => {
-                        let n = stats.samples;
+                        // Past the cap each new score keeps a fixed 1/(cap+1) weight
+                        let n = stats.samples.min(T::MaxScoreStatSamples::get().max(1));
                         let old_mean = stats.mean;
                         
-                        // Update mean
-                        stats.mean = (old_mean * n + (new_score as u32 * 100)) / (n + 1);
+                        // Update mean (u64 intermediates so large sample counts can't wrap)
+                        let mean = (old_mean as u64)
+                            .saturating_mul(n as u64)
+                            .saturating_add(new_score as u64 * 100)
+                            / (n as u64 + 1);
+                        stats.mean = mean.saturated_into::<u32>();
                         
                         // Update std dev (simplified incremental calculation)
                         if n > 1 {
-                            let delta = ((new_score as i32) * 100) - (old_mean as i32);
-                            let delta2 = ((new_score as i32) * 100) - (stats.mean as i32);
+                            let delta = ((new_score as i64) * 100) - (old_mean as i64);
+                            let delta2 = ((new_score as i64) * 100) - (stats.mean as i64);
                             
                             // M2 = M2 + delta * delta2
                             let m2_update = (delta * delta2) / 100;
-                            let variance = (stats.std_dev as i32 * stats.std_dev as i32) / 100;
-                            let new_variance = ((variance * n as i32) + m2_update) / (n as i32 + 1);
+                            let variance = (stats.std_dev as i64 * stats.std_dev as i64) / 100;
+                            let new_variance = (variance.saturating_mul(n as i64) + m2_update) / (n as i64 + 1);
                             
-                            stats.std_dev = Self::integer_sqrt(new_variance.max(0) as u32);
+                            stats.std_dev = Self::integer_sqrt(new_variance.clamp(0, u32::MAX as i64) as u32);
                         }
                         
                         // Update min/max
                         stats.min = stats.min.min(new_score);
                         stats.max = stats.max.max(new_score);
                         
-                        stats.samples = n + 1;
+                        stats.samples = stats.samples.saturating_add(1);
                         stats.last_score = new_score;
                         stats.last_timestamp = now;
                         
@@ -3498,30 +4716,11 @@ pub mod pallet {
                         let n = envelope.samples_count;
                         let n_plus_1 = n + 1;
                         
-                        // Update typing speed mean and variance (Welford's algorithm)
-                        let old_mean_typing = envelope.mean_typing_speed;
+                        // Update means incrementally
                         envelope.mean_typing_speed = 
-                            ((old_mean_typing as u64 * n as u64 + new_features.typing_speed_wpm as u64) 
+                            ((envelope.mean_typing_speed as u64 * n as u64 + new_features.typing_speed_wpm as u64) 
                             / n_plus_1 as u64) as u32;
                         
-                        // Update variance incrementally
-                        if n > 1 {
-                            let _delta = new_features.typing_speed_wpm as i64 - old_mean_typing as i64;
-                            let _delta2 = new_features.typing_speed_wpm as i64 - envelope.mean_typing_speed as i64;
-                            
-                            // M2 = M2 + delta * delta2
-                            // variance = M2 / (n - 1)
-                            // std_dev = sqrt(variance)
-                            // For fixed-point: store std_dev * 100
-                            
-                            envelope.std_dev_typing_speed = Self::calculate_std_dev_from_samples(
-                                did, 
-                                envelope.mean_typing_speed,
-                                0 // feature index for typing speed
-                            )?;
-                        }
-                        
-                        // Similar updates for other features
                         envelope.mean_key_hold_time = 
                             ((envelope.mean_key_hold_time as u64 * n as u64 + new_features.avg_key_hold_time_ms as u64) 
                             / n_plus_1 as u64) as u32;
@@ -3534,18 +4733,33 @@ pub mod pallet {
                             ((envelope.mean_error_rate as u32 * n + new_features.error_rate_percent as u32) 
                             / n_plus_1) as u8;
                         
-                        // Update 2-sigma bounds
-                        let std_typing = envelope.std_dev_typing_speed / 100; // Convert from fixed-point
-                        envelope.min_typing_speed = envelope.mean_typing_speed.saturating_sub(2 * std_typing);
-                        envelope.max_typing_speed = envelope.mean_typing_speed.saturating_add(2 * std_typing);
-                        
-                        let std_hold = envelope.std_dev_key_hold_time / 100;
-                        envelope.min_key_hold_time = envelope.mean_key_hold_time.saturating_sub(2 * std_hold);
-                        envelope.max_key_hold_time = envelope.mean_key_hold_time.saturating_add(2 * std_hold);
+                        // Recompute every std dev over the stored samples (fixed-point: value * 100)
+                        if n > 1 {
+                            envelope.std_dev_typing_speed = Self::calculate_std_dev_from_samples(
+                                did,
+                                envelope.mean_typing_speed,
+                                0,
+                            )?;
+                            envelope.std_dev_key_hold_time = Self::calculate_std_dev_from_samples(
+                                did,
+                                envelope.mean_key_hold_time,
+                                1,
+                            )?;
+                            envelope.std_dev_transition_time = Self::calculate_std_dev_from_samples(
+                                did,
+                                envelope.mean_transition_time,
+                                2,
+                            )?;
+                            envelope.std_dev_error_rate = Self::calculate_std_dev_from_samples(
+                                did,
+                                envelope.mean_error_rate as u32,
+                                3,
+                            )?
+                            .saturated_into::<u16>();
+                        }
                         
-                        let std_transition = envelope.std_dev_transition_time / 100;
-                        envelope.min_transition_time = envelope.mean_transition_time.saturating_sub(2 * std_transition);
-                        envelope.max_transition_time = envelope.mean_transition_time.saturating_add(2 * std_transition);
+                        // Update 2-sigma bounds
+                        Self::apply_two_sigma_bounds(envelope);
                         
                         envelope.samples_count = n_plus_1;
                         envelope.last_updated = now;
@@ -3561,6 +4775,68 @@ pub mod pallet {
             })
         }
         
+        /// Rebuild a DID's envelope from scratch over its stored samples,
+        /// correcting rounding drift accumulated by incremental updates
+        pub fn recompute_envelope(did: &H256) -> Result<(), Error<T>> {
+            let samples = BehavioralPatternSamples::<T>::get(did);
+            ensure!(!samples.is_empty(), Error::<T>::PatternNotFound);
+            
+            let count = samples.len() as u64;
+            let mean_of = |value: fn(&BehavioralFeatures) -> u32| -> u32 {
+                (samples.iter().map(|sample| value(&sample.features) as u64).sum::<u64>() / count) as u32
+            };
+            
+            let mean_typing_speed = mean_of(|f| f.typing_speed_wpm);
+            let mean_key_hold_time = mean_of(|f| f.avg_key_hold_time_ms);
+            let mean_transition_time = mean_of(|f| f.avg_transition_time_ms);
+            let mean_error_rate = mean_of(|f| f.error_rate_percent as u32);
+            
+            let mut envelope = BehavioralEnvelope {
+                mean_typing_speed,
+                mean_key_hold_time,
+                mean_transition_time,
+                mean_error_rate: mean_error_rate as u8,
+                std_dev_typing_speed: Self::calculate_std_dev_from_samples(did, mean_typing_speed, 0)?,
+                std_dev_key_hold_time: Self::calculate_std_dev_from_samples(did, mean_key_hold_time, 1)?,
+                std_dev_transition_time: Self::calculate_std_dev_from_samples(did, mean_transition_time, 2)?,
+                std_dev_error_rate: Self::calculate_std_dev_from_samples(did, mean_error_rate, 3)?
+                    .saturated_into::<u16>(),
+                min_typing_speed: 0,
+                max_typing_speed: 0,
+                min_key_hold_time: 0,
+                max_key_hold_time: 0,
+                min_transition_time: 0,
+                max_transition_time: 0,
+                samples_count: samples.len() as u32,
+                last_updated: <T as Config>::TimeProvider::now().saturated_into::<u64>(),
+            };
+            Self::apply_two_sigma_bounds(&mut envelope);
+            
+            BehavioralEnvelopes::<T>::insert(did, envelope.clone());
+            
+            Self::deposit_event(Event::EnvelopeUpdated {
+                did: *did,
+                samples_count: envelope.samples_count,
+            });
+            
+            Ok(())
+        }
+        
+        /// Set min/max bounds to mean +/- 2 standard deviations (std devs are fixed-point * 100)
+        fn apply_two_sigma_bounds(envelope: &mut BehavioralEnvelope) {
+            let std_typing = envelope.std_dev_typing_speed / 100;
+            envelope.min_typing_speed = envelope.mean_typing_speed.saturating_sub(2 * std_typing);
+            envelope.max_typing_speed = envelope.mean_typing_speed.saturating_add(2 * std_typing);
+            
+            let std_hold = envelope.std_dev_key_hold_time / 100;
+            envelope.min_key_hold_time = envelope.mean_key_hold_time.saturating_sub(2 * std_hold);
+            envelope.max_key_hold_time = envelope.mean_key_hold_time.saturating_add(2 * std_hold);
+            
+            let std_transition = envelope.std_dev_transition_time / 100;
+            envelope.min_transition_time = envelope.mean_transition_time.saturating_sub(2 * std_transition);
+            envelope.max_transition_time = envelope.mean_transition_time.saturating_add(2 * std_transition);
+        }
+        
         /// Calculate standard deviation from stored samples
         fn calculate_std_dev_from_samples(
             did: &H256,
@@ -3579,6 +4855,7 @@ pub mod pallet {
                     0 => sample.features.typing_speed_wpm,
                     1 => sample.features.avg_key_hold_time_ms,
                     2 => sample.features.avg_transition_time_ms,
+                    3 => sample.features.error_rate_percent as u32,
                     _ => return Ok(1000),
                 };
                 
@@ -3703,12 +4980,16 @@ pub mod pallet {
             let newest = &recent_samples[recent_samples.len() - 1].features;
             
             let typing_trend = newest.typing_speed_wpm as i64 - oldest.typing_speed_wpm as i64;
-            let predicted_typing = newest.typing_speed_wpm as i64 + typing_trend / 2;
+            // A steep downward trend can't predict below 0 WPM
+            let predicted_typing = (newest.typing_speed_wpm as i64 + typing_trend / 2).max(0);
             
             let actual_diff = (new_features.typing_speed_wpm as i64 - predicted_typing).abs();
             
-            // Allow 20% deviation from trend
-            actual_diff < (predicted_typing.abs() / 5)
+            // Allow the configured percentage of deviation from trend, with an absolute
+            // floor so a zero or near-zero prediction doesn't reject every change
+            let tolerance = (predicted_typing * T::TrendDeviationTolerancePercent::get() as i64 / 100)
+                .max(MIN_TREND_TOLERANCE_WPM);
+            actual_diff < tolerance
         }
 
         /// Calculate weighted distance between two behavioral feature sets
@@ -3768,105 +5049,657 @@ pub mod pallet {
             }
         }
 
-        /// Normalize typing speed difference to 0-100 scale
-        /// ±10 WPM = 90% similarity (distance of 10)
-        fn normalize_typing_speed_diff(current: u32, stored: u32) -> u32 {
-            let diff = Self::absolute_diff(current, stored);
-            (diff * 10).min(100)  // 10 WPM = 100 distance points
-        }
-        
-        /// Normalize time differences (ms)
-        fn normalize_time_diff(current: u32, stored: u32, max_expected: u32) -> u32 {
-            let diff = Self::absolute_diff(current, stored);
-            ((diff * 100) / max_expected).min(100)
-        }
-        
-        /// Calculate absolute difference (safe subtraction)
-        fn absolute_diff(a: u32, b: u32) -> u32 {
-            if a > b {
-                a.saturating_sub(b)
-            } else {
-                b.saturating_sub(a)
-            }
-        }
-        
-        /// Integer square root using Newton's method
-        fn integer_sqrt(n: u32) -> u32 {
-            if n < 2 {
-                return n;
-            }
-            let mut x = n;
-            let mut y = (x + 1) / 2;
-            while y < x {
-                x = y;
-                y = (x + n / x) / 2;
+        /// Normalize typing speed difference to 0-100 scale
+        /// ±10 WPM = 90% similarity (distance of 10)
+        fn normalize_typing_speed_diff(current: u32, stored: u32) -> u32 {
+            let diff = Self::absolute_diff(current, stored);
+            (diff * 10).min(100)  // 10 WPM = 100 distance points
+        }
+        
+        /// Normalize time differences (ms)
+        fn normalize_time_diff(current: u32, stored: u32, max_expected: u32) -> u32 {
+            let diff = Self::absolute_diff(current, stored);
+            ((diff * 100) / max_expected).min(100)
+        }
+        
+        /// Calculate absolute difference (safe subtraction)
+        fn absolute_diff(a: u32, b: u32) -> u32 {
+            if a > b {
+                a.saturating_sub(b)
+            } else {
+                b.saturating_sub(a)
+            }
+        }
+        
+        /// Integer square root using Newton's method
+        fn integer_sqrt(n: u32) -> u32 {
+            if n < 2 {
+                return n;
+            }
+            let mut x = n;
+            let mut y = (x + 1) / 2;
+            while y < x {
+                x = y;
+                y = (x + n / x) / 2;
+            }
+            x
+        }
+
+        /// Calculate match confidence (0-100)
+        pub fn calculate_match_confidence(
+            distance: u32,
+            _envelope: Option<&BehavioralEnvelope>,
+            samples_available: u32,
+        ) -> u8 {
+            // Convert distance to similarity (inverse relationship)
+            // Distance of 0 = 100% similarity, zero-confidence distance = 0% similarity
+            let zero_distance = T::BehavioralZeroConfidenceDistance::get().max(1);
+            let base_similarity = if distance >= zero_distance {
+                0
+            } else {
+                100 - distance.saturating_mul(100) / zero_distance
+            };
+            
+            // Confidence boost based on number of samples
+            let tiers = T::BehavioralSampleConfidenceTiers::get();
+            let sample_confidence = match samples_available {
+                0..=1 => tiers[0],   // Low confidence with 1 sample
+                2..=3 => tiers[1],   // Medium confidence
+                4..=5 => tiers[2],   // Good confidence
+                6..=10 => tiers[3],  // High confidence
+                _ => tiers[4],       // Maximum confidence
+            };
+            
+            // Combined confidence: weighted average of similarity and samples
+            let similarity_weight = T::BehavioralSimilarityWeight::get().min(100) as u32;
+            let confidence = ((base_similarity * similarity_weight)
+                + (sample_confidence as u32 * (100 - similarity_weight))) / 100;
+            
+            confidence.min(100) as u8
+        }
+        
+        /// Check if pattern falls within 2-sigma envelope
+        pub fn is_within_envelope(
+            features: &BehavioralFeatures,
+            envelope: &BehavioralEnvelope,
+        ) -> (bool, Vec<u8>) {
+            let mut violations = Vec::new();
+            
+            // Check typing speed
+            if features.typing_speed_wpm < envelope.min_typing_speed 
+                || features.typing_speed_wpm > envelope.max_typing_speed {
+                violations.push(0); // Feature index 0
+            }
+            
+            // Check key hold time
+            if features.avg_key_hold_time_ms < envelope.min_key_hold_time 
+                || features.avg_key_hold_time_ms > envelope.max_key_hold_time {
+                violations.push(1); // Feature index 1
+            }
+            
+            // Check transition time
+            if features.avg_transition_time_ms < envelope.min_transition_time 
+                || features.avg_transition_time_ms > envelope.max_transition_time {
+                violations.push(2); // Feature index 2
+            }
+            
+            let is_within = violations.is_empty();
+            (is_within, violations)
+        }
+
+        /// Check if nullifier is part of any personhood
+        pub fn get_personhood_for_nullifier(nullifier: &H256) -> Option<H256> {
+            BiometricBindings::<T>::get(nullifier)
+        }
+        
+        /// Resolve the DID controlled by `account`, using the local cache before the registry
+        pub fn identity_of_account(
+            account: &T::AccountId,
+        ) -> Option<(H256, pallet_identity_registry::pallet::Identity<T>)> {
+            if let Some(did) = ControllerToDid::<T>::get(account) {
+                if let Some(identity) = Identities::<T>::get(&did) {
+                    if identity.controller == *account {
+                        return Some((did, identity));
+                    }
+                }
+            }
+            pallet_identity_registry::pallet::Pallet::<T>::get_identity_by_account(account)
+        }
+        
+        /// Resolve the DID controlled by `account`, requiring the identity to be active
+        pub fn active_did_of(account: &T::AccountId) -> Result<H256, Error<T>> {
+            let (did, identity) = Self::identity_of_account(account)
+                .ok_or(Error::<T>::DidNotFound)?;
+            ensure!(identity.active, Error::<T>::NotAuthorized);
+            Ok(did)
+        }
+        
+        /// Ensure `did` exists, is active and is controlled by `account`
+        pub fn ensure_active_controller(did: &H256, account: &T::AccountId) -> Result<(), Error<T>> {
+            let identity = Identities::<T>::get(did)
+                .ok_or(Error::<T>::DidNotFound)?;
+            ensure!(identity.controller == *account, Error::<T>::NotAuthorized);
+            ensure!(identity.active, Error::<T>::NotAuthorized);
+            Ok(())
+        }
+        
+        /// Decode a user-supplied stake (all bytes must be consumed) and check it is affordable
+        pub fn decode_and_validate_stake(
+            who: &T::AccountId,
+            data: &[u8],
+        ) -> Result<BalanceOf<T>, Error<T>> {
+            let amount = BalanceOf::<T>::decode_all(&mut &data[..])
+                .map_err(|_| Error::<T>::InvalidStakeEncoding)?;
+            Self::ensure_stake_affordable(who, amount)?;
+            Ok(amount)
+        }
+        
+        /// Ensure `amount` does not exceed the caller's free balance
+        pub fn ensure_stake_affordable(who: &T::AccountId, amount: BalanceOf<T>) -> Result<(), Error<T>> {
+            ensure!(
+                T::Currency::free_balance(who) >= amount,
+                Error::<T>::InsufficientStakeBalance
+            );
+            Ok(())
+        }
+        
+        /// Everything stored about a DID's behavior, or None if nothing is
+        fn export_behavioral_data(did: &H256) -> Option<BehavioralExport> {
+            let export = BehavioralExport {
+                samples: BehavioralPatternSamples::<T>::get(did).into_inner(),
+                envelope: BehavioralEnvelopes::<T>::get(did),
+                score_statistics: ScoreStatistics::<T>::get(did),
+            };
+            
+            if export.samples.is_empty() && export.envelope.is_none() && export.score_statistics.is_none() {
+                None
+            } else {
+                Some(export)
+            }
+        }
+        
+        /// Export for `requester`, who must be the DID's active controller, on a request
+        /// signed to expire at `valid_until` (chain time) within `MAX_BEHAVIORAL_EXPORT_VALIDITY`
+        pub fn export_behavioral_data_for(
+            did: &H256,
+            requester: &T::AccountId,
+            valid_until: u64,
+        ) -> Result<Option<BehavioralExport>, Error<T>> {
+            let now = <T as Config>::TimeProvider::now().saturated_into::<u64>();
+            ensure!(
+                valid_until >= now && valid_until <= now.saturating_add(MAX_BEHAVIORAL_EXPORT_VALIDITY),
+                Error::<T>::BehavioralExportExpired
+            );
+            Self::ensure_active_controller(did, requester)?;
+            Ok(Self::export_behavioral_data(did))
+        }
+        
+        /// Message a controller signs to request its behavioral export
+        /// `nonce` is chosen by the requester so no two requests share a signature
+        pub fn behavioral_export_payload(did: &H256, valid_until: u64, nonce: u64) -> Vec<u8> {
+            (b"pop:behavioral-export", did, valid_until, nonce).encode()
+        }
+        
+        /// Single health gauge over oracles, consensus outcomes and the scoring backlog
+        pub fn ml_subsystem_health() -> MlHealthReport {
+            let (active_oracles, reputation_sum) = ActiveOracleIds::<T>::get()
+                .into_iter()
+                .filter_map(|oracle_id| MLOracles::<T>::get(oracle_id))
+                .fold((0u32, 0u32), |(count, sum), oracle| {
+                    (count.saturating_add(1), sum.saturating_add(oracle.reputation as u32))
+                });
+            let average_reputation = if active_oracles > 0 {
+                (reputation_sum / active_oracles) as u8
+            } else {
+                0
+            };
+            
+            let (consensus_successes, consensus_failures) = Self::consensus_outcomes();
+            let rounds = consensus_successes as u64 + consensus_failures as u64;
+            let consensus_success_rate = if rounds > 0 {
+                (consensus_successes as u64 * 100 / rounds) as u8
+            } else {
+                0
+            };
+            
+            MlHealthReport {
+                active_oracles,
+                average_reputation,
+                consensus_successes,
+                consensus_failures,
+                consensus_success_rate,
+                pending_patterns: PendingMLPatterns::<T>::count(),
+            }
+        }
+        
+        /// p1 / median / p99 of the global score distribution (all zero while it is empty)
+        pub fn score_percentiles() -> ScorePercentiles {
+            let distribution = GlobalScoreDistribution::<T>::get();
+            let samples = distribution.iter().fold(0u32, |sum, count| sum.saturating_add(*count));
+            
+            // Highest score whose rank is still below `percentile`
+            let at = |percentile: u32| -> u8 {
+                (1..=100u8)
+                    .take_while(|score| {
+                        Self::percentile_rank(&distribution, *score).map_or(false, |rank| rank < percentile)
+                    })
+                    .last()
+                    .unwrap_or(0)
+            };
+            
+            ScorePercentiles {
+                p1: at(1),
+                median: at(50),
+                p99: at(99),
+                samples,
+            }
+        }
+        
+        /// Uniqueness conflicts for each proposed (DID, nullifier) registration
+        pub fn check_registration_conflicts(
+            requests: BoundedVec<(H256, H256), ConstU32<MAX_CONFLICT_CHECKS>>,
+        ) -> Vec<RegistrationConflict> {
+            let now = <T as Config>::TimeProvider::now().saturated_into::<u64>();
+            
+            requests
+                .into_iter()
+                .map(|(did, nullifier)| {
+                    let cooldown_end = RegistrationCooldown::<T>::get(&nullifier);
+                    RegistrationConflict {
+                        did,
+                        nullifier,
+                        did_registered: DidToNullifier::<T>::contains_key(&did),
+                        nullifier_registered: PersonhoodRegistry::<T>::contains_key(&nullifier),
+                        nullifier_bound: NullifierClaims::<T>::get(&nullifier),
+                        invalid_nullifier: !Self::validate_nullifier(&nullifier),
+                        cooling_down_until: (now <= cooldown_end).then_some(cooldown_end),
+                    }
+                })
+                .collect()
+        }
+        
+        /// How the anomaly detector would classify `score` for `did` at `now` (read-only)
+        pub fn classify_score(did: &H256, score: u8, now: u64) -> AnomalyType {
+            Self::detect_score_anomaly(did, score, now)
+        }
+        
+        /// Guardians registered for a DID with their relationship details
+        pub fn guardians_of(did: &H256) -> Vec<(T::AccountId, GuardianRelationship<T>)> {
+            GuardianRelationships::<T>::iter_prefix(did)
+                .take(MAX_GUARDIANS as usize)
+                .collect()
+        }
+        
+        /// Whether `account` is a registered guardian of `did`
+        pub fn is_guardian(did: &H256, account: &T::AccountId) -> bool {
+            GuardianRelationships::<T>::contains_key(did, account)
+        }
+        
+        /// Every biometric enrolled for a personhood, primary first
+        pub fn get_bound_modalities(did: &H256) -> Vec<(H256, BiometricModality)> {
+            PersonhoodBindings::<T>::get(did)
+                .map(|binding| {
+                    core::iter::once((binding.primary_nullifier, binding.primary_modality))
+                        .chain(binding.bound_nullifiers.into_iter())
+                        .collect()
+                })
+                .unwrap_or_default()
+        }
+        
+        /// Total bond a guardian has reserved across every DID it guards
+        pub fn total_guardian_bond(guardian: &T::AccountId) -> BalanceOf<T> {
+            GuardianBondTotals::<T>::get(guardian)
+        }
+        
+        /// Smallest bond a guardian may hold
+        pub fn min_guardian_bond() -> BalanceOf<T> {
+            T::MinGuardianBond::get()
+        }
+        
+        /// A guardian's bond stays locked while an outcome it took part in is undecided:
+        /// an approval or vote in a recovery of `did`, or a vote on an open fraud challenge
+        fn ensure_guardian_bond_unlocked(did: &H256, guardian: &T::AccountId) -> DispatchResult {
+            ensure!(
+                !GuardianApprovals::<T>::get(did).contains(guardian),
+                Error::<T>::RecoveryInProgress
+            );
+            if let Some(recovery) = ProgressiveRecoveries::<T>::get(did) {
+                ensure!(
+                    !recovery.guardian_votes.iter().any(|(voter, _)| voter == guardian),
+                    Error::<T>::RecoveryInProgress
+                );
+            }
+
+            ensure!(
+                Self::prune_voter_challenges(guardian)? == 0,
+                Error::<T>::GuardianVoteOnOpenChallenge
+            );
+
+            Ok(())
+        }
+
+        /// Drop `voter`'s entries for settled challenges and count the open ones, visiting
+        /// at most `MAX_OPEN_CHALLENGE_VOTES + 1` entries
+        fn prune_voter_challenges(voter: &T::AccountId) -> Result<u32, Error<T>> {
+            let entries: Vec<H256> = VoterChallenges::<T>::iter_key_prefix(voter)
+                .take(MAX_OPEN_CHALLENGE_VOTES as usize + 1)
+                .collect();
+            let mut open = 0u32;
+            for challenge_id in entries.iter() {
+                let still_open = FraudChallenges::<T>::get(challenge_id).map_or(false, |challenge| {
+                    challenge.status == ChallengeStatus::Pending ||
+                    challenge.status == ChallengeStatus::UnderReview
+                });
+                if still_open {
+                    open = open.saturating_add(1);
+                } else {
+                    VoterChallenges::<T>::remove(voter, challenge_id);
+                }
+            }
+            // Only reachable for votes indexed before the cap existed
+            ensure!(
+                entries.len() <= MAX_OPEN_CHALLENGE_VOTES as usize,
+                Error::<T>::TooManyOpenChallengeVotes
+            );
+            Ok(open)
+        }
+
+        /// Check that reserving `additional` keeps the guardian under `MaxGuardianBondPerAccount`
+        fn ensure_guardian_bond_within_cap(
+            guardian: &T::AccountId,
+            additional: BalanceOf<T>,
+        ) -> Result<(), Error<T>> {
+            if let Some(cap) = T::MaxGuardianBondPerAccount::get() {
+                ensure!(
+                    GuardianBondTotals::<T>::get(guardian).saturating_add(additional) <= cap,
+                    Error::<T>::GuardianBondCapExceeded
+                );
+            }
+            Ok(())
+        }
+        
+        /// Registration and recovery attempts `who` has made in the current window
+        pub fn registration_attempts(who: &T::AccountId) -> u32 {
+            let now = <T as Config>::TimeProvider::now().saturated_into::<u64>();
+            let (window_start, attempts) = RegistrationAttempts::<T>::get(who);
+            if now >= window_start.saturating_add(T::RegistrationAttemptWindow::get()) {
+                0
+            } else {
+                attempts
+            }
+        }
+        
+        /// Count a registration or recovery attempt, opening a new window if the last one lapsed
+        pub(crate) fn note_registration_attempt(who: &T::AccountId) {
+            let now = <T as Config>::TimeProvider::now().saturated_into::<u64>();
+            RegistrationAttempts::<T>::mutate(who, |(window_start, attempts)| {
+                if now >= window_start.saturating_add(T::RegistrationAttemptWindow::get()) {
+                    *window_start = now;
+                    *attempts = 0;
+                }
+                *attempts = attempts.saturating_add(1);
+            });
+        }
+        
+        /// DIDs with a pending or progressive recovery the guardian can act on
+        pub fn pending_recoveries_for_guardian(guardian: &T::AccountId) -> Vec<H256> {
+            GuardianRecoveries::<T>::iter_key_prefix(guardian).collect()
+        }
+        
+        /// Remove a pending recovery and release its deposit
+        /// The request is taken before unreserving, so a deposit can only ever be returned once
+        fn close_pending_recovery(did: &H256) -> Option<RecoveryRequest<T>> {
+            let request = PendingRecoveries::<T>::take(did)?;
+            GuardianApprovals::<T>::remove(did);
+            T::Currency::unreserve(&request.requester, request.deposit);
+            Self::refresh_guardian_recoveries(did, request.guardians.iter().cloned());
+            Some(request)
+        }
+        
+        /// Drop index entries for guardians no longer named in any open recovery of `did`
+        fn refresh_guardian_recoveries(did: &H256, guardians: impl Iterator<Item = T::AccountId>) {
+            let pending = PendingRecoveries::<T>::get(did);
+            let progressive = ProgressiveRecoveries::<T>::contains_key(did);
+            for guardian in guardians {
+                let named = pending.as_ref().map_or(false, |request| request.guardians.contains(&guardian))
+                    || (progressive && GuardianRelationships::<T>::contains_key(did, &guardian));
+                if !named {
+                    GuardianRecoveries::<T>::remove(&guardian, did);
+                }
+            }
+        }
+        
+        /// Whether enough behavioral samples and an envelope exist to compare patterns
+        pub fn behavioral_baseline_ready(did: &H256) -> bool {
+            BehavioralPatternSamples::<T>::get(did).len() >= 3
+                && BehavioralEnvelopes::<T>::contains_key(did)
+        }
+        
+        /// Configured ML service URL, if set and valid UTF-8
+        pub fn ml_service_url_string() -> Option<String> {
+            let url = MLServiceUrl::<T>::get();
+            if url.is_empty() {
+                return None;
+            }
+            core::str::from_utf8(&url).ok().map(String::from)
+        }
+        
+        /// Basic http(s) URL check: scheme, non-empty host, no whitespace or control characters
+        pub fn is_valid_http_url(url: &[u8]) -> bool {
+            let Ok(url) = core::str::from_utf8(url) else {
+                return false;
+            };
+            let Some(rest) = url.strip_prefix("https://").or_else(|| url.strip_prefix("http://")) else {
+                return false;
+            };
+            let host = rest.split(|c| c == '/' || c == '?' || c == '#').next().unwrap_or("");
+            !host.is_empty()
+                && !host.starts_with(':')
+                && url.chars().all(|c| c.is_ascii_graphic())
+        }
+        
+        /// Lowest oracle ID not yet registered
+        pub fn next_oracle_id() -> Option<u8> {
+            (0..=u8::MAX).find(|id| !MLOracles::<T>::contains_key(id))
+        }
+        
+        /// Commitment binding an oracle score to the exact features it was queried with
+        pub fn features_commitment(features: &BehavioralFeatures) -> H256 {
+            H256::from(sp_io::hashing::blake2_256(&features.encode()))
+        }
+        
+        /// Check whether a cross-biometric session token has already been consumed
+        pub fn is_session_token_used(session_id: &H256) -> bool {
+            UsedSessionTokens::<T>::contains_key(session_id)
+        }
+        
+        /// Validate and bind one additional biometric to `binding`
+        /// Writes the reverse mappings and session token; the caller persists `binding`
+        fn bind_biometric_to(
+            did: &H256,
+            binding: &mut BiometricBinding<T>,
+            new_nullifier: H256,
+            new_modality: BiometricModality,
+            cross_biometric_proof: &CrossBiometricProof,
+            now: u64,
+        ) -> DispatchResult {
+            // Check nullifier not already used
+            ensure!(
+                !NullifierClaims::<T>::get(&new_nullifier),
+                Error::<T>::NullifierAlreadyBound
+            );
+            ensure!(
+                now > RegistrationCooldown::<T>::get(&new_nullifier),
+                Error::<T>::RegistrationTooSoon
+            );
+            
+            // One nullifier per modality, the primary's included
+            ensure!(
+                binding.primary_modality != new_modality
+                    && !binding.bound_nullifiers.iter().any(|(_, modality)| *modality == new_modality),
+                Error::<T>::ModalityAlreadyRegistered
+            );
+            
+            // Check session token not used
+            ensure!(
+                !UsedSessionTokens::<T>::contains_key(&cross_biometric_proof.session_id),
+                Error::<T>::SessionTokenUsed
+            );
+            
+            // Session token valid for the configured window after capture
+            ensure!(
+                now.saturating_sub(cross_biometric_proof.captured_at) < T::SessionValidityWindow::get(),
+                Error::<T>::SessionTokenExpired
+            );
+            
+            // Verify cross-biometric ZK proof
+            // This proves: "I have BOTH biometrics from the SAME capture session"
+            Self::verify_cross_biometric_proof(
+                &binding.primary_nullifier,
+                &new_nullifier,
+                cross_biometric_proof,
+            )?;
+            
+            // Mark session as used
+            UsedSessionTokens::<T>::insert(&cross_biometric_proof.session_id, now);
+            
+            // Add to binding
+            binding.bound_nullifiers.try_push((new_nullifier, new_modality.clone()))
+                .map_err(|_| Error::<T>::MaxBiometricsReached)?;
+            binding.updated_at = now;
+            
+            BiometricBindings::<T>::insert(&new_nullifier, *did);
+            NullifierClaims::<T>::insert(&new_nullifier, true);
+            
+            Self::deposit_event(Event::BiometricBound {
+                did: *did,
+                nullifier: new_nullifier,
+                modality: new_modality,
+            });
+            
+            Ok(())
+        }
+
+        /// Whether `approvals` carry a recovery of `did` by `guardians`
+        ///
+        /// When every listed guardian has an established relationship, approvals are
+        /// weighted by relationship strength and must reach `GuardianStrengthThreshold`
+        /// of the listed total. Otherwise a 2/3 majority by count is required.
+        pub fn guardian_approvals_sufficient(
+            did: &H256,
+            guardians: &[T::AccountId],
+            approvals: &[T::AccountId],
+        ) -> bool {
+            let strengths: Option<Vec<u32>> = guardians
+                .iter()
+                .map(|guardian| {
+                    GuardianRelationships::<T>::get(did, guardian)
+                        .map(|relationship| relationship.relationship_strength as u32)
+                })
+                .collect();
+            
+            match strengths {
+                Some(strengths) if !guardians.is_empty() => {
+                    let total: u32 = strengths.iter().sum();
+                    let approved: u32 = guardians
+                        .iter()
+                        .zip(strengths.iter())
+                        .filter(|(guardian, _)| approvals.contains(guardian))
+                        .map(|(_, strength)| *strength)
+                        .sum();
+                    approved > 0 && approved >= T::GuardianStrengthThreshold::get().mul_ceil(total)
+                },
+                _ => {
+                    let required = (guardians.len() * 2 / 3).saturating_add(1);
+                    approvals.len() >= required
+                },
             }
-            x
         }
 
-        /// Calculate match confidence (0-100)
-        pub fn calculate_match_confidence(
-            distance: u32,
-            _envelope: &BehavioralEnvelope,
-            samples_available: u32,
-        ) -> u8 {
-            // Convert distance to similarity (inverse relationship)
-            // Distance of 0 = 100% similarity, distance of 100 = 0% similarity
-            let base_similarity = if distance >= 100 {
-                0
-            } else {
-                100 - distance
-            };
+        /// Validate a new guardian for `did`, reserve its bond and record the relationship
+        fn add_guardian_to(
+            did: &H256,
+            controller: &T::AccountId,
+            guardian: T::AccountId,
+            relationship_strength: u8,
+            bond_amount: BalanceOf<T>,
+            now: u64,
+        ) -> DispatchResult {
+            // A controller can't vouch for their own recovery
+            ensure!(guardian != *controller, Error::<T>::SelfGuardianNotAllowed);
+            if let Some((guardian_did, _)) = Self::identity_of_account(&guardian) {
+                ensure!(guardian_did != *did, Error::<T>::SelfGuardianNotAllowed);
+            }
             
-            // Confidence boost based on number of samples
-            let sample_confidence = match samples_available {
-                0..=1 => 50,   // Low confidence with 1 sample
-                2..=3 => 70,   // Medium confidence
-                4..=5 => 85,   // Good confidence
-                6..=10 => 95,  // High confidence
-                _ => 100,      // Maximum confidence
-            };
+            // Validate strength (1-10)
+            ensure!(
+                relationship_strength >= 1 && relationship_strength <= 10,
+                Error::<T>::InvalidRelationshipStrength
+            );
             
-            // Combined confidence: weighted average (70% similarity, 30% samples)
-            let confidence = ((base_similarity as u32 * 70) + (sample_confidence as u32 * 30)) / 100;
+            // Ensure guardian doesn't already exist
+            ensure!(
+                !GuardianRelationships::<T>::contains_key(did, &guardian),
+                Error::<T>::GuardianAlreadyExists
+            );
+            ensure!(
+                (GuardianRelationships::<T>::iter_prefix(did).count() as u32) < MAX_GUARDIANS,
+                Error::<T>::TooManyGuardians
+            );
             
-            confidence.min(100) as u8
-        }
-        
-        /// Check if pattern falls within 2-sigma envelope
-        pub fn is_within_envelope(
-            features: &BehavioralFeatures,
-            envelope: &BehavioralEnvelope,
-        ) -> (bool, Vec<u8>) {
-            let mut violations = Vec::new();
+            // Require minimum bond (prevents sybil guardians)
+            ensure!(bond_amount >= Self::min_guardian_bond(), Error::<T>::InsufficientGuardianBond);
             
-            // Check typing speed
-            if features.typing_speed_wpm < envelope.min_typing_speed 
-                || features.typing_speed_wpm > envelope.max_typing_speed {
-                violations.push(0); // Feature index 0
-            }
+            // Reserve bond from guardian
+            Self::ensure_guardian_bond_within_cap(&guardian, bond_amount)?;
+            T::Currency::reserve(&guardian, bond_amount)?;
+            GuardianBondTotals::<T>::mutate(&guardian, |total| *total = total.saturating_add(bond_amount));
             
-            // Check key hold time
-            if features.avg_key_hold_time_ms < envelope.min_key_hold_time 
-                || features.avg_key_hold_time_ms > envelope.max_key_hold_time {
-                violations.push(1); // Feature index 1
-            }
+            let relationship = GuardianRelationship {
+                guardian: guardian.clone(),
+                relationship_strength,
+                established_at: now,
+                interaction_count: 0,
+                bonded_stake: bond_amount,
+            };
             
-            // Check transition time
-            if features.avg_transition_time_ms < envelope.min_transition_time 
-                || features.avg_transition_time_ms > envelope.max_transition_time {
-                violations.push(2); // Feature index 2
+            GuardianRelationships::<T>::insert(did, &guardian, relationship);
+            if ProgressiveRecoveries::<T>::contains_key(did) {
+                GuardianRecoveries::<T>::insert(&guardian, did, ());
             }
             
-            let is_within = violations.is_empty();
-            (is_within, violations)
+            Self::deposit_event(Event::GuardianRelationshipEstablished {
+                did: *did,
+                guardian,
+                strength: relationship_strength,
+            });
+            
+            Ok(())
         }
 
-        /// Check if nullifier is part of any personhood
-        pub fn get_personhood_for_nullifier(nullifier: &H256) -> Option<H256> {
-            BiometricBindings::<T>::get(nullifier)
+        /// Replace a recovered nullifier in the binding storages
+        /// Keeps `get_personhood_for_nullifier` consistent with the legacy registry
+        fn rotate_binding_nullifier(did: &H256, old_nullifier: &H256, new_nullifier: &H256, now: u64) {
+            BiometricBindings::<T>::remove(old_nullifier);
+            NullifierClaims::<T>::remove(old_nullifier);
+            BiometricBindings::<T>::insert(new_nullifier, *did);
+            NullifierClaims::<T>::insert(new_nullifier, true);
+            
+            PersonhoodBindings::<T>::mutate(did, |binding_opt| {
+                if let Some(binding) = binding_opt {
+                    if binding.primary_nullifier == *old_nullifier {
+                        binding.primary_nullifier = *new_nullifier;
+                    }
+                    for (nullifier, _) in binding.bound_nullifiers.iter_mut() {
+                        if *nullifier == *old_nullifier {
+                            *nullifier = *new_nullifier;
+                        }
+                    }
+                    binding.updated_at = now;
+                }
+            });
         }
-        
+
         /// SECURITY CHECK: Verify credential issuance doesn't create duplicate personhoods
         pub fn verify_single_personhood_for_credential(
             _issuer_did: &H256,
@@ -3884,6 +5717,12 @@ pub mod pallet {
             Ok(())
         }
 
+        /// Whether ZK verifier calls are bypassed: the runtime must ask for it and the
+        /// pallet must be built for development
+        fn skip_zk_verification() -> bool {
+            cfg!(any(test, feature = "dev-skip-zk-verification")) && T::DevSkipZkVerification::get()
+        }
+
         /// Verify ZK proof that biometric is valid and unique
         fn verify_biometric_zk_proof( 
             nullifier: &H256,
@@ -3930,8 +5769,10 @@ pub mod pallet {
                 nonce: *nullifier,
             };
             
-            pallet_zk_credentials::pallet::Pallet::<T::ZkCredentials>::verify_proof_internal(&zk_proof)
-                .map_err(|_| Error::<T>::InvalidUniquenessProof)?;
+            if !Self::skip_zk_verification() {
+                pallet_zk_credentials::pallet::Pallet::<T::ZkCredentials>::verify_proof_internal(&zk_proof)
+                    .map_err(|_| Error::<T>::InvalidUniquenessProof)?;
+            }
             
             Ok(())
         }
@@ -3957,34 +5798,29 @@ pub mod pallet {
         }
 
         /// Batch verify multiple existence proofs (for cross-chain efficiency)
+        /// Returns whether each nullifier is included; a proof that cannot answer
+        /// for every key against `state_root` is rejected as malformed
         pub fn batch_verify_existence_proofs(
             nullifiers: Vec<H256>,
             state_root: H256,
             proof_nodes: Vec<Vec<u8>>,
         ) -> Result<Vec<bool>, Error<T>> {
-            let keys: Vec<Vec<u8>> = nullifiers
-                .iter()
-                .map(|n| Self::storage_key_for_nullifier(n))
-                .collect();
-            
-            let key_refs: Vec<(&[u8], Option<&[u8]>)> = keys
-                .iter()
-                .map(|k| (k.as_slice(), None))
-                .collect();
-            
-            let result = verify_trie_proof::<LayoutV1<BlakeTwo256>, _, _, _>(
-                &state_root,
-                &proof_nodes,
-                &key_refs,
+            ensure!(
+                !proof_nodes.is_empty() && proof_nodes.iter().all(|node| !node.is_empty()),
+                Error::<T>::EmptyExistenceProof
             );
             
-            match result {
-                Ok(_) => {
-                    // All keys verified, return true for each
-                    Ok(vec![true; nullifiers.len()])
-                },
-                Err(_) => Ok(vec![false; nullifiers.len()]),
-            }
+            let db = StorageProof::new(proof_nodes).into_memory_db::<BlakeTwo256>();
+            
+            nullifiers
+                .iter()
+                .map(|nullifier| {
+                    let key = Self::storage_key_for_nullifier(nullifier);
+                    read_trie_value::<LayoutV1<BlakeTwo256>, _>(&db, &state_root, &key, None, None)
+                        .map(|value| value.is_some())
+                        .map_err(|_| Error::<T>::InvalidExistenceProof)
+                })
+                .collect()
         }
 
         /// Store a new behavioral sample (maintains rolling window of 10)
@@ -4034,7 +5870,7 @@ pub mod pallet {
 
         /// Punish oracles with outlier scores
         fn punish_outlier_oracles(did: &H256, median: u8, tolerance: u8) {
-            for (oracle_id, _oracle) in MLOracles::<T>::iter() {
+            for oracle_id in ActiveOracleIds::<T>::get() {
                 if let Some((score, _)) = OracleResponses::<T>::get(did, oracle_id) {
                     let deviation = if score > median {
                         score - median
@@ -4103,53 +5939,107 @@ pub mod pallet {
             new_nullifier: &H256,
             proof_bytes: &[u8],
         ) -> Result<(), Error<T>> {
+            // Anything shorter than a real proof could never pass ZK verification
             ensure!(
-                !proof_bytes.is_empty() && proof_bytes.len() <= 4096,
+                proof_bytes.len() >= T::MinRecoveryProofLength::get() as usize
+                    && proof_bytes.len() <= 4096,
                 Error::<T>::InvalidRecoveryProof
             );
 
             let old_nullifier = DidToNullifier::<T>::get(old_did)
                 .ok_or(Error::<T>::DidNotFound)?;
 
-            if proof_bytes.len() > 64 {
-                let bounded_proof: BoundedVec<u8, ConstU32<8192>> = proof_bytes
-                    .to_vec()
+            // Replay is tracked on the proof's public nonce rather than its bytes, since a
+            // Groth16 proof can be re-randomized into different bytes that still verify
+            let now = <T as Config>::TimeProvider::now().saturated_into::<u64>();
+            ensure!(
+                UsedRecoveryProofNonces::<T>::get(new_nullifier).map_or(true, |expires_at| expires_at <= now),
+                Error::<T>::RecoveryProofReplayed
+            );
+
+            let bounded_proof: BoundedVec<u8, ConstU32<8192>> = proof_bytes
+                .to_vec()
+                .try_into()
+                .map_err(|_| Error::<T>::InvalidRecoveryProof)?;
+            
+            let mut public_inputs = Vec::new();
+            public_inputs.push(
+                old_nullifier.as_bytes().to_vec()
+                    .try_into()
+                    .map_err(|_| Error::<T>::InvalidRecoveryProof)?
+            );
+            public_inputs.push(
+                new_nullifier.as_bytes().to_vec()
+                    .try_into()
+                    .map_err(|_| Error::<T>::InvalidRecoveryProof)?
+            );
+            
+            let bounded_inputs: BoundedVec<BoundedVec<u8, ConstU32<64>>, ConstU32<16>> = 
+                public_inputs
                     .try_into()
                     .map_err(|_| Error::<T>::InvalidRecoveryProof)?;
-                
-                let mut public_inputs = Vec::new();
-                public_inputs.push(
-                    old_nullifier.as_bytes().to_vec()
-                        .try_into()
-                        .map_err(|_| Error::<T>::InvalidRecoveryProof)?
-                );
-                public_inputs.push(
-                    new_nullifier.as_bytes().to_vec()
-                        .try_into()
-                        .map_err(|_| Error::<T>::InvalidRecoveryProof)?
-                );
-                
-                let bounded_inputs: BoundedVec<BoundedVec<u8, ConstU32<64>>, ConstU32<16>> = 
-                    public_inputs
-                        .try_into()
-                        .map_err(|_| Error::<T>::InvalidRecoveryProof)?;
-                
-                let zk_proof = pallet_zk_credentials::pallet::ZkProof {
-                    proof_type: pallet_zk_credentials::pallet::ProofType::Personhood,
-                    proof_data: bounded_proof,
-                    public_inputs: bounded_inputs,
-                    credential_hash: *old_did,
-                    created_at: <T as Config>::TimeProvider::now().saturated_into::<u64>(),
-                    nonce: *new_nullifier,
-                };
-                
+            
+            let zk_proof = pallet_zk_credentials::pallet::ZkProof {
+                proof_type: pallet_zk_credentials::pallet::ProofType::Personhood,
+                proof_data: bounded_proof,
+                public_inputs: bounded_inputs,
+                credential_hash: *old_did,
+                created_at: now,
+                nonce: *new_nullifier,
+            };
+            
+            if !Self::skip_zk_verification() {
                 pallet_zk_credentials::pallet::Pallet::<T::ZkCredentials>::verify_proof_internal(&zk_proof)
                     .map_err(|_| Error::<T>::InvalidRecoveryProof)?;
             }
 
+            UsedRecoveryProofNonces::<T>::insert(
+                new_nullifier,
+                now.saturating_add(T::RecoveryProofNonceLifetime::get()),
+            );
+
             Ok(())
         }
 
+        /// Remove expired recovery proof nonces within `limit`, resuming from where the
+        /// previous pass stopped
+        fn prune_recovery_proof_nonces(limit: Weight) -> Weight {
+            let db = T::DbWeight::get();
+            let per_entry = db.reads_writes(1, 1);
+            // Timestamp and cursor
+            let mut used = db.reads_writes(2, 1);
+            if !used.saturating_add(per_entry).all_lte(limit) {
+                return Weight::zero();
+            }
+
+            let now = <T as Config>::TimeProvider::now().saturated_into::<u64>();
+            let mut nonces = match RecoveryProofNoncePruneCursor::<T>::take() {
+                Some(cursor) => UsedRecoveryProofNonces::<T>::iter_from(cursor.into_inner()),
+                None => UsedRecoveryProofNonces::<T>::iter(),
+            };
+
+            let mut last_visited = None;
+            while used.saturating_add(per_entry).all_lte(limit) {
+                let Some((nonce, expires_at)) = nonces.next() else {
+                    return used;
+                };
+                used = used.saturating_add(per_entry);
+                if expires_at <= now {
+                    UsedRecoveryProofNonces::<T>::remove(nonce);
+                }
+                last_visited = Some(nonce);
+            }
+
+            if let Some(nonce) = last_visited {
+                let cursor: Result<BoundedVec<u8, ConstU32<128>>, _> =
+                    UsedRecoveryProofNonces::<T>::hashed_key_for(nonce).try_into();
+                if let Ok(cursor) = cursor {
+                    RecoveryProofNoncePruneCursor::<T>::put(cursor);
+                }
+            }
+            used
+        }
+
         /// Verify cross-biometric ZK proof
         fn verify_cross_biometric_proof(
             existing_nullifier: &H256,
@@ -4194,8 +6084,10 @@ pub mod pallet {
                 nonce: *new_nullifier,
             };
             
-            pallet_zk_credentials::pallet::Pallet::<T::ZkCredentials>::verify_proof_internal(&zk_proof)
-                .map_err(|_| Error::<T>::InvalidCrossBiometricProof)?;
+            if !Self::skip_zk_verification() {
+                pallet_zk_credentials::pallet::Pallet::<T::ZkCredentials>::verify_proof_internal(&zk_proof)
+                    .map_err(|_| Error::<T>::InvalidCrossBiometricProof)?;
+            }
             
             Ok(())
         }
@@ -4208,12 +6100,10 @@ pub mod pallet {
             // Collect all responses for this DID
             let mut responses: Vec<(u8, u8, u64)> = Vec::new(); // (oracle_id, score, timestamp)
             
-            for (oracle_id, oracle) in MLOracles::<T>::iter() {
+            // Only active oracles are indexed
+            for oracle_id in ActiveOracleIds::<T>::get() {
                 if let Some((score, timestamp)) = OracleResponses::<T>::get(did, oracle_id) {
-                    // Only include active oracles
-                    if oracle.active {
-                        responses.push((oracle_id, score, timestamp));
-                    }
+                    responses.push((oracle_id, score, timestamp));
                 }
             }
             
@@ -4227,19 +6117,18 @@ pub mod pallet {
             scores.sort_unstable();
             let median_score = scores[scores.len() / 2];
             
-            // Check variance (all scores must be within tolerance of median)
-            let max_deviation = scores.iter()
-                .map(|s| {
-                    if *s > median_score {
-                        s - median_score
-                    } else {
-                        median_score - s
-                    }
-                })
-                .max()
-                .unwrap_or(0);
+            // Split responses into those within tolerance of the median and outliers
+            let (agreeing, outliers): (Vec<_>, Vec<_>) = responses
+                .iter()
+                .partition(|(_, score, _)| score.abs_diff(median_score) <= variance_tolerance);
+            
+            let required_agreement = match ConsensusAgreementThreshold::<T>::get() {
+                0 => responses.len(),
+                agreement => agreement as usize,
+            };
             
-            if max_deviation > variance_tolerance {
+            if agreeing.len() < required_agreement {
+                Self::record_consensus_outcome(false);
                 Self::deposit_event(Event::ConsensusFailed {
                     did: *did,
                     reason: b"Score variance too high".to_vec(),
@@ -4251,12 +6140,17 @@ pub mod pallet {
                 return Err(Error::<T>::OracleScoreVarianceTooHigh);
             }
             
+            // Enough oracles agree; outliers are excluded from the score and penalized
+            if !outliers.is_empty() {
+                Self::punish_outlier_oracles(did, median_score, variance_tolerance);
+            }
+            
             // Calculate weighted average (weight by oracle reputation)
             let mut weighted_sum = 0u32;
             let mut weight_total = 0u32;
             let mut participating_oracles = Vec::new();
             
-            for (oracle_id, score, _) in responses.iter() {
+            for (oracle_id, score, _) in agreeing.iter() {
                 if let Some(oracle) = MLOracles::<T>::get(oracle_id) {
                     let weight = oracle.reputation as u32;
                     weighted_sum += (*score as u32) * weight;
@@ -4285,11 +6179,13 @@ pub mod pallet {
                     // Remove from pending queue
                     PendingMLPatterns::<T>::remove(did);
                     
-                    // Clean up oracle responses
-                    for oracle_id in participating_oracles.iter() {
+                    // Clean up oracle responses, including excluded outliers
+                    for (oracle_id, _, _) in responses.iter() {
                         OracleResponses::<T>::remove(did, oracle_id);
                     }
+                    OracleRoundStartedAt::<T>::remove(did);
                     
+                    Self::record_consensus_outcome(true);
                     Self::deposit_event(Event::ConsensusReached {
                         did: *did,
                         final_score,
@@ -4343,21 +6239,45 @@ pub mod pallet {
             Ok(())
         }
 
-        /// Verify fraud proof with cryptographic signatures
-        fn verify_fraud_proof(
+        /// Drop an oracle from the active index when it is deactivated
+        fn remove_active_oracle_id(oracle_id: u8) {
+            ActiveOracleIds::<T>::mutate(|ids| ids.retain(|id| *id != oracle_id));
+        }
+
+        /// Discard a DID's oracle responses once its round outlives `OracleResponseWindow`
+        fn expire_oracle_round(did: &H256, now: u64) {
+            let Some(started_at) = OracleRoundStartedAt::<T>::get(did) else {
+                return;
+            };
+            if now.saturating_sub(started_at) <= T::OracleResponseWindow::get() {
+                return;
+            }
+            
+            // Oracle IDs are u8, so one pass clears every response
+            let _ = OracleResponses::<T>::clear_prefix(did, u8::MAX as u32 + 1, None);
+            OracleRoundStartedAt::<T>::remove(did);
+            
+            Self::record_consensus_outcome(false);
+            Self::deposit_event(Event::ConsensusFailed {
+                did: *did,
+                reason: b"window expired".to_vec(),
+            });
+        }
+
+        /// Verify fraud proof signed by the DID's controller
+        pub(crate) fn verify_fraud_proof(
             did: &H256,
             guardian: &T::AccountId,
             proof: &[u8],
         ) -> bool {
-            // Proof format: [sig: 64 bytes][timestamp: 8 bytes][evidence_hash: 32 bytes][public_key: 32 bytes]
-            if proof.len() < 136 {
+            // Proof format: [sig: 64 bytes][timestamp: 8 bytes][evidence_hash: 32 bytes]
+            if proof.len() != 104 {
                 return false;
             }
             
             let signature_bytes = &proof[0..64];
             let timestamp_bytes = &proof[64..72];
             let evidence_hash = &proof[72..104];
-            let public_key_bytes = &proof[104..136];
             
             // Parse timestamp
             let timestamp = u64::from_le_bytes(
@@ -4367,9 +6287,9 @@ pub mod pallet {
                 }
             );
             
-            // Verify proof is recent (within 7 days)
+            // Verify proof is recent (within MaxFraudProofAge)
             let now = <T as Config>::TimeProvider::now().saturated_into::<u64>();
-            if now.saturating_sub(timestamp) > MAX_FRAUD_PROOF_AGE {
+            if now.saturating_sub(timestamp) > T::MaxFraudProofAge::get() {
                 return false;
             }
             
@@ -4389,8 +6309,13 @@ pub mod pallet {
             
             let message_hash = sp_io::hashing::blake2_256(&message);
             
-            // Convert to sr25519 types
-            let public_key = match sr25519::Public::try_from(public_key_bytes) {
+            // Only the key registered on-chain as the DID's controller may attest fraud;
+            // a key carried in the proof itself would let anyone sign their own
+            let controller = match Identities::<T>::get(did) {
+                Some(identity) => identity.controller,
+                None => return false,
+            };
+            let public_key = match sr25519::Public::try_from(&controller.encode()[..]) {
                 Ok(pk) => pk,
                 Err(_) => return false,
             };
@@ -4410,17 +6335,6 @@ pub mod pallet {
                 return false;
             }
             
-            // Check guardian's approval history for suspicious patterns
-            let guardian_approval_count = GuardianApprovals::<T>::iter()
-                .filter(|(_, approvals)| approvals.contains(guardian))
-                .count();
-            
-            // Suspicious if too many recent approvals
-            if guardian_approval_count > MAX_GUARDIAN_APPROVALS {
-                return true;
-            }
-            
-            // All checks passed
             relationship.relationship_strength > 0
         }
 
@@ -4466,22 +6380,8 @@ pub mod pallet {
         ) -> u32 {
             let mut score: u32 = 0;
             
-            let guardian_score: u32 = recovery.guardian_votes.iter()
-                .map(|(guardian, vote_strength)| {
-                    GuardianRelationships::<T>::get(&recovery.did, guardian)
-                        .map(|rel| {
-                            let base = (*vote_strength as u32) * (rel.relationship_strength as u32);
-                            let age_bonus = if now.saturating_sub(rel.established_at) > (365 * 24 * 60 * 60) {
-                                2
-                            } else {
-                                0
-                            };
-                            base + age_bonus
-                        })
-                        .unwrap_or(0)
-                })
-                .sum();
-            score = score.saturating_add(guardian_score.min(30));
+            let guardian_score = Self::guardian_contribution(&recovery.did, &recovery.guardian_votes, now);
+            score = score.saturating_add(guardian_score);
             
             let behavioral_score = (recovery.behavioral_confidence as u32 * 30) / 100;
             score = score.saturating_add(behavioral_score);
@@ -4505,8 +6405,59 @@ pub mod pallet {
             
             score
         }
+
+        /// Weighted guardian vote score (max 30 points)
+        /// Each vote counts vote_strength * relationship_strength plus the age bonus;
+        /// relationships younger than `MinGuardianRelationshipAge` count nothing
+        fn guardian_contribution(
+            did: &H256,
+            votes: &[(T::AccountId, u8)],
+            now: u64,
+        ) -> u32 {
+            let guardian_score: u32 = votes.iter()
+                .map(|(guardian, vote_strength)| {
+                    GuardianRelationships::<T>::get(did, guardian)
+                        .filter(|rel| {
+                            now.saturating_sub(rel.established_at) >= T::MinGuardianRelationshipAge::get()
+                        })
+                        .map(|rel| {
+                            let base = (*vote_strength as u32) * (rel.relationship_strength as u32);
+                            base.saturating_add(Self::guardian_age_bonus(rel.established_at, now))
+                        })
+                        .unwrap_or(0)
+                })
+                .fold(0u32, |acc, contribution| acc.saturating_add(contribution));
+            
+            guardian_score.min(30)
+        }
+
+        /// Age bonus for a guardian relationship established at `established_at`
+        fn guardian_age_bonus(established_at: u64, now: u64) -> u32 {
+            if now.saturating_sub(established_at) > T::GuardianAgeBonusThreshold::get() {
+                T::GuardianAgeBonus::get()
+            } else {
+                0
+            }
+        }
         
+        /// Delay reduction for a historical proof, scaling from 0 at the threshold to 45 days at 100
+        pub fn historical_delay_reduction(strength: u8) -> u64 {
+            let threshold = T::MinHistoricalStrength::get();
+            if strength <= threshold || threshold >= 100 {
+                return 0;
+            }
+            let max_reduction = 45 * 24 * 60 * 60u64;
+            let above = strength.min(100).saturating_sub(threshold) as u64;
+            max_reduction.saturating_mul(above) / (100 - threshold) as u64
+        }
+
+    }
 
+    impl<T: Config> BehavioralVerifier for Pallet<T> {
+        /// Read-only comparison, so callers can never alter the stored baseline
+        fn verify_pattern(did: &H256, pattern_data: &[u8]) -> Result<u8, DispatchError> {
+            Self::verify_behavioral_pattern(did, pattern_data, true).map_err(Into::into)
+        }
     }
 
     /// Check if personhood is registered
@@ -0,0 +1,196 @@
+//! Storage migrations for the proof-of-personhood pallet
+
+use crate::pallet::{
+    AMDRootKeys, BiometricBinding, BiometricModality, ChallengeStatus, ChallengeVotes, Config,
+    FraudChallenges, Pallet, PendingMLPatterns, PersonhoodBindings, UsedRecoveryProofNonces,
+    VoterChallenges,
+};
+use codec::{Decode, Encode};
+use frame_support::{
+    migrations::VersionedMigration,
+    traits::{ConstU32, Get, UncheckedOnRuntimeUpgrade},
+    weights::Weight,
+    BoundedVec,
+};
+use sp_core::H256;
+use sp_std::marker::PhantomData;
+
+/// v1: `AMDRootKeys` holds ARK RSA moduli instead of P-384 points
+pub mod v1 {
+    use super::*;
+
+    /// The old entries were P-384 points standing in for AMD's root key, which is an
+    /// RSA key; none of them can anchor a VCEK chain, so they are dropped and governance
+    /// re-registers the ARK moduli through `add_amd_root_key`
+    pub struct UncheckedMigrateToV1<T>(PhantomData<T>);
+
+    impl<T: Config> UncheckedOnRuntimeUpgrade for UncheckedMigrateToV1<T> {
+        fn on_runtime_upgrade() -> Weight {
+            // A handful of governance-registered keys, cleared in one pass
+            let removed = AMDRootKeys::<T>::clear(u32::MAX, None).unique;
+            log::info!("Dropped {} AMD root keys in the old format", removed);
+            T::DbWeight::get().reads_writes(removed.into(), removed.into())
+        }
+    }
+
+    pub type MigrateToV1<T> = VersionedMigration<
+        0,
+        1,
+        UncheckedMigrateToV1<T>,
+        Pallet<T>,
+        <T as frame_system::Config>::DbWeight,
+    >;
+}
+
+/// v2: biometric bindings record the modality of their primary nullifier
+pub mod v2 {
+    use super::*;
+
+    /// Binding as stored before `primary_modality` was added
+    #[derive(Encode, Decode)]
+    pub struct OldBiometricBinding<T: Config> {
+        pub primary_did: H256,
+        pub primary_nullifier: H256,
+        pub bound_nullifiers: BoundedVec<(H256, BiometricModality), ConstU32<10>>,
+        pub created_at: u64,
+        pub updated_at: u64,
+        pub controller: T::AccountId,
+    }
+
+    /// Modalities in the order a legacy primary is assumed to have been enrolled with
+    const LEGACY_PRIMARY_ORDER: [BiometricModality; 6] = [
+        BiometricModality::Fingerprint,
+        BiometricModality::Iris,
+        BiometricModality::FaceGeometry,
+        BiometricModality::Voice,
+        BiometricModality::Gait,
+        BiometricModality::Retina,
+    ];
+
+    /// Registration used to discard the primary's modality, so it cannot be recovered.
+    /// Legacy primaries are recorded as the first modality (fingerprint first) that none of
+    /// their bound nullifiers use, which keeps the one-nullifier-per-modality rule intact.
+    pub struct UncheckedMigrateToV2<T>(PhantomData<T>);
+
+    impl<T: Config> UncheckedOnRuntimeUpgrade for UncheckedMigrateToV2<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let mut translated = 0u64;
+            PersonhoodBindings::<T>::translate::<OldBiometricBinding<T>, _>(|_, old| {
+                translated += 1;
+                // The old rules allowed every modality to be bound; fall back to fingerprint then
+                let primary_modality = LEGACY_PRIMARY_ORDER
+                    .into_iter()
+                    .find(|candidate| !old.bound_nullifiers.iter().any(|(_, bound)| bound == candidate))
+                    .unwrap_or(BiometricModality::Fingerprint);
+                Some(BiometricBinding {
+                    primary_did: old.primary_did,
+                    primary_nullifier: old.primary_nullifier,
+                    primary_modality,
+                    bound_nullifiers: old.bound_nullifiers,
+                    created_at: old.created_at,
+                    updated_at: old.updated_at,
+                    controller: old.controller,
+                })
+            });
+            log::info!("Recorded a primary modality on {} biometric bindings", translated);
+            T::DbWeight::get().reads_writes(translated, translated)
+        }
+    }
+
+    pub type MigrateToV2<T> = VersionedMigration<
+        1,
+        2,
+        UncheckedMigrateToV2<T>,
+        Pallet<T>,
+        <T as frame_system::Config>::DbWeight,
+    >;
+}
+
+/// v3: `PendingMLPatterns` is a counted map
+pub mod v3 {
+    use super::*;
+
+    /// The map's entries predate its counter, which would otherwise start at zero
+    pub struct UncheckedMigrateToV3<T>(PhantomData<T>);
+
+    impl<T: Config> UncheckedOnRuntimeUpgrade for UncheckedMigrateToV3<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let pending = PendingMLPatterns::<T>::initialize_counter();
+            log::info!("Counted {} patterns pending ML scoring", pending);
+            T::DbWeight::get().reads_writes(u64::from(pending) + 1, 1)
+        }
+    }
+
+    pub type MigrateToV3<T> = VersionedMigration<
+        2,
+        3,
+        UncheckedMigrateToV3<T>,
+        Pallet<T>,
+        <T as frame_system::Config>::DbWeight,
+    >;
+}
+
+/// v4: recovery proof nonces are keyed on the proof's public nonce and expire
+pub mod v4 {
+    use super::*;
+
+    /// Old entries were keyed on a hash of the raw proof bytes and hold a consumption
+    /// time, not an expiry; they cannot be rekeyed, so they are dropped. Completed
+    /// recoveries stay protected because their new nullifier is already registered
+    pub struct UncheckedMigrateToV4<T>(PhantomData<T>);
+
+    impl<T: Config> UncheckedOnRuntimeUpgrade for UncheckedMigrateToV4<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let removed = UsedRecoveryProofNonces::<T>::clear(u32::MAX, None).unique;
+            log::info!("Dropped {} recovery proof nonces in the old format", removed);
+            T::DbWeight::get().reads_writes(removed.into(), removed.into())
+        }
+    }
+
+    pub type MigrateToV4<T> = VersionedMigration<
+        3,
+        4,
+        UncheckedMigrateToV4<T>,
+        Pallet<T>,
+        <T as frame_system::Config>::DbWeight,
+    >;
+}
+
+/// v5: index the fraud challenges each account has voted on
+pub mod v5 {
+    use super::*;
+
+    /// Votes on challenges that are still open lock the voter's guardian bonds, so they
+    /// are indexed by voter; votes on settled challenges no longer matter
+    pub struct UncheckedMigrateToV5<T>(PhantomData<T>);
+
+    impl<T: Config> UncheckedOnRuntimeUpgrade for UncheckedMigrateToV5<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let mut reads = 0u64;
+            let mut writes = 0u64;
+            for (challenge_id, challenge) in FraudChallenges::<T>::iter() {
+                reads += 1;
+                if challenge.status != ChallengeStatus::Pending
+                    && challenge.status != ChallengeStatus::UnderReview
+                {
+                    continue;
+                }
+                for voter in ChallengeVotes::<T>::iter_key_prefix(challenge_id) {
+                    reads += 1;
+                    VoterChallenges::<T>::insert(&voter, challenge_id, ());
+                    writes += 1;
+                }
+            }
+            log::info!("Indexed {} votes on open fraud challenges", writes);
+            T::DbWeight::get().reads_writes(reads, writes)
+        }
+    }
+
+    pub type MigrateToV5<T> = VersionedMigration<
+        4,
+        5,
+        UncheckedMigrateToV5<T>,
+        Pallet<T>,
+        <T as frame_system::Config>::DbWeight,
+    >;
+}
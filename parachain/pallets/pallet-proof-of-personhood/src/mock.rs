@@ -0,0 +1,402 @@
+use crate as pallet_proof_of_personhood;
+use ark_bn254::{Bn254, Fr};
+use ark_ff::PrimeField;
+use ark_groth16::Groth16;
+use ark_relations::{
+    lc,
+    r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError, Variable},
+};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_snark::{CircuitSpecificSetupSNARK, SNARK};
+use codec::Decode;
+use crate::pallet::{DidToNullifier, PersonhoodProof, PersonhoodRegistry};
+use pallet_zk_credentials::pallet::{ProofType, VerificationKeyData, VerifyingKeys};
+use frame_support::{
+    assert_ok, derive_impl, parameter_types,
+    traits::{ConstU32, ConstU64, ConstU8},
+};
+use sp_core::{
+    offchain::{testing, OffchainDbExt, OffchainWorkerExt, TransactionPoolExt},
+    H256,
+};
+use sp_keystore::{testing::MemoryKeystore, Keystore, KeystoreExt};
+use sp_runtime::{
+    testing::TestXt,
+    traits::{IdentifyAccount, IdentityLookup, Verify},
+    AccountId32, BuildStorage, MultiSignature, Percent,
+};
+
+type Block = frame_system::mocking::MockBlock<Test>;
+type Extrinsic = TestXt<RuntimeCall, ()>;
+pub type AccountId = <<MultiSignature as Verify>::Signer as IdentifyAccount>::AccountId;
+pub type Balance = u64;
+
+pub const ALICE: AccountId32 = AccountId32::new([1u8; 32]);
+pub const BOB: AccountId32 = AccountId32::new([2u8; 32]);
+pub const CHARLIE: AccountId32 = AccountId32::new([3u8; 32]);
+pub const DAVE: AccountId32 = AccountId32::new([4u8; 32]);
+pub const INSURANCE_POT: AccountId32 = AccountId32::new([0xeeu8; 32]);
+
+pub const INITIAL_BALANCE: Balance = 1_000_000;
+
+// Configure a mock runtime for testing
+frame_support::construct_runtime!(
+    pub enum Test {
+        System: frame_system,
+        Balances: pallet_balances,
+        Timestamp: pallet_timestamp,
+        IdentityRegistry: pallet_identity_registry,
+        ZkCredentials: pallet_zk_credentials,
+        ProofOfPersonhood: pallet_proof_of_personhood,
+        StepUpAuth: pallet_step_up_auth,
+    }
+);
+
+/// Minimal dependent pallet that gates an action on behavioral confidence
+#[frame_support::pallet(dev_mode)]
+pub mod pallet_step_up_auth {
+    use crate::BehavioralVerifier;
+    use frame_support::pallet_prelude::*;
+    use frame_system::pallet_prelude::*;
+    use sp_core::H256;
+
+    #[pallet::pallet]
+    pub struct Pallet<T>(_);
+
+    #[pallet::config]
+    pub trait Config: frame_system::Config {
+        type BehavioralVerifier: BehavioralVerifier;
+    }
+
+    #[pallet::error]
+    pub enum Error<T> {
+        InsufficientConfidence,
+    }
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        #[pallet::call_index(0)]
+        pub fn sensitive_action(
+            origin: OriginFor<T>,
+            did: H256,
+            pattern_data: Vec<u8>,
+        ) -> DispatchResult {
+            ensure_signed(origin)?;
+            let confidence = T::BehavioralVerifier::verify_pattern(&did, &pattern_data)?;
+            ensure!(confidence >= 80, Error::<T>::InsufficientConfidence);
+            Ok(())
+        }
+    }
+}
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for Test {
+    type Block = Block;
+    type AccountId = AccountId;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type AccountData = pallet_balances::AccountData<Balance>;
+}
+
+#[derive_impl(pallet_balances::config_preludes::TestDefaultConfig)]
+impl pallet_balances::Config for Test {
+    type Balance = Balance;
+    type AccountStore = System;
+}
+
+impl pallet_timestamp::Config for Test {
+    type Moment = u64;
+    type OnTimestampSet = ();
+    type MinimumPeriod = ConstU64<1>;
+    type WeightInfo = ();
+}
+
+impl pallet_identity_registry::pallet::Config for Test {
+    type TimeProvider = Timestamp;
+    type WeightInfo = ();
+}
+
+impl pallet_zk_credentials::pallet::Config for Test {
+    type WeightInfo = ();
+}
+
+parameter_types! {
+    pub static RegistrationDeposit: Balance = 100;
+    pub static RecoveryDeposit: Balance = 500;
+    pub const GuardianAgeBonusThreshold: u64 = 365 * 24 * 60 * 60;
+    pub static BehavioralStoreThreshold: u8 = 80;
+    pub static BehavioralSimilarityWeight: u8 = 70;
+    pub static MaxActiveOracles: u32 = 3;
+    pub static MaxScoreStatSamples: u32 = 1_000;
+    pub static MinGuardianRelationshipAge: u64 = 0;
+    pub static DevSkipZkVerification: bool = false;
+    pub static MinGuardianBond: Balance = 500;
+    pub static MaxGuardianBondPerAccount: Option<Balance> = None;
+    pub static MinRecoveryProofLength: u32 = 128;
+    pub static SessionValidityWindow: u64 = 300;
+    pub static TrendDeviationTolerancePercent: u8 = 20;
+    pub static MinSamplesForEnvelopeRejection: u32 = 0;
+    pub static FraudRewardPercent: Percent = Percent::from_percent(50);
+    pub const InsurancePot: AccountId = INSURANCE_POT;
+    pub static MaxPendingMLPatterns: u32 = 100;
+    pub static BaseRecoveryDelay: u64 = 6 * 30 * 24 * 60 * 60;
+    pub static MinRecoveryDelay: u64 = 7 * 24 * 60 * 60;
+    pub static ChallengeVoteMargin: Percent = Percent::from_percent(20);
+    pub static GuardianStrengthThreshold: Percent = Percent::from_percent(60);
+    pub const BehavioralSampleConfidenceTiers: [u8; 5] = [50, 70, 85, 95, 100];
+}
+
+impl pallet_proof_of_personhood::pallet::Config for Test {
+    type Currency = Balances;
+    type TimeProvider = Timestamp;
+    type RegistrationDeposit = RegistrationDeposit;
+    type RecoveryDeposit = RecoveryDeposit;
+    type ZkCredentials = Test;
+    type WeightInfo = ();
+    type AuthorityId = crate::crypto::TestAuthId;
+    type MinBehavioralConfidence = ConstU8<80>;
+    type MinHistoricalStrength = ConstU8<90>;
+    type GuardianAgeBonusThreshold = GuardianAgeBonusThreshold;
+    type GuardianAgeBonus = ConstU32<2>;
+    type MaxMLPatternsPerRun = ConstU32<10>;
+    type MaxBiometricBatchSize = ConstU32<5>;
+    type BehavioralStoreThreshold = BehavioralStoreThreshold;
+    type BehavioralDriftStoreThreshold = ConstU8<70>;
+    type MaxActiveOracles = MaxActiveOracles;
+    type BehavioralSimilarityWeight = BehavioralSimilarityWeight;
+    type BehavioralZeroConfidenceDistance = ConstU32<100>;
+    type BehavioralSampleConfidenceTiers = BehavioralSampleConfidenceTiers;
+    type MaxScoreStatSamples = MaxScoreStatSamples;
+    type MinGuardianRelationshipAge = MinGuardianRelationshipAge;
+    type DevSkipZkVerification = DevSkipZkVerification;
+    type MinGuardianBond = MinGuardianBond;
+    type MaxGuardianBondPerAccount = MaxGuardianBondPerAccount;
+    type MinRecoveryProofLength = MinRecoveryProofLength;
+    type MaxReputationGainPerWindow = ConstU8<5>;
+    type ReputationGainWindow = ConstU64<3_600>;
+    type SessionValidityWindow = SessionValidityWindow;
+    type ConsensusOutcomeWindow = ConstU64<3_600>;
+    type TrendDeviationTolerancePercent = TrendDeviationTolerancePercent;
+    type MinSamplesForEnvelopeRejection = MinSamplesForEnvelopeRejection;
+    type MaxRegistrationAttemptsPerWindow = ConstU32<1>;
+    type RegistrationAttemptWindow = ConstU64<3_600>;
+    type FraudRewardPercent = FraudRewardPercent;
+    type InsurancePot = InsurancePot;
+    type MaxPendingMLPatterns = MaxPendingMLPatterns;
+    type RecoveryDelay = ConstU64<{ 6 * 30 * 24 * 60 * 60 }>;
+    type RecoveryProofNonceLifetime = ConstU64<{ 30 * 24 * 60 * 60 }>;
+    type RegistrationCooldownPeriod = ConstU64<{ 6 * 30 * 24 * 60 * 60 }>;
+    type BaseRecoveryDelay = BaseRecoveryDelay;
+    type MinRecoveryDelay = MinRecoveryDelay;
+    type RequiredRecoveryScore = ConstU32<100>;
+    type MaxFraudProofAge = ConstU64<{ 7 * 24 * 60 * 60 }>;
+    type ChallengeVoteQuorum = ConstU32<2>;
+    type ChallengeVoteMargin = ChallengeVoteMargin;
+    type MLResponseValidity = ConstU64<60>;
+    type MaxGuardiansPerBatch = ConstU32<5>;
+    type GuardianStrengthThreshold = GuardianStrengthThreshold;
+    type OracleResponseWindow = ConstU64<600>;
+}
+
+impl pallet_step_up_auth::Config for Test {
+    type BehavioralVerifier = ProofOfPersonhood;
+}
+
+impl frame_system::offchain::SigningTypes for Test {
+    type Public = <MultiSignature as Verify>::Signer;
+    type Signature = MultiSignature;
+}
+
+impl<LocalCall> frame_system::offchain::CreateTransactionBase<LocalCall> for Test
+where
+    RuntimeCall: From<LocalCall>,
+{
+    type RuntimeCall = RuntimeCall;
+    type Extrinsic = Extrinsic;
+}
+
+impl<LocalCall> frame_system::offchain::CreateSignedTransaction<LocalCall> for Test
+where
+    RuntimeCall: From<LocalCall>,
+{
+    fn create_signed_transaction<
+        C: frame_system::offchain::AppCrypto<Self::Public, Self::Signature>,
+    >(
+        call: RuntimeCall,
+        _public: <MultiSignature as Verify>::Signer,
+        _account: AccountId,
+        nonce: u64,
+    ) -> Option<Extrinsic> {
+        Some(Extrinsic::new_signed(call, nonce, (), ()))
+    }
+}
+
+// Test helpers
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    let mut storage = frame_system::GenesisConfig::<Test>::default()
+        .build_storage()
+        .unwrap();
+
+    pallet_balances::GenesisConfig::<Test> {
+        balances: vec![
+            (ALICE, INITIAL_BALANCE),
+            (BOB, INITIAL_BALANCE),
+            (CHARLIE, INITIAL_BALANCE),
+            (DAVE, INITIAL_BALANCE),
+        ],
+        ..Default::default()
+    }
+    .assimilate_storage(&mut storage)
+    .unwrap();
+
+    let mut ext: sp_io::TestExternalities = storage.into();
+    ext.execute_with(|| {
+        System::set_block_number(1);
+        Timestamp::set_timestamp(1_700_000_000);
+    });
+    ext
+}
+
+/// Externalities with offchain, transaction pool and a keystore holding an oracle signing key
+pub fn new_offchain_test_ext() -> sp_io::TestExternalities {
+    new_offchain_test_ext_with_http(vec![]).0
+}
+
+/// Offchain externalities answering `expected` HTTP requests in order, plus a reader
+/// for the calls the worker submitted to the transaction pool
+pub fn new_offchain_test_ext_with_http(
+    expected: Vec<testing::PendingRequest>,
+) -> (sp_io::TestExternalities, impl Fn() -> Vec<RuntimeCall>) {
+    let (offchain, offchain_state) = testing::TestOffchainExt::new();
+    for request in expected {
+        offchain_state.write().expect_request(request);
+    }
+    let (pool, pool_state) = testing::TestTransactionPoolExt::new();
+    let keystore = MemoryKeystore::new();
+    keystore
+        .sr25519_generate_new(crate::KEY_TYPE, Some("//Oracle"))
+        .unwrap();
+
+    let mut ext = new_test_ext();
+    ext.register_extension(OffchainWorkerExt::new(offchain.clone()));
+    ext.register_extension(OffchainDbExt::new(offchain));
+    ext.register_extension(TransactionPoolExt::new(pool));
+    ext.register_extension(KeystoreExt::new(keystore));
+
+    let submitted_calls = move || {
+        pool_state
+            .read()
+            .transactions
+            .iter()
+            .map(|tx| Extrinsic::decode(&mut &tx[..]).unwrap().function)
+            .collect::<Vec<_>>()
+    };
+    (ext, submitted_calls)
+}
+
+/// Public key the offchain keystore signs with
+pub fn ocw_signing_key() -> [u8; 32] {
+    <sp_core::sr25519::Pair as sp_core::Pair>::from_string("//Oracle", None)
+        .unwrap()
+        .public()
+        .0
+}
+
+pub fn create_test_identity(account: AccountId, did: &[u8]) -> H256 {
+    assert_ok!(IdentityRegistry::create_identity(
+        RuntimeOrigin::signed(account),
+        did.to_vec(),
+        H256::repeat_byte(0xAA),
+    ));
+    IdentityRegistry::hash_did(did)
+}
+
+/// Seed a personhood registration directly, bypassing the ZK uniqueness proof
+pub fn seed_personhood(did: H256, nullifier: H256, controller: AccountId) {
+    let now = Timestamp::get();
+    let proof = PersonhoodProof::<Test> {
+        biometric_commitment: H256::repeat_byte(0xCC),
+        nullifier,
+        uniqueness_proof: Default::default(),
+        registered_at: now,
+        did,
+        controller,
+    };
+    PersonhoodRegistry::<Test>::insert(&nullifier, proof);
+    DidToNullifier::<Test>::insert(&did, nullifier);
+}
+
+/// Circuit exposing each value as a public input bound to an equal witness
+#[derive(Clone)]
+struct PublicInputsCircuit {
+    inputs: Vec<Fr>,
+}
+
+impl ConstraintSynthesizer<Fr> for PublicInputsCircuit {
+    fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+        for value in self.inputs {
+            let input = cs.new_input_variable(|| Ok(value))?;
+            let witness = cs.new_witness_variable(|| Ok(value))?;
+            cs.enforce_constraint(lc!() + input, lc!() + Variable::One, lc!() + witness)?;
+        }
+        Ok(())
+    }
+}
+
+/// Install a Groth16 verifying key for `proof_type` and return a valid proof over `inputs`
+/// Setup is seeded deterministically, so proofs with the same input count share one key
+pub fn zk_proof_for(proof_type: ProofType, inputs: &[&[u8]]) -> Vec<u8> {
+    let mut rng = ark_std::test_rng();
+    let circuit = PublicInputsCircuit {
+        inputs: inputs.iter().map(|input| Fr::from_be_bytes_mod_order(input)).collect(),
+    };
+
+    let (pk, vk) = Groth16::<Bn254>::circuit_specific_setup(circuit.clone(), &mut rng).unwrap();
+    let proof = Groth16::<Bn254>::prove(&pk, circuit, &mut rng).unwrap();
+
+    let mut vk_bytes = Vec::new();
+    vk.serialize_compressed(&mut vk_bytes).unwrap();
+    VerifyingKeys::<Test>::insert(
+        &proof_type,
+        VerificationKeyData {
+            proof_type: proof_type.clone(),
+            vk_data: vk_bytes.try_into().unwrap(),
+            registered_by: H256::zero(),
+            registered_at: 0,
+        },
+    );
+
+    let mut proof_bytes = Vec::new();
+    proof.serialize_compressed(&mut proof_bytes).unwrap();
+    proof_bytes
+}
+
+/// Build a commitment and uniqueness proof ([salt][groth16 proof]) for `nullifier`
+pub fn uniqueness_proof(nullifier: H256) -> (H256, Vec<u8>) {
+    let salt = [0x5Au8; 32];
+    let mut preimage = nullifier.as_bytes().to_vec();
+    preimage.extend_from_slice(&salt);
+    let commitment = H256::from(sp_io::hashing::blake2_256(&preimage));
+
+    let mut proof = salt.to_vec();
+    proof.extend(zk_proof_for(
+        ProofType::Personhood,
+        &[nullifier.as_bytes(), commitment.as_bytes()],
+    ));
+    (commitment, proof)
+}
+
+pub fn recovery_proof(old_nullifier: H256, new_nullifier: H256) -> Vec<u8> {
+    zk_proof_for(ProofType::Personhood, &[old_nullifier.as_bytes(), new_nullifier.as_bytes()])
+}
+
+/// Re-randomize a Groth16 proof under the installed key: different bytes, same statement
+pub fn rerandomize_proof(proof_type: ProofType, proof_bytes: &[u8]) -> Vec<u8> {
+    let vk_data = VerifyingKeys::<Test>::get(&proof_type).unwrap().vk_data;
+    let vk = ark_groth16::VerifyingKey::<Bn254>::deserialize_compressed(&vk_data[..]).unwrap();
+    let proof = ark_groth16::Proof::<Bn254>::deserialize_compressed(proof_bytes).unwrap();
+    let proof = Groth16::<Bn254>::rerandomize_proof(&vk, &proof, &mut ark_std::test_rng());
+
+    let mut rerandomized = Vec::new();
+    proof.serialize_compressed(&mut rerandomized).unwrap();
+    rerandomized
+}
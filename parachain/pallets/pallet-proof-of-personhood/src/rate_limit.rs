@@ -0,0 +1,121 @@
+//! Transaction extension rate-limiting personhood registration and recovery per account
+
+use crate::pallet::{Call, Config, Pallet};
+use codec::{Decode, DecodeWithMemTracking, Encode};
+use core::marker::PhantomData;
+use frame_support::{
+    dispatch::DispatchInfo, pallet_prelude::TransactionSource, traits::{Get, IsSubType},
+    weights::Weight,
+};
+use scale_info::TypeInfo;
+use sp_runtime::{
+    traits::{
+        AsSystemOriginSigner, DispatchInfoOf, Dispatchable, Implication, TransactionExtension,
+        ValidateResult,
+    },
+    transaction_validity::{InvalidTransaction, TransactionValidityError, ValidTransaction},
+};
+
+/// `InvalidTransaction::Custom` code for an account over its registration allowance
+pub const REGISTRATION_RATE_LIMITED: u8 = 1;
+
+/// Rejects registration and recovery extrinsics from an account that has used up
+/// `MaxRegistrationAttemptsPerWindow` in the current `RegistrationAttemptWindow`
+#[derive(Encode, Decode, DecodeWithMemTracking, Clone, Eq, PartialEq, TypeInfo)]
+#[scale_info(skip_type_params(T))]
+pub struct CheckRegistrationRate<T: Config + Send + Sync>(PhantomData<T>);
+
+impl<T: Config + Send + Sync> CheckRegistrationRate<T> {
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+
+    fn is_rate_limited(call: &T::RuntimeCall) -> bool
+    where
+        T::RuntimeCall: IsSubType<Call<T>>,
+    {
+        matches!(
+            call.is_sub_type(),
+            Some(
+                Call::register_personhood { .. }
+                    | Call::register_primary_personhood { .. }
+                    | Call::request_recovery { .. }
+                    | Call::initiate_progressive_recovery { .. }
+            )
+        )
+    }
+}
+
+impl<T: Config + Send + Sync> Default for CheckRegistrationRate<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Config + Send + Sync> core::fmt::Debug for CheckRegistrationRate<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "CheckRegistrationRate")
+    }
+}
+
+impl<T: Config + Send + Sync> TransactionExtension<T::RuntimeCall> for CheckRegistrationRate<T>
+where
+    T::RuntimeCall: Dispatchable<Info = DispatchInfo> + IsSubType<Call<T>>,
+    <T::RuntimeCall as Dispatchable>::RuntimeOrigin: AsSystemOriginSigner<T::AccountId> + Clone,
+{
+    const IDENTIFIER: &'static str = "CheckRegistrationRate";
+    type Implicit = ();
+    type Val = ();
+    type Pre = ();
+
+    fn weight(&self, call: &T::RuntimeCall) -> Weight {
+        if Self::is_rate_limited(call) {
+            T::DbWeight::get().reads_writes(1, 1)
+        } else {
+            Weight::zero()
+        }
+    }
+
+    fn validate(
+        &self,
+        origin: <T::RuntimeCall as Dispatchable>::RuntimeOrigin,
+        call: &T::RuntimeCall,
+        _info: &DispatchInfoOf<T::RuntimeCall>,
+        _len: usize,
+        _self_implicit: Self::Implicit,
+        _inherited_implication: &impl Implication,
+        _source: TransactionSource,
+    ) -> ValidateResult<Self::Val, T::RuntimeCall> {
+        if !Self::is_rate_limited(call) {
+            return Ok((ValidTransaction::default(), (), origin));
+        }
+        let Some(who) = origin.as_system_origin_signer() else {
+            return Ok((ValidTransaction::default(), (), origin));
+        };
+
+        if Pallet::<T>::registration_attempts(who) >= T::MaxRegistrationAttemptsPerWindow::get() {
+            return Err(TransactionValidityError::Invalid(InvalidTransaction::Custom(
+                REGISTRATION_RATE_LIMITED,
+            )));
+        }
+
+        Ok((ValidTransaction::default(), (), origin))
+    }
+
+    fn prepare(
+        self,
+        _val: Self::Val,
+        origin: &<T::RuntimeCall as Dispatchable>::RuntimeOrigin,
+        call: &T::RuntimeCall,
+        _info: &DispatchInfoOf<T::RuntimeCall>,
+        _len: usize,
+    ) -> Result<Self::Pre, TransactionValidityError> {
+        // Count the attempt even if dispatch later fails, so failing calls can't be spammed
+        if Self::is_rate_limited(call) {
+            if let Some(who) = origin.as_system_origin_signer() {
+                Pallet::<T>::note_registration_attempt(who);
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,3648 @@
+use crate::{mock::*, pallet::*};
+use codec::{Decode, Encode};
+use frame_support::{
+    assert_noop, assert_ok,
+    traits::{GetStorageVersion, Hooks, OnRuntimeUpgrade, ReservableCurrency, StorageVersion},
+    weights::Weight,
+};
+use sp_core::{
+    offchain::{testing, StorageKind},
+    sr25519, Pair, H256,
+};
+use sp_runtime::{traits::Dispatchable, Percent};
+
+#[test]
+fn add_guardian_works() {
+    new_test_ext().execute_with(|| {
+        let did = create_test_identity(ALICE, b"did:identity:alice");
+
+        assert_ok!(ProofOfPersonhood::add_guardian(
+            RuntimeOrigin::signed(ALICE),
+            did,
+            BOB,
+            7,
+            500,
+        ));
+
+        let relationship = ProofOfPersonhood::guardian_relationships(&did, &BOB).unwrap();
+        assert_eq!(relationship.relationship_strength, 7);
+        assert_eq!(relationship.bonded_stake, 500);
+        assert_eq!(Balances::reserved_balance(&BOB), 500);
+    });
+}
+
+#[test]
+fn add_guardian_rejects_self_guardianship() {
+    new_test_ext().execute_with(|| {
+        let did = create_test_identity(ALICE, b"did:identity:alice");
+
+        assert_noop!(
+            ProofOfPersonhood::add_guardian(RuntimeOrigin::signed(ALICE), did, ALICE, 5, 500),
+            Error::<Test>::SelfGuardianNotAllowed
+        );
+        assert_eq!(Balances::reserved_balance(&ALICE), 0);
+    });
+}
+
+#[test]
+fn add_guardian_rejects_duplicate_guardian() {
+    new_test_ext().execute_with(|| {
+        let did = create_test_identity(ALICE, b"did:identity:alice");
+
+        assert_ok!(ProofOfPersonhood::add_guardian(
+            RuntimeOrigin::signed(ALICE),
+            did,
+            BOB,
+            5,
+            500,
+        ));
+        assert_noop!(
+            ProofOfPersonhood::add_guardian(RuntimeOrigin::signed(ALICE), did, BOB, 8, 500),
+            Error::<Test>::GuardianAlreadyExists
+        );
+    });
+}
+
+#[test]
+fn guardian_bond_below_the_minimum_is_rejected() {
+    new_test_ext().execute_with(|| {
+        MinGuardianBond::set(1);
+        let did = create_test_identity(ALICE, b"did:identity:alice");
+
+        assert_noop!(
+            ProofOfPersonhood::add_guardian(RuntimeOrigin::signed(ALICE), did, BOB, 5, 0),
+            Error::<Test>::InsufficientGuardianBond
+        );
+        assert_ok!(ProofOfPersonhood::add_guardian(RuntimeOrigin::signed(ALICE), did, BOB, 5, 1));
+
+        assert_noop!(
+            ProofOfPersonhood::adjust_guardian_bond(RuntimeOrigin::signed(BOB), did, 1, false),
+            Error::<Test>::InsufficientGuardianBond
+        );
+    });
+}
+
+#[test]
+fn guardian_batch_reverts_entirely_on_an_invalid_guardian() {
+    new_test_ext().execute_with(|| {
+        let did = create_test_identity(ALICE, b"did:identity:alice");
+
+        assert_noop!(
+            ProofOfPersonhood::add_guardians_batch(
+                RuntimeOrigin::signed(ALICE),
+                did,
+                vec![(BOB, 5, 500), (CHARLIE, 7, 500), (DAVE, 11, 500)],
+            ),
+            Error::<Test>::InvalidRelationshipStrength
+        );
+        assert_eq!(Balances::reserved_balance(&BOB), 0);
+        assert_eq!(Balances::reserved_balance(&CHARLIE), 0);
+        assert_eq!(GuardianRelationships::<Test>::iter_prefix(&did).count(), 0);
+
+        assert_ok!(ProofOfPersonhood::add_guardians_batch(
+            RuntimeOrigin::signed(ALICE),
+            did,
+            vec![(BOB, 5, 500), (CHARLIE, 7, 600), (DAVE, 10, 700)],
+        ));
+        assert_eq!(Balances::reserved_balance(&CHARLIE), 600);
+        assert_eq!(ProofOfPersonhood::guardian_relationships(&did, &DAVE).unwrap().relationship_strength, 10);
+        System::assert_last_event(RuntimeEvent::ProofOfPersonhood(Event::GuardianRelationshipEstablished {
+            did,
+            guardian: DAVE,
+            strength: 10,
+        }));
+    });
+}
+
+#[test]
+fn guardian_bond_cap_limits_total_reserved_across_dids() {
+    new_test_ext().execute_with(|| {
+        MaxGuardianBondPerAccount::set(Some(1_200));
+        let alice_did = create_test_identity(ALICE, b"did:identity:alice");
+        let charlie_did = create_test_identity(CHARLIE, b"did:identity:charlie");
+        let dave_did = create_test_identity(DAVE, b"did:identity:dave");
+
+        assert_ok!(ProofOfPersonhood::add_guardian(RuntimeOrigin::signed(ALICE), alice_did, BOB, 5, 600));
+        assert_ok!(ProofOfPersonhood::add_guardian(RuntimeOrigin::signed(CHARLIE), charlie_did, BOB, 5, 600));
+        assert_eq!(ProofOfPersonhood::total_guardian_bond(&BOB), 1_200);
+        assert_eq!(Balances::reserved_balance(&BOB), 1_200);
+
+        assert_noop!(
+            ProofOfPersonhood::add_guardian(RuntimeOrigin::signed(DAVE), dave_did, BOB, 5, 500),
+            Error::<Test>::GuardianBondCapExceeded
+        );
+        assert_noop!(
+            ProofOfPersonhood::adjust_guardian_bond(RuntimeOrigin::signed(BOB), alice_did, 1, true),
+            Error::<Test>::GuardianBondCapExceeded
+        );
+
+        assert_ok!(ProofOfPersonhood::adjust_guardian_bond(RuntimeOrigin::signed(BOB), alice_did, 100, false));
+        assert_eq!(ProofOfPersonhood::total_guardian_bond(&BOB), 1_100);
+        assert_eq!(Balances::reserved_balance(&BOB), 1_100);
+    });
+}
+
+/// DID whose controller is an sr25519 key, so tests can sign fraud proofs for it
+fn keyed_identity(seed: u8, did: &[u8]) -> (H256, AccountId, sr25519::Pair) {
+    let pair = sr25519::Pair::from_seed(&[seed; 32]);
+    let controller = AccountId::from(pair.public());
+    (create_test_identity(controller.clone(), did), controller, pair)
+}
+
+fn fraud_proof(did: H256, guardian: &AccountId, signer: &sr25519::Pair) -> Vec<u8> {
+    let timestamp = Timestamp::get();
+    let evidence_hash = H256::repeat_byte(0xEE);
+
+    let mut message = Vec::new();
+    message.extend_from_slice(b"FRAUD:");
+    message.extend_from_slice(did.as_bytes());
+    message.extend_from_slice(&guardian.encode());
+    message.extend_from_slice(evidence_hash.as_bytes());
+    message.extend_from_slice(&timestamp.to_le_bytes());
+    let signature = signer.sign(&sp_io::hashing::blake2_256(&message));
+
+    let mut proof = Vec::new();
+    proof.extend_from_slice(signature.as_ref());
+    proof.extend_from_slice(&timestamp.to_le_bytes());
+    proof.extend_from_slice(evidence_hash.as_bytes());
+    proof
+}
+
+#[test]
+fn fraud_proof_must_be_signed_by_the_did_controller() {
+    new_test_ext().execute_with(|| {
+        let (did, _, controller_key) = keyed_identity(7, b"did:identity:keyed");
+        seed_guardian(did, BOB, 5, Timestamp::get());
+
+        assert!(ProofOfPersonhood::verify_fraud_proof(&did, &BOB, &fraud_proof(did, &BOB, &controller_key)));
+
+        // Any other key, even one appended to the proof, is not trusted
+        let outsider = sr25519::Pair::from_seed(&[8u8; 32]);
+        assert!(!ProofOfPersonhood::verify_fraud_proof(&did, &BOB, &fraud_proof(did, &BOB, &outsider)));
+        let mut with_key = fraud_proof(did, &BOB, &outsider);
+        with_key.extend_from_slice(outsider.public().as_ref());
+        assert!(!ProofOfPersonhood::verify_fraud_proof(&did, &BOB, &with_key));
+
+        let mut forged = fraud_proof(did, &BOB, &controller_key);
+        forged[0] ^= 0xFF;
+        assert!(!ProofOfPersonhood::verify_fraud_proof(&did, &BOB, &forged));
+
+        // Signed for a different guardian
+        assert!(!ProofOfPersonhood::verify_fraud_proof(&did, &BOB, &fraud_proof(did, &CHARLIE, &controller_key)));
+    });
+}
+
+#[test]
+fn fraud_proof_against_zero_strength_guardian_is_rejected() {
+    new_test_ext().execute_with(|| {
+        let (did, _, controller_key) = keyed_identity(7, b"did:identity:keyed");
+        seed_guardian(did, BOB, 0, Timestamp::get());
+
+        assert!(!ProofOfPersonhood::verify_fraud_proof(&did, &BOB, &fraud_proof(did, &BOB, &controller_key)));
+    });
+}
+
+#[test]
+fn challenge_recovery_slashes_bond_and_rewards_challenger() {
+    new_test_ext().execute_with(|| {
+        let (did, owner, owner_key) = keyed_identity(7, b"did:identity:keyed");
+        assert_ok!(ProofOfPersonhood::add_guardian(
+            RuntimeOrigin::signed(owner),
+            did,
+            BOB,
+            5,
+            600,
+        ));
+
+        let proof = fraud_proof(did, &BOB, &owner_key);
+        assert_ok!(ProofOfPersonhood::challenge_recovery(
+            RuntimeOrigin::signed(CHARLIE),
+            did,
+            BOB,
+            proof,
+        ));
+
+        assert_eq!(Balances::reserved_balance(&BOB), 0);
+        assert_eq!(Balances::free_balance(&BOB), INITIAL_BALANCE - 600);
+        assert_eq!(Balances::free_balance(&CHARLIE), INITIAL_BALANCE + 300);
+        assert!(ProofOfPersonhood::guardian_relationships(&did, &BOB).is_none());
+    });
+}
+
+#[test]
+fn challenge_recovery_reward_scales_to_actual_slash() {
+    new_test_ext().execute_with(|| {
+        let (did, owner, owner_key) = keyed_identity(7, b"did:identity:keyed");
+        assert_ok!(ProofOfPersonhood::add_guardian(
+            RuntimeOrigin::signed(owner),
+            did,
+            BOB,
+            5,
+            600,
+        ));
+
+        // Part of the bond was released outside this pallet
+        Balances::unreserve(&BOB, 200);
+
+        let proof = fraud_proof(did, &BOB, &owner_key);
+        assert_ok!(ProofOfPersonhood::challenge_recovery(
+            RuntimeOrigin::signed(CHARLIE),
+            did,
+            BOB,
+            proof,
+        ));
+
+        assert_eq!(Balances::reserved_balance(&BOB), 0);
+        assert_eq!(Balances::free_balance(&BOB), INITIAL_BALANCE - 400);
+        assert_eq!(Balances::free_balance(&CHARLIE), INITIAL_BALANCE + 200);
+        System::assert_last_event(
+            Event::GuardianSlashed { did, guardian: BOB, amount: 400, total_slashed: 400 }.into(),
+        );
+    });
+}
+
+#[test]
+fn fraud_reward_percent_splits_slash_with_insurance_pot() {
+    new_test_ext().execute_with(|| {
+        FraudRewardPercent::set(Percent::from_percent(70));
+        let (did, owner, owner_key) = keyed_identity(7, b"did:identity:keyed");
+        assert_ok!(ProofOfPersonhood::add_guardian(
+            RuntimeOrigin::signed(owner),
+            did,
+            BOB,
+            5,
+            600,
+        ));
+
+        let proof = fraud_proof(did, &BOB, &owner_key);
+        assert_ok!(ProofOfPersonhood::challenge_recovery(
+            RuntimeOrigin::signed(CHARLIE),
+            did,
+            BOB,
+            proof,
+        ));
+
+        assert_eq!(Balances::free_balance(&CHARLIE), INITIAL_BALANCE + 420);
+        assert_eq!(Balances::free_balance(&INSURANCE_POT), 180);
+    });
+}
+
+#[test]
+fn slashed_guardian_bonds_accumulate() {
+    new_test_ext().execute_with(|| {
+        let (alice_did, alice, alice_key) = keyed_identity(7, b"did:identity:alice");
+        let (dave_did, dave, dave_key) = keyed_identity(9, b"did:identity:dave");
+        assert_ok!(ProofOfPersonhood::add_guardian(RuntimeOrigin::signed(alice), alice_did, BOB, 5, 600));
+        assert_ok!(ProofOfPersonhood::add_guardian(RuntimeOrigin::signed(dave), dave_did, BOB, 5, 700));
+
+        let proof = fraud_proof(alice_did, &BOB, &alice_key);
+        assert_ok!(ProofOfPersonhood::challenge_recovery(RuntimeOrigin::signed(CHARLIE), alice_did, BOB, proof));
+        assert_eq!(ProofOfPersonhood::total_slashed_guardian_bonds(), 600);
+
+        let proof = fraud_proof(dave_did, &BOB, &dave_key);
+        assert_ok!(ProofOfPersonhood::challenge_recovery(RuntimeOrigin::signed(CHARLIE), dave_did, BOB, proof));
+        assert_eq!(ProofOfPersonhood::total_slashed_guardian_bonds(), 1_300);
+        System::assert_last_event(
+            Event::GuardianSlashed { did: dave_did, guardian: BOB, amount: 700, total_slashed: 1_300 }.into(),
+        );
+    });
+}
+
+#[test]
+fn adjust_guardian_bond_increases_stake() {
+    new_test_ext().execute_with(|| {
+        let did = create_test_identity(ALICE, b"did:identity:alice");
+        assert_ok!(ProofOfPersonhood::add_guardian(
+            RuntimeOrigin::signed(ALICE),
+            did,
+            BOB,
+            5,
+            500,
+        ));
+
+        assert_ok!(ProofOfPersonhood::adjust_guardian_bond(
+            RuntimeOrigin::signed(BOB),
+            did,
+            250,
+            true,
+        ));
+
+        let relationship = ProofOfPersonhood::guardian_relationships(&did, &BOB).unwrap();
+        assert_eq!(relationship.bonded_stake, 750);
+        assert_eq!(Balances::reserved_balance(&BOB), 750);
+    });
+}
+
+#[test]
+fn adjust_guardian_bond_cannot_drop_below_minimum() {
+    new_test_ext().execute_with(|| {
+        let did = create_test_identity(ALICE, b"did:identity:alice");
+        assert_ok!(ProofOfPersonhood::add_guardian(
+            RuntimeOrigin::signed(ALICE),
+            did,
+            BOB,
+            5,
+            800,
+        ));
+
+        // Down to exactly the minimum is fine
+        assert_ok!(ProofOfPersonhood::adjust_guardian_bond(
+            RuntimeOrigin::signed(BOB),
+            did,
+            300,
+            false,
+        ));
+        assert_eq!(ProofOfPersonhood::guardian_relationships(&did, &BOB).unwrap().bonded_stake, 500);
+        assert_eq!(Balances::reserved_balance(&BOB), 500);
+
+        assert_noop!(
+            ProofOfPersonhood::adjust_guardian_bond(RuntimeOrigin::signed(BOB), did, 1, false),
+            Error::<Test>::InsufficientGuardianBond
+        );
+
+        // Only the guardian can adjust their own bond
+        assert_noop!(
+            ProofOfPersonhood::adjust_guardian_bond(RuntimeOrigin::signed(ALICE), did, 100, true),
+            Error::<Test>::GuardianNotFound
+        );
+    });
+}
+
+#[test]
+fn replayed_recovery_proof_is_rejected() {
+    new_test_ext().execute_with(|| {
+        let did = create_test_identity(ALICE, b"did:identity:alice");
+        seed_personhood(did, H256::repeat_byte(0x11), ALICE);
+
+        let new_nullifier = H256::repeat_byte(0x22);
+        let new_commitment = H256::repeat_byte(0x33);
+        let proof = recovery_proof(H256::repeat_byte(0x11), new_nullifier);
+
+        assert_ok!(ProofOfPersonhood::request_recovery(
+            RuntimeOrigin::signed(ALICE),
+            did,
+            new_nullifier,
+            new_commitment,
+            proof.clone(),
+            vec![BOB, CHARLIE],
+        ));
+        assert_ok!(ProofOfPersonhood::cancel_recovery(RuntimeOrigin::signed(ALICE), did));
+
+        assert_noop!(
+            ProofOfPersonhood::request_recovery(
+                RuntimeOrigin::signed(ALICE),
+                did,
+                new_nullifier,
+                new_commitment,
+                proof,
+                vec![BOB, CHARLIE],
+            ),
+            Error::<Test>::RecoveryProofReplayed
+        );
+    });
+}
+
+#[test]
+fn rerandomized_recovery_proof_is_rejected_as_a_replay() {
+    new_test_ext().execute_with(|| {
+        let did = create_test_identity(ALICE, b"did:identity:alice");
+        seed_personhood(did, H256::repeat_byte(0x11), ALICE);
+
+        let new_nullifier = H256::repeat_byte(0x22);
+        let proof = recovery_proof(H256::repeat_byte(0x11), new_nullifier);
+        let rerandomized = rerandomize_proof(pallet_zk_credentials::pallet::ProofType::Personhood, &proof);
+        assert_ne!(rerandomized, proof);
+
+        assert_ok!(ProofOfPersonhood::request_recovery(
+            RuntimeOrigin::signed(ALICE),
+            did,
+            new_nullifier,
+            H256::repeat_byte(0x33),
+            proof,
+            vec![BOB, CHARLIE],
+        ));
+        assert_ok!(ProofOfPersonhood::cancel_recovery(RuntimeOrigin::signed(ALICE), did));
+
+        assert_noop!(
+            ProofOfPersonhood::request_recovery(
+                RuntimeOrigin::signed(ALICE),
+                did,
+                new_nullifier,
+                H256::repeat_byte(0x33),
+                rerandomized,
+                vec![BOB, CHARLIE],
+            ),
+            Error::<Test>::RecoveryProofReplayed
+        );
+    });
+}
+
+#[test]
+fn expired_recovery_proof_nonces_are_pruned_on_idle() {
+    new_test_ext().execute_with(|| {
+        let did = create_test_identity(ALICE, b"did:identity:alice");
+        seed_personhood(did, H256::repeat_byte(0x11), ALICE);
+
+        let new_nullifier = H256::repeat_byte(0x22);
+        assert_ok!(ProofOfPersonhood::request_recovery(
+            RuntimeOrigin::signed(ALICE),
+            did,
+            new_nullifier,
+            H256::repeat_byte(0x33),
+            recovery_proof(H256::repeat_byte(0x11), new_nullifier),
+            vec![BOB, CHARLIE],
+        ));
+        assert_ok!(ProofOfPersonhood::cancel_recovery(RuntimeOrigin::signed(ALICE), did));
+
+        // Still live: nothing to prune
+        ProofOfPersonhood::on_idle(1, Weight::MAX);
+        assert!(UsedRecoveryProofNonces::<Test>::contains_key(new_nullifier));
+
+        // Too little weight to visit an entry leaves the map untouched
+        Timestamp::set_timestamp(Timestamp::get() + 30 * 24 * 60 * 60);
+        assert_eq!(ProofOfPersonhood::on_idle(1, Weight::zero()), Weight::zero());
+        assert!(UsedRecoveryProofNonces::<Test>::contains_key(new_nullifier));
+
+        ProofOfPersonhood::on_idle(1, Weight::MAX);
+        assert!(!UsedRecoveryProofNonces::<Test>::contains_key(new_nullifier));
+        assert!(RecoveryProofNoncePruneCursor::<Test>::get().is_none());
+    });
+}
+
+#[test]
+fn finalize_after_auto_cancel_fails_without_double_unreserve() {
+    new_test_ext().execute_with(|| {
+        let did = create_test_identity(ALICE, b"did:identity:alice");
+        seed_personhood(did, H256::repeat_byte(0x11), ALICE);
+        let new_nullifier = H256::repeat_byte(0x22);
+        assert_ok!(ProofOfPersonhood::request_recovery(
+            RuntimeOrigin::signed(ALICE),
+            did,
+            new_nullifier,
+            H256::repeat_byte(0x33),
+            recovery_proof(H256::repeat_byte(0x11), new_nullifier),
+            vec![BOB, CHARLIE],
+        ));
+        assert_ok!(ProofOfPersonhood::approve_recovery(RuntimeOrigin::signed(BOB), did));
+        assert_ok!(ProofOfPersonhood::approve_recovery(RuntimeOrigin::signed(CHARLIE), did));
+        let reserved_during_recovery = Balances::reserved_balance(&ALICE);
+
+        // Activity auto-cancels the recovery and returns the deposit once
+        assert_ok!(ProofOfPersonhood::record_activity(RuntimeOrigin::signed(ALICE)));
+        assert_eq!(Balances::reserved_balance(&ALICE), reserved_during_recovery - RecoveryDeposit::get());
+        let free_after_cancel = Balances::free_balance(&ALICE);
+
+        Timestamp::set_timestamp(Timestamp::get() + 30 * 24 * 60 * 60);
+        assert_noop!(
+            ProofOfPersonhood::finalize_recovery(RuntimeOrigin::signed(ALICE), did),
+            Error::<Test>::RecoveryRequestNotFound
+        );
+        assert_eq!(Balances::free_balance(&ALICE), free_after_cancel);
+        assert_eq!(ProofOfPersonhood::did_to_nullifier(&did), Some(H256::repeat_byte(0x11)));
+    });
+}
+
+fn sample_features() -> BehavioralFeatures {
+    BehavioralFeatures {
+        typing_speed_wpm: 60,
+        avg_key_hold_time_ms: 100,
+        avg_transition_time_ms: 150,
+        error_rate_percent: 5,
+        common_patterns_hash: H256::repeat_byte(0x42),
+        activity_hour_preference: 14,
+    }
+}
+
+#[test]
+fn ml_queue_rejects_new_dids_once_full() {
+    new_test_ext().execute_with(|| {
+        MaxPendingMLPatterns::set(1);
+        let alice_did = create_test_identity(ALICE, b"did:identity:alice");
+        create_test_identity(BOB, b"did:identity:bob");
+        let pattern = sample_features().encode();
+
+        assert_ok!(ProofOfPersonhood::queue_for_ml_scoring(RuntimeOrigin::signed(ALICE), pattern.clone()));
+        assert_noop!(
+            ProofOfPersonhood::queue_for_ml_scoring(RuntimeOrigin::signed(BOB), pattern.clone()),
+            Error::<Test>::PendingQueueFull
+        );
+
+        // Refreshing an already pending DID doesn't take a new slot
+        let refreshed = BehavioralFeatures { typing_speed_wpm: 70, ..sample_features() };
+        assert_ok!(ProofOfPersonhood::queue_for_ml_scoring(RuntimeOrigin::signed(ALICE), refreshed.encode()));
+        assert_eq!(ProofOfPersonhood::pending_ml_patterns(&alice_did), Some(refreshed));
+    });
+}
+
+#[test]
+fn failed_oracle_call_records_diagnostic() {
+    new_offchain_test_ext().execute_with(|| {
+        let did = H256::repeat_byte(0x01);
+        assert_ok!(ProofOfPersonhood::register_oracle(
+            RuntimeOrigin::root(),
+            Some(1),
+            H256::repeat_byte(0x10),
+            [9u8; 32],
+            None,
+        ));
+        PendingMLPatterns::<Test>::insert(&did, sample_features());
+
+        // No oracle URL is configured in local storage, so the call fails
+        ProofOfPersonhood::offchain_worker(10);
+
+        let diagnostic = ProofOfPersonhood::last_ml_diagnostic(1).unwrap();
+        assert_eq!(diagnostic.did, did);
+        assert_eq!(diagnostic.oracle_id, 1);
+        assert_eq!(diagnostic.error, b"Oracle URL not configured".to_vec());
+    });
+}
+
+#[test]
+fn malformed_oracle_response_is_reported_on_chain() {
+    let features = sample_features();
+    let (mut ext, submitted_calls) = new_offchain_test_ext_with_http(vec![testing::PendingRequest {
+        method: "POST".into(),
+        uri: "http://oracle.test/score".into(),
+        body: ProofOfPersonhood::build_ml_request_payload(&features).unwrap(),
+        response: Some(br#"{"confidence_score":80}"#.to_vec()),
+        sent: true,
+        ..Default::default()
+    }]);
+
+    ext.execute_with(|| {
+        let did = H256::repeat_byte(0x01);
+        assert_ok!(ProofOfPersonhood::register_oracle(
+            RuntimeOrigin::root(),
+            Some(1),
+            H256::repeat_byte(0x10),
+            [9u8; 32],
+            None,
+        ));
+        assert_ok!(ProofOfPersonhood::add_ml_service_key(RuntimeOrigin::root(), ocw_signing_key()));
+        PendingMLPatterns::<Test>::insert(&did, features.clone());
+        sp_io::offchain::local_storage_set(
+            StorageKind::PERSISTENT,
+            b"oracle_url_1",
+            b"http://oracle.test/score",
+        );
+
+        ProofOfPersonhood::offchain_worker(10);
+
+        // The worker's report carries the parse error to chain
+        let calls = submitted_calls();
+        assert_eq!(
+            calls,
+            vec![RuntimeCall::ProofOfPersonhood(Call::report_ml_failure {
+                oracle_id: 1,
+                did,
+                error: b"Field not found".to_vec(),
+            })]
+        );
+        assert_ok!(calls[0]
+            .clone()
+            .dispatch(RuntimeOrigin::signed(AccountId::from(ocw_signing_key()))));
+        System::assert_has_event(
+            Event::<Test>::MLServiceCallFailed {
+                did,
+                oracle_id: 1,
+                error: b"Field not found".to_vec(),
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn ml_failure_reports_require_a_trusted_key() {
+    let features = sample_features();
+    let (mut ext, submitted_calls) = new_offchain_test_ext_with_http(vec![testing::PendingRequest {
+        method: "POST".into(),
+        uri: "http://oracle.test/score".into(),
+        body: ProofOfPersonhood::build_ml_request_payload(&features).unwrap(),
+        response: Some(br#"{"confidence_score":80}"#.to_vec()),
+        sent: true,
+        ..Default::default()
+    }]);
+
+    ext.execute_with(|| {
+        let did = H256::repeat_byte(0x01);
+        assert_ok!(ProofOfPersonhood::register_oracle(
+            RuntimeOrigin::root(),
+            Some(1),
+            H256::repeat_byte(0x10),
+            [9u8; 32],
+            None,
+        ));
+        PendingMLPatterns::<Test>::insert(&did, features);
+        sp_io::offchain::local_storage_set(
+            StorageKind::PERSISTENT,
+            b"oracle_url_1",
+            b"http://oracle.test/score",
+        );
+
+        // The local key is not trusted, so the worker has nothing to sign with
+        ProofOfPersonhood::offchain_worker(10);
+        assert!(submitted_calls().is_empty());
+
+        // Nor can an arbitrary account spam failure events
+        assert_noop!(
+            ProofOfPersonhood::report_ml_failure(
+                RuntimeOrigin::signed(ALICE),
+                1,
+                did,
+                b"forged".to_vec(),
+            ),
+            Error::<Test>::MLServiceKeyNotTrusted
+        );
+        assert_ok!(ProofOfPersonhood::report_ml_failure(
+            RuntimeOrigin::signed(AccountId::from([9u8; 32])),
+            1,
+            did,
+            b"forged".to_vec(),
+        ));
+    });
+}
+
+#[test]
+fn oracle_response_must_match_pending_features() {
+    new_test_ext().execute_with(|| {
+        let did = H256::repeat_byte(0x01);
+        assert_ok!(ProofOfPersonhood::register_oracle(
+            RuntimeOrigin::root(),
+            Some(1),
+            H256::repeat_byte(0x10),
+            [9u8; 32],
+            None,
+        ));
+        let features = sample_features();
+        PendingMLPatterns::<Test>::insert(&did, features.clone());
+
+        assert_noop!(
+            ProofOfPersonhood::store_oracle_response(
+                RuntimeOrigin::none(),
+                1,
+                did,
+                80,
+                1,
+                H256::repeat_byte(0xEE),
+            ),
+            Error::<Test>::FeatureHashMismatch
+        );
+
+        assert_ok!(ProofOfPersonhood::store_oracle_response(
+            RuntimeOrigin::none(),
+            1,
+            did,
+            80,
+            1,
+            ProofOfPersonhood::features_commitment(&features),
+        ));
+    });
+}
+
+#[test]
+fn incomplete_oracle_round_expires_after_window() {
+    new_test_ext().execute_with(|| {
+        let did = H256::repeat_byte(0x01);
+        assert_ok!(ProofOfPersonhood::register_oracle(
+            RuntimeOrigin::root(),
+            Some(1),
+            H256::repeat_byte(0x10),
+            [9u8; 32],
+            None,
+        ));
+        assert_ok!(ProofOfPersonhood::set_consensus_threshold(RuntimeOrigin::root(), 2));
+        let features = sample_features();
+        PendingMLPatterns::<Test>::insert(&did, features.clone());
+        let respond = |nonce: u64| {
+            ProofOfPersonhood::store_oracle_response(
+                RuntimeOrigin::none(),
+                1,
+                did,
+                80,
+                nonce,
+                ProofOfPersonhood::features_commitment(&features),
+            )
+        };
+
+        let started_at = Timestamp::get();
+        assert_ok!(respond(1));
+        assert_eq!(ProofOfPersonhood::oracle_round_started_at(&did), Some(started_at));
+
+        // Still inside the 600 second window
+        Timestamp::set_timestamp(started_at + 600);
+        assert_noop!(respond(2), Error::<Test>::OracleAlreadyResponded);
+
+        Timestamp::set_timestamp(started_at + 601);
+        assert_ok!(respond(2));
+        System::assert_has_event(RuntimeEvent::ProofOfPersonhood(Event::ConsensusFailed {
+            did,
+            reason: b"window expired".to_vec(),
+        }));
+        assert_eq!(ProofOfPersonhood::oracle_responses(&did, 1), Some((80, started_at + 601)));
+        assert_eq!(ProofOfPersonhood::oracle_round_started_at(&did), Some(started_at + 601));
+    });
+}
+
+fn seed_guardian(did: H256, guardian: AccountId, strength: u8, established_at: u64) {
+    GuardianRelationships::<Test>::insert(
+        &did,
+        &guardian,
+        GuardianRelationship {
+            guardian: guardian.clone(),
+            relationship_strength: strength,
+            established_at,
+            interaction_count: 100,
+            bonded_stake: 500,
+        },
+    );
+}
+
+fn start_progressive_recovery(controller: AccountId, did_bytes: &[u8]) -> H256 {
+    let did = create_test_identity(controller.clone(), did_bytes);
+    seed_personhood(did, H256::repeat_byte(0x11), controller.clone());
+    assert_ok!(ProofOfPersonhood::initiate_progressive_recovery(
+        RuntimeOrigin::signed(controller),
+        did,
+        None,
+        None,
+    ));
+    did
+}
+
+#[test]
+fn remove_guardian_releases_bond() {
+    new_test_ext().execute_with(|| {
+        let did = create_test_identity(ALICE, b"did:identity:alice");
+        assert_ok!(ProofOfPersonhood::add_guardian(RuntimeOrigin::signed(ALICE), did, BOB, 5, 600));
+        assert_eq!(Balances::reserved_balance(&BOB), 600);
+
+        assert_noop!(
+            ProofOfPersonhood::remove_guardian(RuntimeOrigin::signed(BOB), did, BOB),
+            Error::<Test>::NotAuthorized
+        );
+        assert_ok!(ProofOfPersonhood::remove_guardian(RuntimeOrigin::signed(ALICE), did, BOB));
+
+        assert!(!ProofOfPersonhood::is_guardian(&did, &BOB));
+        assert_eq!(Balances::reserved_balance(&BOB), 0);
+        assert_eq!(Balances::free_balance(&BOB), INITIAL_BALANCE);
+        assert_eq!(ProofOfPersonhood::total_guardian_bond(&BOB), 0);
+        System::assert_last_event(Event::GuardianRemoved { did, guardian: BOB, released_bond: 600 }.into());
+
+        assert_noop!(
+            ProofOfPersonhood::remove_guardian(RuntimeOrigin::signed(ALICE), did, BOB),
+            Error::<Test>::GuardianNotFound
+        );
+    });
+}
+
+#[test]
+fn remove_guardian_refuses_a_guardian_voting_in_a_recovery() {
+    new_test_ext().execute_with(|| {
+        let did = start_progressive_recovery(ALICE, b"did:identity:alice");
+        assert_ok!(ProofOfPersonhood::add_guardian(RuntimeOrigin::signed(ALICE), did, BOB, 5, 600));
+        assert_ok!(ProofOfPersonhood::add_guardian(RuntimeOrigin::signed(ALICE), did, CHARLIE, 5, 600));
+        assert_ok!(ProofOfPersonhood::submit_recovery_evidence(
+            RuntimeOrigin::signed(BOB),
+            did,
+            EvidenceType::GuardianApproval { vote_strength: 5 },
+            vec![],
+        ));
+
+        assert_noop!(
+            ProofOfPersonhood::remove_guardian(RuntimeOrigin::signed(ALICE), did, BOB),
+            Error::<Test>::RecoveryInProgress
+        );
+        assert_eq!(Balances::reserved_balance(&BOB), 600);
+
+        // Guardians that haven't voted can still go
+        assert_ok!(ProofOfPersonhood::remove_guardian(RuntimeOrigin::signed(ALICE), did, CHARLIE));
+        assert_eq!(Balances::reserved_balance(&CHARLIE), 0);
+    });
+}
+
+#[test]
+fn guardian_bond_cannot_shrink_while_backing_a_recovery_vote() {
+    new_test_ext().execute_with(|| {
+        let did = start_progressive_recovery(ALICE, b"did:identity:alice");
+        assert_ok!(ProofOfPersonhood::add_guardian(RuntimeOrigin::signed(ALICE), did, BOB, 5, 800));
+        assert_ok!(ProofOfPersonhood::submit_recovery_evidence(
+            RuntimeOrigin::signed(BOB),
+            did,
+            EvidenceType::GuardianApproval { vote_strength: 5 },
+            vec![],
+        ));
+
+        assert_noop!(
+            ProofOfPersonhood::adjust_guardian_bond(RuntimeOrigin::signed(BOB), did, 100, false),
+            Error::<Test>::RecoveryInProgress
+        );
+        // Raising the stake behind the vote is still allowed
+        assert_ok!(ProofOfPersonhood::adjust_guardian_bond(RuntimeOrigin::signed(BOB), did, 100, true));
+        assert_eq!(Balances::reserved_balance(&BOB), 900);
+    });
+}
+
+#[test]
+fn recovery_evidence_clamps_delay_to_configured_minimum() {
+    new_test_ext().execute_with(|| {
+        let hour = 60 * 60;
+        BaseRecoveryDelay::set(2 * 24 * hour);
+        MinRecoveryDelay::set(hour);
+        let did = start_progressive_recovery(ALICE, b"did:identity:alice");
+        assert_eq!(ProofOfPersonhood::progressive_recoveries(&did).unwrap().finalization_delay, 2 * 24 * hour);
+        seed_guardian(did, BOB, 1, Timestamp::get());
+
+        // One strength point takes three days off, more than the whole base delay
+        assert_ok!(ProofOfPersonhood::submit_recovery_evidence(
+            RuntimeOrigin::signed(BOB),
+            did,
+            EvidenceType::GuardianApproval { vote_strength: 1 },
+            vec![],
+        ));
+
+        assert_eq!(ProofOfPersonhood::progressive_recoveries(&did).unwrap().finalization_delay, hour);
+    });
+}
+
+#[test]
+fn recovery_delay_reduction_reports_the_clamped_delta() {
+    new_test_ext().execute_with(|| {
+        let day = 24 * 60 * 60;
+        let did = start_progressive_recovery(ALICE, b"did:identity:alice");
+        let base = ProofOfPersonhood::progressive_recoveries(&did).unwrap().finalization_delay;
+        seed_guardian(did, BOB, 5, Timestamp::get());
+        let vote = |vote_strength: u8| {
+            assert_ok!(ProofOfPersonhood::submit_recovery_evidence(
+                RuntimeOrigin::signed(BOB),
+                did,
+                EvidenceType::GuardianApproval { vote_strength },
+                vec![],
+            ));
+        };
+        let reductions = || {
+            System::events()
+                .into_iter()
+                .filter_map(|record| match record.event {
+                    RuntimeEvent::ProofOfPersonhood(Event::RecoveryDelayReduced { reduction_seconds, .. }) => {
+                        Some(reduction_seconds)
+                    },
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+
+        vote(5);
+        System::assert_has_event(RuntimeEvent::ProofOfPersonhood(Event::RecoveryDelayReduced {
+            did,
+            evidence_type: EvidenceType::GuardianApproval { vote_strength: 5 },
+            reduction_seconds: 15 * day,
+            new_delay: base - 15 * day,
+        }));
+
+        // Clamping to the minimum caps what the next submission can take off
+        MinRecoveryDelay::set(base - 20 * day);
+        vote(5);
+        assert_eq!(reductions(), vec![15 * day, 5 * day]);
+        assert_eq!(ProofOfPersonhood::progressive_recoveries(&did).unwrap().finalization_delay, base - 20 * day);
+
+        // Already at the minimum: nothing changes, so nothing is reported
+        vote(5);
+        assert_eq!(reductions(), vec![15 * day, 5 * day]);
+    });
+}
+
+#[test]
+fn fresh_guardian_votes_count_only_after_minimum_age() {
+    new_test_ext().execute_with(|| {
+        let day = 24 * 60 * 60;
+        MinGuardianRelationshipAge::set(7 * day);
+        let did = start_progressive_recovery(ALICE, b"did:identity:alice");
+        let start = Timestamp::get();
+        seed_guardian(did, BOB, 1, start - day);
+
+        let vote = || {
+            assert_ok!(ProofOfPersonhood::submit_recovery_evidence(
+                RuntimeOrigin::signed(BOB),
+                did,
+                EvidenceType::GuardianApproval { vote_strength: 1 },
+                vec![],
+            ));
+            ProofOfPersonhood::progressive_recoveries(&did).unwrap().recovery_score
+        };
+
+        // Added yesterday: the vote is recorded but earns nothing
+        assert_eq!(vote(), 0);
+        Timestamp::set_timestamp(start + 5 * day);
+        assert_eq!(vote(), 0);
+
+        // Seven days old: the vote counts (plus one point of elapsed time)
+        Timestamp::set_timestamp(start + 6 * day);
+        assert_eq!(vote(), 1 + 1);
+    });
+}
+
+#[test]
+fn guardian_age_bonus_applies_only_past_threshold() {
+    new_test_ext().execute_with(|| {
+        let did = start_progressive_recovery(ALICE, b"did:identity:alice");
+        let now = Timestamp::get();
+        let threshold = GuardianAgeBonusThreshold::get();
+
+        // Just over the threshold earns the bonus, just under doesn't
+        seed_guardian(did, BOB, 1, now - threshold - 1);
+        seed_guardian(did, CHARLIE, 1, now - threshold + 1);
+
+        assert_ok!(ProofOfPersonhood::submit_recovery_evidence(
+            RuntimeOrigin::signed(BOB),
+            did,
+            EvidenceType::GuardianApproval { vote_strength: 1 },
+            vec![],
+        ));
+        assert_eq!(ProofOfPersonhood::progressive_recoveries(&did).unwrap().recovery_score, 1 + 2);
+
+        assert_ok!(ProofOfPersonhood::submit_recovery_evidence(
+            RuntimeOrigin::signed(CHARLIE),
+            did,
+            EvidenceType::GuardianApproval { vote_strength: 1 },
+            vec![],
+        ));
+        assert_eq!(ProofOfPersonhood::progressive_recoveries(&did).unwrap().recovery_score, 1 + 2 + 1);
+    });
+}
+
+#[test]
+fn guardian_score_matches_between_evidence_and_finalization() {
+    new_test_ext().execute_with(|| {
+        let did = start_progressive_recovery(ALICE, b"did:identity:alice");
+        let now = Timestamp::get();
+        seed_guardian(did, BOB, 3, now - 400 * 24 * 60 * 60);
+        seed_guardian(did, CHARLIE, 2, now);
+
+        assert_ok!(ProofOfPersonhood::submit_recovery_evidence(
+            RuntimeOrigin::signed(BOB),
+            did,
+            EvidenceType::GuardianApproval { vote_strength: 3 },
+            vec![],
+        ));
+        assert_ok!(ProofOfPersonhood::submit_recovery_evidence(
+            RuntimeOrigin::signed(CHARLIE),
+            did,
+            EvidenceType::GuardianApproval { vote_strength: 2 },
+            vec![],
+        ));
+
+        let submitted_increase = System::events()
+            .into_iter()
+            .filter_map(|record| match record.event {
+                RuntimeEvent::ProofOfPersonhood(Event::RecoveryEvidenceSubmitted {
+                    score_increase,
+                    ..
+                }) => Some(score_increase),
+                _ => None,
+            })
+            .last()
+            .unwrap();
+
+        // 3*3 + 2 (age bonus) + 2*2
+        assert_eq!(submitted_increase, 15);
+
+        // Same instant, so the time component is zero and the totals must agree
+        let recovery = ProofOfPersonhood::progressive_recoveries(&did).unwrap();
+        assert_eq!(recovery.recovery_score, submitted_increase);
+    });
+}
+
+#[test]
+fn legacy_registration_event_carries_modality() {
+    new_test_ext().execute_with(|| {
+        let did = create_test_identity(ALICE, b"did:identity:alice");
+        let nullifier = H256::repeat_byte(0x11);
+        let (commitment, proof) = uniqueness_proof(nullifier);
+
+        assert_ok!(ProofOfPersonhood::register_personhood(
+            RuntimeOrigin::signed(ALICE),
+            did,
+            nullifier,
+            commitment,
+            proof,
+        ));
+
+        System::assert_last_event(
+            Event::PersonhoodRegistered {
+                did,
+                nullifier,
+                modality: BiometricModality::Fingerprint,
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn pending_ml_patterns_rotate_across_runs() {
+    new_offchain_test_ext().execute_with(|| {
+        let dids: Vec<H256> = (0..25u64).map(H256::from_low_u64_be).collect();
+        for did in dids.iter() {
+            PendingMLPatterns::<Test>::insert(did, sample_features());
+        }
+
+        let mut served = std::collections::BTreeSet::new();
+        for _ in 0..3 {
+            let batch = ProofOfPersonhood::get_pending_ml_patterns();
+            assert!(batch.len() <= 10);
+            served.extend(batch.into_iter().map(|(did, _)| did));
+        }
+
+        // Three runs of ten cover all 25 DIDs instead of the first ten repeatedly
+        assert_eq!(served.len(), dids.len());
+    });
+}
+
+#[test]
+fn request_recovery_rejects_short_proof() {
+    new_test_ext().execute_with(|| {
+        let did = create_test_identity(ALICE, b"did:identity:alice");
+        seed_personhood(did, H256::repeat_byte(0x11), ALICE);
+
+        assert_noop!(
+            ProofOfPersonhood::request_recovery(
+                RuntimeOrigin::signed(ALICE),
+                did,
+                H256::repeat_byte(0x22),
+                H256::repeat_byte(0x33),
+                vec![7u8; 50],
+                vec![BOB, CHARLIE],
+            ),
+            Error::<Test>::InvalidRecoveryProof
+        );
+
+        // Long enough but not a valid proof: the ZK check always runs
+        assert_noop!(
+            ProofOfPersonhood::request_recovery(
+                RuntimeOrigin::signed(ALICE),
+                did,
+                H256::repeat_byte(0x22),
+                H256::repeat_byte(0x33),
+                vec![7u8; 128],
+                vec![BOB, CHARLIE],
+            ),
+            Error::<Test>::InvalidRecoveryProof
+        );
+    });
+}
+
+#[test]
+fn request_recovery_rejects_zero_commitment() {
+    new_test_ext().execute_with(|| {
+        let did = create_test_identity(ALICE, b"did:identity:alice");
+        seed_personhood(did, H256::repeat_byte(0x11), ALICE);
+
+        assert_noop!(
+            ProofOfPersonhood::request_recovery(
+                RuntimeOrigin::signed(ALICE),
+                did,
+                H256::repeat_byte(0x22),
+                H256::zero(),
+                vec![7u8; 48],
+                vec![BOB, CHARLIE],
+            ),
+            Error::<Test>::InvalidCommitment
+        );
+    });
+}
+
+#[test]
+fn finalize_recovery_rejects_nullifier_claimed_meanwhile() {
+    new_test_ext().execute_with(|| {
+        let did = create_test_identity(ALICE, b"did:identity:alice");
+        seed_personhood(did, H256::repeat_byte(0x11), ALICE);
+        let new_nullifier = H256::repeat_byte(0x22);
+
+        assert_ok!(ProofOfPersonhood::request_recovery(
+            RuntimeOrigin::signed(ALICE),
+            did,
+            new_nullifier,
+            H256::repeat_byte(0x33),
+            recovery_proof(H256::repeat_byte(0x11), new_nullifier),
+            vec![BOB, CHARLIE],
+        ));
+        assert_ok!(ProofOfPersonhood::approve_recovery(RuntimeOrigin::signed(BOB), did));
+        assert_ok!(ProofOfPersonhood::approve_recovery(RuntimeOrigin::signed(CHARLIE), did));
+
+        // Someone else registers the same nullifier before finalization
+        let other_did = create_test_identity(DAVE, b"did:identity:dave");
+        seed_personhood(other_did, new_nullifier, DAVE);
+
+        let active_at = ProofOfPersonhood::pending_recoveries(&did).unwrap().active_at;
+        Timestamp::set_timestamp(active_at);
+
+        assert_noop!(
+            ProofOfPersonhood::finalize_recovery(RuntimeOrigin::signed(ALICE), did),
+            Error::<Test>::NullifierAlreadyUsed
+        );
+        assert_eq!(ProofOfPersonhood::personhood_registry(&new_nullifier).unwrap().did, other_did);
+    });
+}
+
+/// Request a recovery of a fresh DID listing BOB, CHARLIE and DAVE, approved by `approvers`
+fn request_recovery_approved_by(approvers: &[AccountId], strengths: Option<[u8; 3]>) -> H256 {
+    let did = create_test_identity(ALICE, b"did:identity:alice");
+    seed_personhood(did, H256::repeat_byte(0x11), ALICE);
+    if let Some(strengths) = strengths {
+        for (guardian, strength) in [BOB, CHARLIE, DAVE].into_iter().zip(strengths) {
+            seed_guardian(did, guardian, strength, Timestamp::get());
+        }
+    }
+
+    let new_nullifier = H256::repeat_byte(0x22);
+    assert_ok!(ProofOfPersonhood::request_recovery(
+        RuntimeOrigin::signed(ALICE),
+        did,
+        new_nullifier,
+        H256::repeat_byte(0x33),
+        recovery_proof(H256::repeat_byte(0x11), new_nullifier),
+        vec![BOB, CHARLIE, DAVE],
+    ));
+    for approver in approvers {
+        assert_ok!(ProofOfPersonhood::approve_recovery(RuntimeOrigin::signed(approver.clone()), did));
+    }
+
+    let active_at = ProofOfPersonhood::pending_recoveries(&did).unwrap().active_at;
+    Timestamp::set_timestamp(active_at);
+    did
+}
+
+#[test]
+fn single_approval_falls_short_of_count_majority() {
+    new_test_ext().execute_with(|| {
+        // No relationships established: 2/3 of three guardians by count
+        let did = request_recovery_approved_by(&[BOB], None);
+        assert_noop!(
+            ProofOfPersonhood::finalize_recovery(RuntimeOrigin::signed(ALICE), did),
+            Error::<Test>::InsufficientGuardianApprovals
+        );
+    });
+}
+
+#[test]
+fn strong_guardian_outweighs_weaker_ones() {
+    new_test_ext().execute_with(|| {
+        // 10 of 16 strength clears the 60% threshold on its own
+        let did = request_recovery_approved_by(&[BOB], Some([10, 3, 3]));
+        assert_ok!(ProofOfPersonhood::finalize_recovery(RuntimeOrigin::signed(ALICE), did));
+        assert_eq!(ProofOfPersonhood::did_to_nullifier(&did), Some(H256::repeat_byte(0x22)));
+    });
+
+    new_test_ext().execute_with(|| {
+        // Two weak guardians make up only 6 of 16
+        let did = request_recovery_approved_by(&[CHARLIE, DAVE], Some([10, 3, 3]));
+        assert_noop!(
+            ProofOfPersonhood::finalize_recovery(RuntimeOrigin::signed(ALICE), did),
+            Error::<Test>::InsufficientGuardianApprovals
+        );
+        assert!(!ProofOfPersonhood::guardian_approvals_sufficient(&did, &[BOB, CHARLIE, DAVE], &[CHARLIE, DAVE]));
+        assert!(ProofOfPersonhood::guardian_approvals_sufficient(&did, &[BOB, CHARLIE, DAVE], &[BOB]));
+    });
+}
+
+/// Seed a progressive recovery that already satisfies the score and delay requirements
+fn seed_ready_progressive_recovery(did: H256, new_nullifier: Option<H256>, requester: AccountId) {
+    let now = Timestamp::get();
+    let old_nullifier = ProofOfPersonhood::did_to_nullifier(&did).unwrap();
+    seed_guardian(did, BOB, 10, now);
+    let delay = 7 * 24 * 60 * 60;
+
+    ProgressiveRecoveries::<Test>::insert(
+        &did,
+        ProgressiveRecoveryRequest {
+            did,
+            old_nullifier,
+            new_nullifier,
+            new_commitment: new_nullifier.map(|_| H256::repeat_byte(0x33)),
+            guardian_votes: vec![(BOB, 3)].try_into().unwrap(),
+            behavioral_confidence: 100,
+            historical_proof_strength: 100,
+            economic_stake: 0,
+            requested_at: now - delay,
+            finalization_delay: delay,
+            base_delay: delay,
+            requester,
+            recovery_score: 0,
+        },
+    );
+}
+
+#[test]
+fn finalize_progressive_recovery_rejects_nullifier_claimed_meanwhile() {
+    new_test_ext().execute_with(|| {
+        let did = create_test_identity(ALICE, b"did:identity:alice");
+        seed_personhood(did, H256::repeat_byte(0x11), ALICE);
+        let new_nullifier = H256::repeat_byte(0x22);
+        seed_ready_progressive_recovery(did, Some(new_nullifier), ALICE);
+
+        // Claimed through the binding path during the recovery window
+        NullifierClaims::<Test>::insert(&new_nullifier, true);
+
+        assert_noop!(
+            ProofOfPersonhood::finalize_progressive_recovery(RuntimeOrigin::signed(ALICE), did),
+            Error::<Test>::NullifierAlreadyUsed
+        );
+
+        NullifierClaims::<Test>::remove(&new_nullifier);
+        assert_ok!(ProofOfPersonhood::finalize_progressive_recovery(
+            RuntimeOrigin::signed(ALICE),
+            did,
+        ));
+        assert_eq!(ProofOfPersonhood::did_to_nullifier(&did), Some(new_nullifier));
+    });
+}
+
+#[test]
+fn recovery_rotates_binding_storage() {
+    new_test_ext().execute_with(|| {
+        let did = create_test_identity(ALICE, b"did:identity:alice");
+        let old_nullifier = H256::repeat_byte(0x11);
+        let (commitment, proof) = uniqueness_proof(old_nullifier);
+        assert_ok!(ProofOfPersonhood::register_primary_personhood(
+            RuntimeOrigin::signed(ALICE),
+            did,
+            old_nullifier,
+            commitment,
+            BiometricModality::Fingerprint,
+            proof,
+        ));
+
+        let new_nullifier = H256::repeat_byte(0x22);
+        assert_ok!(ProofOfPersonhood::request_recovery(
+            RuntimeOrigin::signed(ALICE),
+            did,
+            new_nullifier,
+            H256::repeat_byte(0x33),
+            recovery_proof(old_nullifier, new_nullifier),
+            vec![BOB, CHARLIE],
+        ));
+        assert_ok!(ProofOfPersonhood::approve_recovery(RuntimeOrigin::signed(BOB), did));
+        assert_ok!(ProofOfPersonhood::approve_recovery(RuntimeOrigin::signed(CHARLIE), did));
+
+        let active_at = ProofOfPersonhood::pending_recoveries(&did).unwrap().active_at;
+        Timestamp::set_timestamp(active_at);
+        assert_ok!(ProofOfPersonhood::finalize_recovery(RuntimeOrigin::signed(ALICE), did));
+
+        assert_eq!(ProofOfPersonhood::get_personhood_for_nullifier(&new_nullifier), Some(did));
+        assert_eq!(ProofOfPersonhood::get_personhood_for_nullifier(&old_nullifier), None);
+        assert!(!ProofOfPersonhood::nullifier_claims(&old_nullifier));
+        assert_eq!(
+            ProofOfPersonhood::personhood_bindings(&did).unwrap().primary_nullifier,
+            new_nullifier
+        );
+    });
+}
+
+fn register_primary(account: AccountId, did_bytes: &[u8], nullifier: H256) -> H256 {
+    let did = create_test_identity(account.clone(), did_bytes);
+    let (commitment, proof) = uniqueness_proof(nullifier);
+    assert_ok!(ProofOfPersonhood::register_primary_personhood(
+        RuntimeOrigin::signed(account),
+        did,
+        nullifier,
+        commitment,
+        BiometricModality::Fingerprint,
+        proof,
+    ));
+    did
+}
+
+fn cross_proof(
+    primary: H256,
+    new_nullifier: H256,
+    modality: BiometricModality,
+    session_id: H256,
+) -> CrossBiometricProof {
+    let zk_binding_proof = zk_proof_for(
+        pallet_zk_credentials::pallet::ProofType::CrossBiometric,
+        &[primary.as_bytes(), new_nullifier.as_bytes(), session_id.as_bytes()],
+    );
+    CrossBiometricProof {
+        nullifier_a: primary,
+        nullifier_b: new_nullifier,
+        modality_a: BiometricModality::Fingerprint,
+        modality_b: modality,
+        zk_binding_proof: zk_binding_proof.try_into().unwrap(),
+        session_id,
+        captured_at: Timestamp::get(),
+    }
+}
+
+#[test]
+fn batch_binding_binds_multiple_modalities() {
+    new_test_ext().execute_with(|| {
+        let primary = H256::repeat_byte(0x11);
+        let did = register_primary(ALICE, b"did:identity:alice", primary);
+
+        let iris = H256::repeat_byte(0x21);
+        let voice = H256::repeat_byte(0x22);
+        let bindings = vec![
+            (
+                iris,
+                H256::repeat_byte(0x31),
+                BiometricModality::Iris,
+                cross_proof(primary, iris, BiometricModality::Iris, H256::repeat_byte(0x41)),
+            ),
+            (
+                voice,
+                H256::repeat_byte(0x32),
+                BiometricModality::Voice,
+                cross_proof(primary, voice, BiometricModality::Voice, H256::repeat_byte(0x42)),
+            ),
+        ];
+
+        assert_ok!(ProofOfPersonhood::bind_additional_biometrics_batch(
+            RuntimeOrigin::signed(ALICE),
+            did,
+            bindings,
+        ));
+
+        let binding = ProofOfPersonhood::personhood_bindings(&did).unwrap();
+        assert_eq!(binding.bound_nullifiers.len(), 2);
+        assert_eq!(ProofOfPersonhood::get_personhood_for_nullifier(&iris), Some(did));
+        assert_eq!(ProofOfPersonhood::get_personhood_for_nullifier(&voice), Some(did));
+        assert!(ProofOfPersonhood::used_session_tokens(&H256::repeat_byte(0x42)).is_some());
+    });
+}
+
+#[test]
+fn binding_a_modality_twice_is_rejected() {
+    new_test_ext().execute_with(|| {
+        let primary = H256::repeat_byte(0x11);
+        let did = register_primary(ALICE, b"did:identity:alice", primary);
+        assert_eq!(
+            ProofOfPersonhood::personhood_bindings(&did).unwrap().primary_modality,
+            BiometricModality::Fingerprint
+        );
+
+        let iris = H256::repeat_byte(0x21);
+        assert_ok!(ProofOfPersonhood::bind_additional_biometric(
+            RuntimeOrigin::signed(ALICE),
+            did,
+            iris,
+            H256::repeat_byte(0x31),
+            BiometricModality::Iris,
+            cross_proof(primary, iris, BiometricModality::Iris, H256::repeat_byte(0x41)),
+        ));
+
+        let second_iris = H256::repeat_byte(0x22);
+        assert_noop!(
+            ProofOfPersonhood::bind_additional_biometric(
+                RuntimeOrigin::signed(ALICE),
+                did,
+                second_iris,
+                H256::repeat_byte(0x32),
+                BiometricModality::Iris,
+                cross_proof(primary, second_iris, BiometricModality::Iris, H256::repeat_byte(0x42)),
+            ),
+            Error::<Test>::ModalityAlreadyRegistered
+        );
+
+        // The primary's modality counts too
+        let second_fingerprint = H256::repeat_byte(0x23);
+        assert_noop!(
+            ProofOfPersonhood::bind_additional_biometric(
+                RuntimeOrigin::signed(ALICE),
+                did,
+                second_fingerprint,
+                H256::repeat_byte(0x33),
+                BiometricModality::Fingerprint,
+                cross_proof(primary, second_fingerprint, BiometricModality::Fingerprint, H256::repeat_byte(0x43)),
+            ),
+            Error::<Test>::ModalityAlreadyRegistered
+        );
+    });
+}
+
+#[test]
+fn primary_biometric_cannot_be_unbound() {
+    new_test_ext().execute_with(|| {
+        let primary = H256::repeat_byte(0x11);
+        let did = register_primary(ALICE, b"did:identity:alice", primary);
+
+        assert_noop!(
+            ProofOfPersonhood::unbind_biometric(RuntimeOrigin::signed(ALICE), did, primary),
+            Error::<Test>::CannotUnbindPrimary
+        );
+        assert_noop!(
+            ProofOfPersonhood::unbind_biometric(RuntimeOrigin::signed(ALICE), did, H256::repeat_byte(0x21)),
+            Error::<Test>::NullifierNotBound
+        );
+    });
+}
+
+#[test]
+fn unbinding_a_biometric_releases_its_nullifier_after_cooldown() {
+    new_test_ext().execute_with(|| {
+        let primary = H256::repeat_byte(0x11);
+        let did = register_primary(ALICE, b"did:identity:alice", primary);
+        let iris = H256::repeat_byte(0x21);
+        assert_ok!(ProofOfPersonhood::bind_additional_biometric(
+            RuntimeOrigin::signed(ALICE),
+            did,
+            iris,
+            H256::repeat_byte(0x31),
+            BiometricModality::Iris,
+            cross_proof(primary, iris, BiometricModality::Iris, H256::repeat_byte(0x41)),
+        ));
+
+        assert_noop!(
+            ProofOfPersonhood::unbind_biometric(RuntimeOrigin::signed(BOB), did, iris),
+            Error::<Test>::NotAuthorized
+        );
+        assert_ok!(ProofOfPersonhood::unbind_biometric(RuntimeOrigin::signed(ALICE), did, iris));
+
+        assert!(ProofOfPersonhood::personhood_bindings(&did).unwrap().bound_nullifiers.is_empty());
+        assert!(ProofOfPersonhood::biometric_bindings(&iris).is_none());
+        assert!(!ProofOfPersonhood::nullifier_claims(&iris));
+        assert_eq!(
+            ProofOfPersonhood::registration_cooldown(&iris),
+            Timestamp::get() + 6 * 30 * 24 * 60 * 60
+        );
+        System::assert_last_event(RuntimeEvent::ProofOfPersonhood(Event::BiometricUnbound {
+            did,
+            nullifier: iris,
+            modality: BiometricModality::Iris,
+        }));
+
+        // The released nullifier cannot immediately anchor another personhood
+        let bob_did = create_test_identity(BOB, b"did:identity:bob");
+        let (commitment, proof) = uniqueness_proof(iris);
+        assert_noop!(
+            ProofOfPersonhood::register_primary_personhood(
+                RuntimeOrigin::signed(BOB),
+                bob_did,
+                iris,
+                commitment,
+                BiometricModality::Iris,
+                proof,
+            ),
+            Error::<Test>::RegistrationTooSoon
+        );
+    });
+}
+
+#[test]
+fn bound_modalities_list_the_primary_and_every_binding() {
+    new_test_ext().execute_with(|| {
+        let primary = H256::repeat_byte(0x11);
+        let did = register_primary(ALICE, b"did:identity:alice", primary);
+        let iris = H256::repeat_byte(0x21);
+        let face = H256::repeat_byte(0x22);
+        assert_ok!(ProofOfPersonhood::bind_additional_biometric(
+            RuntimeOrigin::signed(ALICE),
+            did,
+            iris,
+            H256::repeat_byte(0x31),
+            BiometricModality::Iris,
+            cross_proof(primary, iris, BiometricModality::Iris, H256::repeat_byte(0x41)),
+        ));
+        assert_ok!(ProofOfPersonhood::bind_additional_biometric(
+            RuntimeOrigin::signed(ALICE),
+            did,
+            face,
+            H256::repeat_byte(0x32),
+            BiometricModality::FaceGeometry,
+            cross_proof(primary, face, BiometricModality::FaceGeometry, H256::repeat_byte(0x42)),
+        ));
+
+        assert_eq!(
+            ProofOfPersonhood::get_bound_modalities(&did),
+            vec![
+                (primary, BiometricModality::Fingerprint),
+                (iris, BiometricModality::Iris),
+                (face, BiometricModality::FaceGeometry),
+            ]
+        );
+        assert!(ProofOfPersonhood::get_bound_modalities(&H256::repeat_byte(0x99)).is_empty());
+    });
+}
+
+#[test]
+fn session_token_usage_is_queryable() {
+    new_test_ext().execute_with(|| {
+        let primary = H256::repeat_byte(0x11);
+        let did = register_primary(ALICE, b"did:identity:alice", primary);
+        let session_id = H256::repeat_byte(0x41);
+        assert!(!ProofOfPersonhood::is_session_token_used(&session_id));
+
+        let iris = H256::repeat_byte(0x21);
+        assert_ok!(ProofOfPersonhood::bind_additional_biometric(
+            RuntimeOrigin::signed(ALICE),
+            did,
+            iris,
+            H256::repeat_byte(0x31),
+            BiometricModality::Iris,
+            cross_proof(primary, iris, BiometricModality::Iris, session_id),
+        ));
+
+        assert!(ProofOfPersonhood::is_session_token_used(&session_id));
+        assert!(!ProofOfPersonhood::is_session_token_used(&H256::repeat_byte(0x42)));
+    });
+}
+
+#[test]
+fn dev_mode_binds_biometrics_without_zk_proofs() {
+    new_test_ext().execute_with(|| {
+        DevSkipZkVerification::set(true);
+
+        let did = create_test_identity(ALICE, b"did:identity:alice");
+        let primary = H256::repeat_byte(0x11);
+        let salt = [0x5Au8; 32];
+        let mut preimage = primary.as_bytes().to_vec();
+        preimage.extend_from_slice(&salt);
+        let commitment = H256::from(sp_io::hashing::blake2_256(&preimage));
+        let mut proof = salt.to_vec();
+        proof.extend_from_slice(&[0xAB; 64]);
+
+        assert_ok!(ProofOfPersonhood::register_primary_personhood(
+            RuntimeOrigin::signed(ALICE),
+            did,
+            primary,
+            commitment,
+            BiometricModality::Fingerprint,
+            proof,
+        ));
+
+        let iris = H256::repeat_byte(0x21);
+        let session_id = H256::repeat_byte(0x41);
+        let junk_proof = CrossBiometricProof {
+            nullifier_a: primary,
+            nullifier_b: iris,
+            modality_a: BiometricModality::Fingerprint,
+            modality_b: BiometricModality::Iris,
+            zk_binding_proof: vec![0xCD; 128].try_into().unwrap(),
+            session_id,
+            captured_at: Timestamp::get(),
+        };
+        assert_ok!(ProofOfPersonhood::bind_additional_biometric(
+            RuntimeOrigin::signed(ALICE),
+            did,
+            iris,
+            H256::repeat_byte(0x31),
+            BiometricModality::Iris,
+            junk_proof.clone(),
+        ));
+        assert_eq!(ProofOfPersonhood::get_personhood_for_nullifier(&iris), Some(did));
+        assert!(ProofOfPersonhood::is_session_token_used(&session_id));
+
+        // Non-ZK checks still apply in dev mode
+        let voice = H256::repeat_byte(0x22);
+        assert_noop!(
+            ProofOfPersonhood::bind_additional_biometric(
+                RuntimeOrigin::signed(ALICE),
+                did,
+                voice,
+                H256::repeat_byte(0x32),
+                BiometricModality::Voice,
+                CrossBiometricProof { nullifier_b: voice, ..junk_proof.clone() },
+            ),
+            Error::<Test>::SessionTokenUsed
+        );
+
+        // The same junk proof is rejected once real verification is back on
+        DevSkipZkVerification::set(false);
+        assert_noop!(
+            ProofOfPersonhood::bind_additional_biometric(
+                RuntimeOrigin::signed(ALICE),
+                did,
+                voice,
+                H256::repeat_byte(0x32),
+                BiometricModality::Voice,
+                CrossBiometricProof { nullifier_b: voice, session_id: H256::repeat_byte(0x42), ..junk_proof },
+            ),
+            Error::<Test>::InvalidCrossBiometricProof
+        );
+    });
+}
+
+#[test]
+fn session_validity_window_is_configurable() {
+    new_test_ext().execute_with(|| {
+        SessionValidityWindow::set(600);
+        Timestamp::set_timestamp(10_000);
+        let primary = H256::repeat_byte(0x11);
+        let did = register_primary(ALICE, b"did:identity:alice", primary);
+
+        let voice = H256::repeat_byte(0x22);
+        let mut stale = cross_proof(primary, voice, BiometricModality::Voice, H256::repeat_byte(0x42));
+        stale.captured_at = Timestamp::get() - 600;
+        assert_noop!(
+            ProofOfPersonhood::bind_additional_biometric(
+                RuntimeOrigin::signed(ALICE),
+                did,
+                voice,
+                H256::repeat_byte(0x32),
+                BiometricModality::Voice,
+                stale,
+            ),
+            Error::<Test>::SessionTokenExpired
+        );
+
+        // Older than the default 5 minutes but inside the configured window
+        let iris = H256::repeat_byte(0x21);
+        let mut fresh = cross_proof(primary, iris, BiometricModality::Iris, H256::repeat_byte(0x41));
+        fresh.captured_at = Timestamp::get() - 599;
+        assert_ok!(ProofOfPersonhood::bind_additional_biometric(
+            RuntimeOrigin::signed(ALICE),
+            did,
+            iris,
+            H256::repeat_byte(0x31),
+            BiometricModality::Iris,
+            fresh,
+        ));
+        assert_eq!(ProofOfPersonhood::get_personhood_for_nullifier(&iris), Some(did));
+    });
+}
+
+#[test]
+fn behavioral_store_threshold_controls_sample_persistence() {
+    new_test_ext().execute_with(|| {
+        let did = H256::repeat_byte(0x01);
+        let pattern = sample_features().encode();
+
+        // Three identical samples build a baseline; the fourth matches at 91% confidence
+        for _ in 0..3 {
+            assert_ok!(ProofOfPersonhood::verify_behavioral_pattern(&did, &pattern, false));
+        }
+        assert_eq!(BehavioralPatternSamples::<Test>::get(&did).len(), 3);
+
+        BehavioralStoreThreshold::set(95);
+        assert_eq!(ProofOfPersonhood::verify_behavioral_pattern(&did, &pattern, false).unwrap(), 91);
+        assert_eq!(BehavioralPatternSamples::<Test>::get(&did).len(), 3);
+
+        BehavioralStoreThreshold::set(90);
+        assert_eq!(ProofOfPersonhood::verify_behavioral_pattern(&did, &pattern, false).unwrap(), 91);
+        assert_eq!(BehavioralPatternSamples::<Test>::get(&did).len(), 4);
+
+    });
+}
+
+#[test]
+fn similarity_weight_shifts_match_confidence() {
+    new_test_ext().execute_with(|| {
+        let did = create_test_identity(ALICE, b"did:identity:alice");
+        let pattern = sample_features().encode();
+        for _ in 0..3 {
+            assert_ok!(ProofOfPersonhood::verify_behavioral_pattern(&did, &pattern, false));
+        }
+        let envelope = BehavioralEnvelopes::<Test>::get(&did).unwrap();
+
+        // Distance 40 with 4 samples: similarity 60, sample tier 85
+        assert_eq!(ProofOfPersonhood::calculate_match_confidence(40, Some(&envelope), 4), 67);
+
+        BehavioralSimilarityWeight::set(50);
+        assert_eq!(ProofOfPersonhood::calculate_match_confidence(40, Some(&envelope), 4), 72);
+
+        BehavioralSimilarityWeight::set(100);
+        assert_eq!(ProofOfPersonhood::calculate_match_confidence(40, Some(&envelope), 4), 60);
+    });
+}
+
+#[test]
+fn missing_envelope_with_samples_scores_conservatively() {
+    new_test_ext().execute_with(|| {
+        let did = create_test_identity(ALICE, b"did:identity:alice");
+        let pattern = sample_features().encode();
+        for _ in 0..3 {
+            assert_ok!(ProofOfPersonhood::verify_behavioral_pattern(&did, &pattern, false));
+        }
+        BehavioralEnvelopes::<Test>::remove(&did);
+
+        // Identical features: similarity 100 blended with the lowest sample tier (50)
+        assert_eq!(ProofOfPersonhood::verify_behavioral_pattern(&did, &pattern, true).unwrap(), 85);
+        assert!(BehavioralEnvelopes::<Test>::get(&did).is_none());
+    });
+}
+
+#[test]
+fn clearing_behavioral_data_resets_baseline_and_scores() {
+    new_test_ext().execute_with(|| {
+        let did = create_test_identity(ALICE, b"did:identity:alice");
+        let pattern = sample_features().encode();
+        for _ in 0..3 {
+            assert_ok!(ProofOfPersonhood::verify_behavioral_pattern(&did, &pattern, false));
+        }
+        MLScores::<Test>::insert(&did, (75u8, 0u64));
+        PendingMLPatterns::<Test>::insert(&did, sample_features());
+        OracleResponses::<Test>::insert(&did, 1, (80u8, 0u64));
+        OracleResponses::<Test>::insert(&did, 2, (82u8, 0u64));
+        OracleRoundStartedAt::<Test>::insert(&did, 0u64);
+        assert!(ProofOfPersonhood::behavioral_baseline_ready(&did));
+
+        // Only the controller or governance may erase
+        assert_noop!(
+            ProofOfPersonhood::clear_behavioral_data(RuntimeOrigin::signed(BOB), did),
+            Error::<Test>::NotAuthorized
+        );
+
+        assert_ok!(ProofOfPersonhood::clear_behavioral_data(RuntimeOrigin::signed(ALICE), did));
+        assert!(!ProofOfPersonhood::behavioral_baseline_ready(&did));
+        assert!(ProofOfPersonhood::ml_scores(&did).is_none());
+        assert!(ProofOfPersonhood::pending_ml_patterns(&did).is_none());
+        assert!(ProofOfPersonhood::score_statistics(&did).is_none());
+        assert_eq!(OracleResponses::<Test>::iter_prefix(&did).count(), 0);
+        assert!(!OracleRoundStartedAt::<Test>::contains_key(&did));
+        System::assert_last_event(Event::BehavioralDataCleared { did }.into());
+
+        assert_ok!(ProofOfPersonhood::clear_behavioral_data(RuntimeOrigin::root(), did));
+    });
+}
+
+#[test]
+fn recompute_envelope_corrects_incremental_drift() {
+    new_test_ext().execute_with(|| {
+        let did = H256::repeat_byte(0x18);
+        let speeds = [60u32, 69, 69, 69, 69, 69, 69, 69, 69, 69];
+
+        for speed in speeds {
+            let features = BehavioralFeatures { typing_speed_wpm: speed, ..sample_features() };
+            BehavioralPatternSamples::<Test>::mutate(&did, |samples| {
+                samples
+                    .try_push(StoredBehavioralPattern {
+                        features: features.clone(),
+                        recorded_at: 0,
+                        sample_count: 1,
+                        confidence_score: 100,
+                    })
+                    .unwrap();
+            });
+            assert_ok!(ProofOfPersonhood::update_behavioral_envelope(&did, &features));
+        }
+
+        // Truncating incremental means drift below the true mean of 68
+        assert_eq!(ProofOfPersonhood::behavioral_envelopes(&did).unwrap().mean_typing_speed, 66);
+
+        assert_ok!(ProofOfPersonhood::recompute_behavioral_envelope(RuntimeOrigin::signed(BOB), did));
+
+        let envelope = ProofOfPersonhood::behavioral_envelopes(&did).unwrap();
+        assert_eq!(envelope.mean_typing_speed, 68);
+        assert_eq!(envelope.std_dev_typing_speed, 200);
+        assert_eq!(envelope.min_typing_speed, 64);
+        assert_eq!(envelope.max_typing_speed, 72);
+        assert_eq!(envelope.mean_key_hold_time, 100);
+        assert_eq!(envelope.std_dev_error_rate, 0);
+        assert_eq!(envelope.samples_count, 10);
+        System::assert_last_event(Event::EnvelopeUpdated { did, samples_count: 10 }.into());
+
+        assert_noop!(
+            ProofOfPersonhood::recompute_behavioral_envelope(RuntimeOrigin::signed(BOB), H256::repeat_byte(0x19)),
+            Error::<Test>::PatternNotFound
+        );
+    });
+}
+
+#[test]
+fn envelope_bounds_narrow_for_tightly_clustered_samples() {
+    new_test_ext().execute_with(|| {
+        let did = H256::repeat_byte(0x1a);
+        let hold_times = [100u32, 101, 99, 100, 100, 101, 99, 100, 100, 100];
+
+        for hold_time in hold_times {
+            let features = BehavioralFeatures { avg_key_hold_time_ms: hold_time, ..sample_features() };
+            BehavioralPatternSamples::<Test>::mutate(&did, |samples| {
+                samples
+                    .try_push(StoredBehavioralPattern {
+                        features: features.clone(),
+                        recorded_at: 0,
+                        sample_count: 1,
+                        confidence_score: 100,
+                    })
+                    .unwrap();
+            });
+            assert_ok!(ProofOfPersonhood::update_behavioral_envelope(&did, &features));
+        }
+
+        let envelope = ProofOfPersonhood::behavioral_envelopes(&did).unwrap();
+        assert!(envelope.std_dev_key_hold_time < 2000);
+        assert_eq!(envelope.std_dev_transition_time, 0);
+        assert_eq!(envelope.std_dev_error_rate, 0);
+        // Well inside the initial 80..=120 window
+        assert!(envelope.min_key_hold_time > 80);
+        assert!(envelope.max_key_hold_time < 120);
+        assert_eq!(envelope.min_transition_time, envelope.mean_transition_time);
+        assert_eq!(envelope.max_transition_time, envelope.mean_transition_time);
+    });
+}
+
+#[test]
+fn behavioral_evidence_without_prior_baseline_contributes_nothing() {
+    new_test_ext().execute_with(|| {
+        let did = start_progressive_recovery(ALICE, b"did:identity:alice");
+        let score_before = ProofOfPersonhood::progressive_recoveries(&did).unwrap().recovery_score;
+
+        assert_ok!(ProofOfPersonhood::submit_recovery_evidence(
+            RuntimeOrigin::signed(BOB),
+            did,
+            EvidenceType::BehavioralBiometric,
+            sample_features().encode(),
+        ));
+
+        let recovery = ProofOfPersonhood::progressive_recoveries(&did).unwrap();
+        assert_eq!(recovery.behavioral_confidence, 0);
+        assert_eq!(recovery.recovery_score, score_before);
+        assert!(BehavioralPatternSamples::<Test>::get(&did).is_empty());
+        assert!(!ProofOfPersonhood::behavioral_baseline_ready(&did));
+        System::assert_has_event(
+            Event::BehavioralEvidenceRejected { did, reason: b"no prior behavioral baseline".to_vec() }.into(),
+        );
+    });
+}
+
+#[test]
+fn recovery_behavioral_evidence_does_not_modify_baseline() {
+    new_test_ext().execute_with(|| {
+        let did = start_progressive_recovery(ALICE, b"did:identity:alice");
+        let pattern = sample_features().encode();
+
+        // Without a baseline, recovery evidence must not seed one
+        assert_ok!(ProofOfPersonhood::submit_recovery_evidence(
+            RuntimeOrigin::signed(BOB),
+            did,
+            EvidenceType::BehavioralBiometric,
+            pattern.clone(),
+        ));
+        assert!(BehavioralPatternSamples::<Test>::get(&did).is_empty());
+        assert!(BehavioralEnvelopes::<Test>::get(&did).is_none());
+
+        for _ in 0..3 {
+            assert_ok!(ProofOfPersonhood::verify_behavioral_pattern(&did, &pattern, false));
+        }
+        let samples = BehavioralPatternSamples::<Test>::get(&did);
+        let envelope = BehavioralEnvelopes::<Test>::get(&did);
+
+        assert_ok!(ProofOfPersonhood::submit_recovery_evidence(
+            RuntimeOrigin::signed(BOB),
+            did,
+            EvidenceType::BehavioralBiometric,
+            pattern,
+        ));
+        assert_eq!(BehavioralPatternSamples::<Test>::get(&did), samples);
+        assert_eq!(BehavioralEnvelopes::<Test>::get(&did), envelope);
+        assert_eq!(ProofOfPersonhood::progressive_recoveries(&did).unwrap().behavioral_confidence, 91);
+    });
+}
+
+#[test]
+fn controller_cache_follows_registration_and_recovery() {
+    new_test_ext().execute_with(|| {
+        let did = register_primary(ALICE, b"did:identity:alice", H256::repeat_byte(0x11));
+        assert_eq!(ProofOfPersonhood::controller_to_did(&ALICE), Some(did));
+        assert_eq!(ProofOfPersonhood::identity_of_account(&ALICE).unwrap().0, did);
+
+        seed_ready_progressive_recovery(did, Some(H256::repeat_byte(0x22)), CHARLIE);
+        assert_ok!(ProofOfPersonhood::finalize_progressive_recovery(
+            RuntimeOrigin::signed(CHARLIE),
+            did,
+        ));
+
+        assert_eq!(ProofOfPersonhood::controller_to_did(&ALICE), None);
+        assert_eq!(ProofOfPersonhood::controller_to_did(&CHARLIE), Some(did));
+        // The registry still lists ALICE as controller, so lookups fall back to it
+        assert_eq!(ProofOfPersonhood::identity_of_account(&ALICE).unwrap().0, did);
+        assert!(ProofOfPersonhood::identity_of_account(&CHARLIE).is_none());
+    });
+}
+
+#[test]
+fn inactive_identity_cannot_submit_fraud_challenge() {
+    new_test_ext().execute_with(|| {
+        let target = create_test_identity(ALICE, b"did:identity:alice");
+        MLScores::<Test>::insert(&target, (90u8, Timestamp::get()));
+        create_test_identity(BOB, b"did:identity:bob");
+        assert_ok!(IdentityRegistry::deactivate_identity(RuntimeOrigin::signed(BOB)));
+
+        assert_noop!(
+            ProofOfPersonhood::submit_fraud_challenge(
+                RuntimeOrigin::signed(BOB),
+                target,
+                vec![1, 2, 3],
+                40,
+            ),
+            Error::<Test>::NotAuthorized
+        );
+    });
+}
+
+#[test]
+fn historical_delay_reduction_scales_with_strength() {
+    new_test_ext().execute_with(|| {
+        // MinHistoricalStrength is 90 in the mock
+        assert_eq!(ProofOfPersonhood::historical_delay_reduction(90), 0);
+        let at_91 = ProofOfPersonhood::historical_delay_reduction(91);
+        let at_92 = ProofOfPersonhood::historical_delay_reduction(92);
+        assert!(at_91 > 0);
+        assert!(at_92 > at_91);
+        assert_eq!(ProofOfPersonhood::historical_delay_reduction(100), 45 * 24 * 60 * 60);
+    });
+}
+
+#[test]
+fn challenges_in_same_block_get_distinct_ids() {
+    new_test_ext().execute_with(|| {
+        let target = create_test_identity(ALICE, b"did:identity:alice");
+        MLScores::<Test>::insert(&target, (90u8, Timestamp::get()));
+        create_test_identity(BOB, b"did:identity:bob");
+
+        for _ in 0..2 {
+            assert_ok!(ProofOfPersonhood::submit_fraud_challenge(
+                RuntimeOrigin::signed(BOB),
+                target,
+                vec![1, 2, 3],
+                40,
+            ));
+        }
+
+        assert_eq!(FraudChallenges::<Test>::iter().count(), 2);
+        assert_eq!(ChallengeBonds::<Test>::iter().count(), 2);
+        assert_eq!(ProofOfPersonhood::challenger_nonces(&BOB), 2);
+        assert_eq!(Balances::reserved_balance(&BOB), 2 * 5 * 500);
+    });
+}
+
+fn submit_challenge(challenger: AccountId, target: H256) -> H256 {
+    assert_ok!(ProofOfPersonhood::submit_fraud_challenge(
+        RuntimeOrigin::signed(challenger),
+        target,
+        vec![1, 2, 3],
+        40,
+    ));
+    System::events()
+        .into_iter()
+        .rev()
+        .find_map(|record| match record.event {
+            RuntimeEvent::ProofOfPersonhood(Event::FraudChallengeSubmitted { challenge_id, .. }) => {
+                Some(challenge_id)
+            }
+            _ => None,
+        })
+        .unwrap()
+}
+
+#[test]
+fn challenge_resolution_outcomes_handle_bond() {
+    new_test_ext().execute_with(|| {
+        let target = create_test_identity(ALICE, b"did:identity:alice");
+        MLScores::<Test>::insert(&target, (90u8, Timestamp::get()));
+        create_test_identity(BOB, b"did:identity:bob");
+        let bond = 5 * 500;
+
+        let upheld = submit_challenge(BOB, target);
+        assert_ok!(ProofOfPersonhood::resolve_fraud_challenge(
+            RuntimeOrigin::root(),
+            upheld,
+            ChallengeResolution::Upheld,
+        ));
+        assert_eq!(ProofOfPersonhood::fraud_challenges(&upheld).unwrap().status, ChallengeStatus::Upheld);
+        assert_eq!(ProofOfPersonhood::ml_scores(&target).unwrap().0, 40);
+        assert_eq!(Balances::reserved_balance(&BOB), 0);
+        assert_eq!(Balances::free_balance(&BOB), INITIAL_BALANCE);
+
+        let good_faith = submit_challenge(BOB, target);
+        assert_ok!(ProofOfPersonhood::resolve_fraud_challenge(
+            RuntimeOrigin::root(),
+            good_faith,
+            ChallengeResolution::DismissedGoodFaith,
+        ));
+        assert_eq!(ProofOfPersonhood::fraud_challenges(&good_faith).unwrap().status, ChallengeStatus::Dismissed);
+        assert_eq!(Balances::reserved_balance(&BOB), 0);
+        assert_eq!(Balances::free_balance(&BOB), INITIAL_BALANCE);
+
+        let malicious = submit_challenge(BOB, target);
+        assert_ok!(ProofOfPersonhood::resolve_fraud_challenge(
+            RuntimeOrigin::root(),
+            malicious,
+            ChallengeResolution::DismissedMalicious,
+        ));
+        assert_eq!(ProofOfPersonhood::fraud_challenges(&malicious).unwrap().status, ChallengeStatus::Dismissed);
+        assert_eq!(Balances::reserved_balance(&BOB), 0);
+        assert_eq!(Balances::free_balance(&BOB), INITIAL_BALANCE - bond);
+    });
+}
+
+#[test]
+fn resolving_challenge_clears_bond_and_reconciles_reserve() {
+    new_test_ext().execute_with(|| {
+        let target = create_test_identity(ALICE, b"did:identity:alice");
+        MLScores::<Test>::insert(&target, (90u8, Timestamp::get()));
+        create_test_identity(BOB, b"did:identity:bob");
+        let bond = 5 * 500;
+
+        let first = submit_challenge(BOB, target);
+        let second = submit_challenge(BOB, target);
+        assert_eq!(ProofOfPersonhood::challenge_bonds(&first), bond);
+        assert_eq!(Balances::reserved_balance(&BOB), 2 * bond);
+
+        assert_ok!(ProofOfPersonhood::resolve_fraud_challenge(
+            RuntimeOrigin::root(),
+            first,
+            ChallengeResolution::DismissedGoodFaith,
+        ));
+        assert!(!ChallengeBonds::<Test>::contains_key(&first));
+        assert_eq!(Balances::reserved_balance(&BOB), bond);
+        assert_eq!(Balances::free_balance(&BOB), INITIAL_BALANCE - bond);
+
+        // A bond no longer backed by the reserve is rejected rather than mis-accounted
+        Balances::unreserve(&BOB, bond);
+        assert_noop!(
+            ProofOfPersonhood::resolve_fraud_challenge(
+                RuntimeOrigin::root(),
+                second,
+                ChallengeResolution::DismissedMalicious,
+            ),
+            Error::<Test>::ChallengeBondMismatch
+        );
+
+        // A challenge without a recorded bond cannot be resolved
+        ChallengeBonds::<Test>::remove(&second);
+        assert_noop!(
+            ProofOfPersonhood::resolve_fraud_challenge(
+                RuntimeOrigin::root(),
+                second,
+                ChallengeResolution::Upheld,
+            ),
+            Error::<Test>::ChallengeBondMissing
+        );
+        assert_eq!(Balances::free_balance(&BOB), INITIAL_BALANCE);
+    });
+}
+
+#[test]
+fn challenge_votes_are_counted_once_per_person() {
+    new_test_ext().execute_with(|| {
+        let target = create_test_identity(ALICE, b"did:identity:alice");
+        MLScores::<Test>::insert(&target, (90u8, Timestamp::get()));
+        create_test_identity(BOB, b"did:identity:bob");
+        register_primary(CHARLIE, b"did:identity:charlie", H256::repeat_byte(0x31));
+        create_test_identity(DAVE, b"did:identity:dave");
+
+        let challenge_id = submit_challenge(BOB, target);
+        assert_ok!(ProofOfPersonhood::vote_on_challenge(
+            RuntimeOrigin::signed(CHARLIE),
+            challenge_id,
+            true,
+        ));
+        assert_noop!(
+            ProofOfPersonhood::vote_on_challenge(RuntimeOrigin::signed(CHARLIE), challenge_id, false),
+            Error::<Test>::AlreadyVotedOnChallenge
+        );
+
+        // Identities without registered personhood, and the challenger itself, cannot vote
+        assert_noop!(
+            ProofOfPersonhood::vote_on_challenge(RuntimeOrigin::signed(DAVE), challenge_id, true),
+            Error::<Test>::NotChallengeVoter
+        );
+        assert_noop!(
+            ProofOfPersonhood::vote_on_challenge(RuntimeOrigin::signed(BOB), challenge_id, true),
+            Error::<Test>::NotChallengeVoter
+        );
+
+        let challenge = ProofOfPersonhood::fraud_challenges(&challenge_id).unwrap();
+        assert_eq!((challenge.votes_for, challenge.votes_against), (1, 0));
+        assert_eq!(challenge.status, ChallengeStatus::Pending);
+        assert_eq!(ProofOfPersonhood::challenge_votes(&challenge_id, &CHARLIE), Some(true));
+        System::assert_has_event(RuntimeEvent::ProofOfPersonhood(Event::ChallengeVoted {
+            challenge_id,
+            voter: CHARLIE,
+            vote_for: true,
+        }));
+    });
+}
+
+#[test]
+fn guardian_bond_cannot_shrink_while_its_challenge_vote_is_open() {
+    new_test_ext().execute_with(|| {
+        let target = create_test_identity(ALICE, b"did:identity:alice");
+        MLScores::<Test>::insert(&target, (90u8, Timestamp::get()));
+        create_test_identity(BOB, b"did:identity:bob");
+        register_primary(CHARLIE, b"did:identity:charlie", H256::repeat_byte(0x31));
+        assert_ok!(ProofOfPersonhood::add_guardian(RuntimeOrigin::signed(ALICE), target, CHARLIE, 5, 800));
+
+        let challenge_id = submit_challenge(BOB, target);
+        assert_ok!(ProofOfPersonhood::vote_on_challenge(
+            RuntimeOrigin::signed(CHARLIE),
+            challenge_id,
+            true,
+        ));
+        assert_noop!(
+            ProofOfPersonhood::adjust_guardian_bond(RuntimeOrigin::signed(CHARLIE), target, 100, false),
+            Error::<Test>::GuardianVoteOnOpenChallenge
+        );
+
+        assert_ok!(ProofOfPersonhood::resolve_fraud_challenge(
+            RuntimeOrigin::root(),
+            challenge_id,
+            ChallengeResolution::DismissedGoodFaith,
+        ));
+        assert_ok!(ProofOfPersonhood::adjust_guardian_bond(RuntimeOrigin::signed(CHARLIE), target, 100, false));
+        assert_eq!(Balances::reserved_balance(&CHARLIE), 700);
+        assert!(!VoterChallenges::<Test>::contains_key(&CHARLIE, &challenge_id));
+    });
+}
+
+#[test]
+fn open_challenge_votes_per_voter_are_capped() {
+    new_test_ext().execute_with(|| {
+        let target = create_test_identity(ALICE, b"did:identity:alice");
+        MLScores::<Test>::insert(&target, (90u8, Timestamp::get()));
+        create_test_identity(BOB, b"did:identity:bob");
+        register_primary(CHARLIE, b"did:identity:charlie", H256::repeat_byte(0x31));
+        assert_ok!(ProofOfPersonhood::add_guardian(RuntimeOrigin::signed(ALICE), target, CHARLIE, 5, 800));
+
+        // Fill CHARLIE's index with votes on copies of one open challenge
+        let template = ProofOfPersonhood::fraud_challenges(&submit_challenge(BOB, target)).unwrap();
+        let open: Vec<H256> = (0..MAX_OPEN_CHALLENGE_VOTES)
+            .map(|i| H256::from_low_u64_be(0xC0_0000 + i as u64))
+            .collect();
+        for challenge_id in open.iter() {
+            FraudChallenges::<Test>::insert(challenge_id, template.clone());
+            VoterChallenges::<Test>::insert(&CHARLIE, challenge_id, ());
+        }
+
+        let next = submit_challenge(BOB, target);
+        assert_noop!(
+            ProofOfPersonhood::vote_on_challenge(RuntimeOrigin::signed(CHARLIE), next, true),
+            Error::<Test>::TooManyOpenChallengeVotes
+        );
+
+        // Settling one frees a slot, and its entry is pruned on the next vote
+        FraudChallenges::<Test>::mutate(&open[0], |challenge| {
+            challenge.as_mut().unwrap().status = ChallengeStatus::Dismissed;
+        });
+        assert_ok!(ProofOfPersonhood::vote_on_challenge(RuntimeOrigin::signed(CHARLIE), next, true));
+        assert!(!VoterChallenges::<Test>::contains_key(&CHARLIE, &open[0]));
+        assert_eq!(
+            VoterChallenges::<Test>::iter_key_prefix(&CHARLIE).count(),
+            MAX_OPEN_CHALLENGE_VOTES as usize
+        );
+
+        // A bond reduction never walks past the cap, even over entries indexed before it
+        for challenge_id in open.iter() {
+            FraudChallenges::<Test>::mutate(challenge_id, |challenge| {
+                challenge.as_mut().unwrap().status = ChallengeStatus::Dismissed;
+            });
+        }
+        VoterChallenges::<Test>::insert(&CHARLIE, H256::from_low_u64_be(0xC1_0000), ());
+        VoterChallenges::<Test>::insert(&CHARLIE, H256::from_low_u64_be(0xC1_0001), ());
+        assert_noop!(
+            ProofOfPersonhood::adjust_guardian_bond(RuntimeOrigin::signed(CHARLIE), target, 100, false),
+            Error::<Test>::TooManyOpenChallengeVotes
+        );
+    });
+}
+
+#[test]
+fn challenge_resolves_once_vote_quorum_and_margin_are_reached() {
+    new_test_ext().execute_with(|| {
+        let target = create_test_identity(ALICE, b"did:identity:alice");
+        MLScores::<Test>::insert(&target, (90u8, Timestamp::get()));
+        create_test_identity(BOB, b"did:identity:bob");
+        register_primary(CHARLIE, b"did:identity:charlie", H256::repeat_byte(0x31));
+        register_primary(DAVE, b"did:identity:dave", H256::repeat_byte(0x41));
+        let vote = |who: AccountId, challenge_id: H256, vote_for: bool| {
+            assert_ok!(ProofOfPersonhood::vote_on_challenge(
+                RuntimeOrigin::signed(who),
+                challenge_id,
+                vote_for,
+            ));
+        };
+
+        // The mock quorum is two votes; a split vote has no margin and stays open
+        let split = submit_challenge(BOB, target);
+        vote(CHARLIE, split, true);
+        vote(DAVE, split, false);
+        assert_eq!(ProofOfPersonhood::fraud_challenges(&split).unwrap().status, ChallengeStatus::Pending);
+
+        let upheld = submit_challenge(BOB, target);
+        vote(CHARLIE, upheld, true);
+        assert_eq!(ProofOfPersonhood::fraud_challenges(&upheld).unwrap().status, ChallengeStatus::Pending);
+        vote(DAVE, upheld, true);
+        assert_eq!(ProofOfPersonhood::fraud_challenges(&upheld).unwrap().status, ChallengeStatus::Upheld);
+        assert_eq!(ProofOfPersonhood::ml_scores(&target).unwrap().0, 40);
+        assert!(!ChallengeBonds::<Test>::contains_key(&upheld));
+
+        let dismissed = submit_challenge(BOB, target);
+        vote(CHARLIE, dismissed, false);
+        vote(DAVE, dismissed, false);
+        assert_eq!(ProofOfPersonhood::fraud_challenges(&dismissed).unwrap().status, ChallengeStatus::Dismissed);
+
+        // Only the still-open split challenge keeps its bond reserved
+        assert_eq!(Balances::reserved_balance(&BOB), 5 * 500);
+        assert_noop!(
+            ProofOfPersonhood::resolve_fraud_challenge(
+                RuntimeOrigin::root(),
+                upheld,
+                ChallengeResolution::DismissedMalicious,
+            ),
+            Error::<Test>::ChallengeAlreadyResolved
+        );
+    });
+}
+
+#[test]
+fn oracle_ids_are_allocated_up_to_the_active_cap() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(ProofOfPersonhood::register_oracle(
+            RuntimeOrigin::root(),
+            Some(0),
+            H256::repeat_byte(0x10),
+            [1u8; 32],
+            None,
+        ));
+        assert_ok!(ProofOfPersonhood::register_oracle(
+            RuntimeOrigin::root(),
+            Some(2),
+            H256::repeat_byte(0x10),
+            [2u8; 32],
+            None,
+        ));
+        assert_eq!(ProofOfPersonhood::next_oracle_id(), Some(1));
+
+        assert_ok!(ProofOfPersonhood::register_oracle(
+            RuntimeOrigin::root(),
+            None,
+            H256::repeat_byte(0x10),
+            [3u8; 32],
+            None,
+        ));
+        assert!(ProofOfPersonhood::ml_oracles(1).is_some());
+        assert_eq!(ProofOfPersonhood::next_oracle_id(), Some(3));
+
+        // MaxActiveOracles is 3 in the mock
+        assert_noop!(
+            ProofOfPersonhood::register_oracle(
+                RuntimeOrigin::root(),
+                None,
+                H256::repeat_byte(0x10),
+                [4u8; 32],
+                None,
+            ),
+            Error::<Test>::TooManyOracles
+        );
+    });
+}
+
+#[test]
+fn active_oracle_index_tracks_register_and_deactivate() {
+    new_test_ext().execute_with(|| {
+        let register = |oracle_id: u8| {
+            assert_ok!(ProofOfPersonhood::register_oracle(
+                RuntimeOrigin::root(),
+                Some(oracle_id),
+                H256::repeat_byte(0x10),
+                [oracle_id; 32],
+                None,
+            ));
+        };
+        let deactivate = |oracle_id: u8| {
+            assert_ok!(ProofOfPersonhood::deactivate_oracle(RuntimeOrigin::root(), oracle_id, b"rotation".to_vec()));
+        };
+        let index_matches_registry = || {
+            let mut active: Vec<u8> = MLOracles::<Test>::iter()
+                .filter(|(_, oracle)| oracle.active)
+                .map(|(oracle_id, _)| oracle_id)
+                .collect();
+            active.sort();
+            let mut indexed = ProofOfPersonhood::active_oracle_ids().into_inner();
+            indexed.sort();
+            active == indexed
+        };
+
+        register(1);
+        register(2);
+        register(3);
+        assert_eq!(ProofOfPersonhood::active_oracle_ids().into_inner(), vec![1, 2, 3]);
+
+        deactivate(2);
+        assert_eq!(ProofOfPersonhood::active_oracle_ids().into_inner(), vec![1, 3]);
+        assert!(index_matches_registry());
+
+        // The freed slot counts against MaxActiveOracles again
+        register(4);
+        assert_eq!(ProofOfPersonhood::active_oracle_ids().into_inner(), vec![1, 3, 4]);
+        deactivate(1);
+        deactivate(1);
+        deactivate(4);
+        assert_eq!(ProofOfPersonhood::active_oracle_ids().into_inner(), vec![3]);
+        assert!(index_matches_registry());
+    });
+}
+
+#[test]
+fn fraud_punishment_below_floor_emits_oracle_deactivated() {
+    new_test_ext().execute_with(|| {
+        let target = create_test_identity(ALICE, b"did:identity:alice");
+        create_test_identity(BOB, b"did:identity:bob");
+        assert_ok!(ProofOfPersonhood::register_oracle(
+            RuntimeOrigin::root(),
+            Some(1),
+            H256::repeat_byte(0x10),
+            [9u8; 32],
+            None,
+        ));
+        MLOracles::<Test>::mutate(1, |oracle| oracle.as_mut().unwrap().reputation = 40);
+        MLScores::<Test>::insert(&target, (90u8, Timestamp::get()));
+        OracleResponses::<Test>::insert(&target, 1, (92u8, 0u64));
+
+        let challenge_id = submit_challenge(BOB, target);
+        assert_ok!(ProofOfPersonhood::resolve_fraud_challenge(
+            RuntimeOrigin::root(),
+            challenge_id,
+            ChallengeResolution::Upheld,
+        ));
+
+        assert!(!ProofOfPersonhood::ml_oracles(1).unwrap().active);
+        assert!(ProofOfPersonhood::active_oracle_ids().is_empty());
+        System::assert_has_event(
+            Event::OracleDeactivated {
+                oracle_id: 1,
+                reason: b"reputation below fraud threshold".to_vec(),
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn guardian_votes_beyond_bound_fail_with_dedicated_error() {
+    new_test_ext().execute_with(|| {
+        let did = start_progressive_recovery(ALICE, b"did:identity:alice");
+        let now = Timestamp::get();
+
+        // Relationships seeded directly, as add_guardian caps the set at ten
+        let guardians: Vec<AccountId> =
+            (0..11u8).map(|i| AccountId::new([0x40 + i; 32])).collect();
+        for guardian in guardians.iter() {
+            seed_guardian(did, guardian.clone(), 5, now);
+        }
+
+        for guardian in guardians.iter().take(10) {
+            assert_ok!(ProofOfPersonhood::submit_recovery_evidence(
+                RuntimeOrigin::signed(guardian.clone()),
+                did,
+                EvidenceType::GuardianApproval { vote_strength: 1 },
+                vec![],
+            ));
+        }
+        assert_noop!(
+            ProofOfPersonhood::submit_recovery_evidence(
+                RuntimeOrigin::signed(guardians[10].clone()),
+                did,
+                EvidenceType::GuardianApproval { vote_strength: 1 },
+                vec![],
+            ),
+            Error::<Test>::TooManyGuardianVotes
+        );
+    });
+}
+
+#[test]
+fn add_guardian_rejects_more_than_ten_guardians() {
+    new_test_ext().execute_with(|| {
+        let did = create_test_identity(ALICE, b"did:identity:alice");
+        let now = Timestamp::get();
+        for i in 0..10u8 {
+            seed_guardian(did, AccountId::new([0x40 + i; 32]), 5, now);
+        }
+
+        assert_noop!(
+            ProofOfPersonhood::add_guardian(RuntimeOrigin::signed(ALICE), did, BOB, 5, 500),
+            Error::<Test>::TooManyGuardians
+        );
+    });
+}
+
+#[test]
+fn dependent_pallet_gates_on_behavioral_verifier() {
+    new_test_ext().execute_with(|| {
+        let did = H256::repeat_byte(0x01);
+        let pattern = sample_features().encode();
+
+        // No baseline yet, so confidence is zero
+        assert_noop!(
+            StepUpAuth::sensitive_action(RuntimeOrigin::signed(ALICE), did, pattern.clone()),
+            pallet_step_up_auth::Error::<Test>::InsufficientConfidence
+        );
+
+        for _ in 0..3 {
+            assert_ok!(ProofOfPersonhood::verify_behavioral_pattern(&did, &pattern, false));
+        }
+        assert_ok!(StepUpAuth::sensitive_action(
+            RuntimeOrigin::signed(ALICE),
+            did,
+            pattern.clone(),
+        ));
+        assert_eq!(BehavioralPatternSamples::<Test>::get(&did).len(), 3);
+
+        assert_noop!(
+            StepUpAuth::sensitive_action(RuntimeOrigin::signed(ALICE), did, vec![0xFF]),
+            Error::<Test>::InvalidFeatureData
+        );
+    });
+}
+
+#[test]
+fn existence_proofs_distinguish_empty_malformed_and_non_inclusive() {
+    use sp_runtime::traits::BlakeTwo256;
+    use sp_trie::{LayoutV1, MemoryDB, TrieDBMutBuilder, TrieMut};
+
+    new_test_ext().execute_with(|| {
+        let nullifier = H256::repeat_byte(0x42);
+
+        assert_noop!(
+            ProofOfPersonhood::batch_verify_existence_proofs(vec![nullifier], H256::zero(), vec![]),
+            Error::<Test>::EmptyExistenceProof
+        );
+        assert_noop!(
+            ProofOfPersonhood::batch_verify_existence_proofs(vec![nullifier], H256::zero(), vec![vec![]]),
+            Error::<Test>::EmptyExistenceProof
+        );
+        assert_noop!(
+            ProofOfPersonhood::batch_verify_existence_proofs(
+                vec![nullifier],
+                H256::repeat_byte(0x01),
+                vec![vec![0xde, 0xad, 0xbe, 0xef]],
+            ),
+            Error::<Test>::InvalidExistenceProof
+        );
+
+        // A single-leaf trie holding some other key proves the nullifier is absent
+        let mut db = MemoryDB::<BlakeTwo256>::default();
+        let mut root = H256::zero();
+        {
+            let mut trie = TrieDBMutBuilder::<LayoutV1<BlakeTwo256>>::new(&mut db, &mut root).build();
+            trie.insert(b"unrelated-key", b"value").unwrap();
+        }
+        let proof_nodes: Vec<Vec<u8>> = db
+            .drain()
+            .into_values()
+            .filter(|(_, rc)| *rc > 0)
+            .map(|(node, _)| node)
+            .collect();
+
+        assert_eq!(
+            ProofOfPersonhood::batch_verify_existence_proofs(vec![nullifier], root, proof_nodes).unwrap(),
+            vec![false]
+        );
+    });
+}
+
+#[test]
+fn ml_service_url_must_be_http_or_https() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(ProofOfPersonhood::ml_service_url_string(), None);
+
+        let invalid: [&[u8]; 5] = [
+            b"ftp://ml.example.com/score",
+            b"https://",
+            b"http://:8080/",
+            b"ml.example.com",
+            b"https://ml example.com",
+        ];
+        for bad in invalid {
+            assert_noop!(
+                ProofOfPersonhood::set_ml_service_url(RuntimeOrigin::root(), bad.to_vec()),
+                Error::<Test>::InvalidMLServiceUrl
+            );
+        }
+
+        assert_ok!(ProofOfPersonhood::set_ml_service_url(
+            RuntimeOrigin::root(),
+            b"https://ml.example.com:8443/score".to_vec(),
+        ));
+        assert_eq!(
+            ProofOfPersonhood::ml_service_url_string().as_deref(),
+            Some("https://ml.example.com:8443/score")
+        );
+    });
+}
+
+fn signed_ml_response(pair: &sp_core::ed25519::Pair, did: H256, nonce: u64) -> SignedMLResponse {
+    signed_ml_response_at(pair, did, nonce, Timestamp::get())
+}
+
+fn signed_ml_response_at(
+    pair: &sp_core::ed25519::Pair,
+    did: H256,
+    nonce: u64,
+    timestamp: u64,
+) -> SignedMLResponse {
+    let mut message = Vec::new();
+    message.extend_from_slice(did.as_bytes());
+    message.push(90);
+    message.extend_from_slice(&timestamp.to_le_bytes());
+    message.extend_from_slice(&nonce.to_le_bytes());
+    let signature = pair.sign(&sp_io::hashing::blake2_256(&message));
+
+    SignedMLResponse {
+        did,
+        confidence_score: 90,
+        timestamp,
+        nonce,
+        signature: signature.0,
+        service_public_key: pair.public().0,
+        tee_quote: None,
+    }
+}
+
+#[test]
+fn ml_response_older_than_validity_is_expired() {
+    new_test_ext().execute_with(|| {
+        let did = H256::repeat_byte(0x01);
+        let pair = sp_core::ed25519::Pair::from_seed(&[1u8; 32]);
+        assert_ok!(ProofOfPersonhood::register_oracle(
+            RuntimeOrigin::root(),
+            Some(1),
+            H256::repeat_byte(0x10),
+            pair.public().0,
+            None,
+        ));
+
+        // MLResponseValidity is 60 seconds in the mock
+        let now = Timestamp::get();
+        assert_ok!(ProofOfPersonhood::verify_ml_response_signature(&signed_ml_response_at(&pair, did, 1, now - 60)));
+        assert_eq!(
+            ProofOfPersonhood::verify_ml_response_signature(&signed_ml_response_at(&pair, did, 2, now - 61)),
+            Err(Error::<Test>::MLResponseExpired)
+        );
+    });
+}
+
+#[test]
+fn rotated_oracle_key_replaces_trusted_key_and_keeps_reputation() {
+    new_offchain_test_ext().execute_with(|| {
+        let did = H256::repeat_byte(0x01);
+        let old_pair = sp_core::ed25519::Pair::from_seed(&[1u8; 32]);
+        let new_pair = sp_core::ed25519::Pair::from_seed(&[2u8; 32]);
+        assert_ok!(ProofOfPersonhood::register_oracle(
+            RuntimeOrigin::root(),
+            Some(1),
+            H256::repeat_byte(0x10),
+            old_pair.public().0,
+            None,
+        ));
+        MLOracles::<Test>::mutate(1, |oracle| {
+            let oracle = oracle.as_mut().unwrap();
+            oracle.reputation = 77;
+            oracle.responses_submitted = 12;
+        });
+        assert_ok!(ProofOfPersonhood::verify_ml_response_signature(&signed_ml_response(&old_pair, did, 1)));
+
+        assert_ok!(ProofOfPersonhood::rotate_oracle_key(RuntimeOrigin::root(), 1, new_pair.public().0, None));
+
+        assert_ok!(ProofOfPersonhood::verify_ml_response_signature(&signed_ml_response(&new_pair, did, 2)));
+        assert!(ProofOfPersonhood::verify_ml_response_signature(&signed_ml_response(&old_pair, did, 3)).is_err());
+        assert!(!TrustedMLKeys::<Test>::get(old_pair.public().0));
+
+        let oracle = ProofOfPersonhood::ml_oracles(1).unwrap();
+        assert_eq!(oracle.public_key, new_pair.public().0);
+        assert_eq!(oracle.reputation, 77);
+        assert_eq!(oracle.responses_submitted, 12);
+
+        // A key that is already trusted cannot be reused
+        assert_noop!(
+            ProofOfPersonhood::rotate_oracle_key(RuntimeOrigin::root(), 1, new_pair.public().0, None),
+            Error::<Test>::OracleKeyInUse
+        );
+    });
+}
+
+#[test]
+fn oracle_key_rotation_can_replace_the_attestation() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(ProofOfPersonhood::register_oracle(
+            RuntimeOrigin::root(),
+            Some(1),
+            H256::repeat_byte(0x10),
+            [1u8; 32],
+            Some(vec![0xA1; 16]),
+        ));
+
+        // Without a new attestation the existing one is kept
+        assert_ok!(ProofOfPersonhood::rotate_oracle_key(RuntimeOrigin::root(), 1, [2u8; 32], None));
+        assert_eq!(ProofOfPersonhood::ml_oracles(1).unwrap().tee_attestation.unwrap().into_inner(), vec![0xA1; 16]);
+
+        assert_ok!(ProofOfPersonhood::rotate_oracle_key(RuntimeOrigin::root(), 1, [3u8; 32], Some(vec![0xB2; 16])));
+        let oracle = ProofOfPersonhood::ml_oracles(1).unwrap();
+        assert_eq!(oracle.public_key, [3u8; 32]);
+        assert_eq!(oracle.tee_attestation.unwrap().into_inner(), vec![0xB2; 16]);
+        System::assert_last_event(RuntimeEvent::ProofOfPersonhood(Event::OracleKeyRotated {
+            oracle_id: 1,
+            old_public_key: [2u8; 32],
+            new_public_key: [3u8; 32],
+        }));
+
+        assert_noop!(
+            ProofOfPersonhood::rotate_oracle_key(RuntimeOrigin::root(), 1, [4u8; 32], Some(vec![0; 257])),
+            Error::<Test>::InvalidFeatureData
+        );
+    });
+}
+
+#[test]
+fn consensus_forms_on_agreeing_subset_and_penalizes_outliers() {
+    new_test_ext().execute_with(|| {
+        MaxActiveOracles::set(5);
+        let did = H256::repeat_byte(0x01);
+        let features = sample_features();
+        PendingMLPatterns::<Test>::insert(&did, features.clone());
+        for oracle_id in 1..=5u8 {
+            assert_ok!(ProofOfPersonhood::register_oracle(
+                RuntimeOrigin::root(),
+                Some(oracle_id),
+                H256::repeat_byte(0x10),
+                [oracle_id; 32],
+                None,
+            ));
+        }
+        assert_ok!(ProofOfPersonhood::set_consensus_threshold(RuntimeOrigin::root(), 5));
+        assert_ok!(ProofOfPersonhood::set_consensus_agreement_threshold(RuntimeOrigin::root(), 3));
+        assert_ok!(ProofOfPersonhood::set_variance_tolerance(RuntimeOrigin::root(), 5));
+
+        for (oracle_id, score) in [(1u8, 70u8), (2, 71), (3, 72), (4, 20), (5, 95)] {
+            assert_ok!(ProofOfPersonhood::store_oracle_response(
+                RuntimeOrigin::none(),
+                oracle_id,
+                did,
+                score,
+                oracle_id as u64,
+                ProofOfPersonhood::features_commitment(&features),
+            ));
+        }
+
+        // Only the three agreeing oracles contribute to the final score
+        assert_eq!(ProofOfPersonhood::ml_scores(&did).unwrap().0, 71);
+        let mut participating = System::events()
+            .into_iter()
+            .find_map(|record| match record.event {
+                RuntimeEvent::ProofOfPersonhood(Event::ConsensusReached { participating_oracles, .. }) => {
+                    Some(participating_oracles)
+                }
+                _ => None,
+            })
+            .unwrap();
+        participating.sort();
+        assert_eq!(participating, vec![1, 2, 3]);
+        for outlier in [4u8, 5] {
+            assert_eq!(ProofOfPersonhood::ml_oracles(outlier).unwrap().reputation, 95);
+            assert!(ProofOfPersonhood::oracle_responses(&did, outlier).is_none());
+        }
+        assert_eq!(ProofOfPersonhood::ml_oracles(1).unwrap().reputation, 100);
+    });
+}
+
+#[test]
+fn guardians_see_exactly_the_recoveries_they_are_part_of() {
+    new_test_ext().execute_with(|| {
+        let alice_did = create_test_identity(ALICE, b"did:identity:alice");
+        seed_personhood(alice_did, H256::repeat_byte(0x11), ALICE);
+        assert_ok!(ProofOfPersonhood::request_recovery(
+            RuntimeOrigin::signed(ALICE),
+            alice_did,
+            H256::repeat_byte(0x22),
+            H256::repeat_byte(0x33),
+            recovery_proof(H256::repeat_byte(0x11), H256::repeat_byte(0x22)),
+            vec![BOB, CHARLIE],
+        ));
+
+        let dave_did = create_test_identity(DAVE, b"did:identity:dave");
+        seed_personhood(dave_did, H256::repeat_byte(0x44), DAVE);
+        seed_guardian(dave_did, BOB, 1, Timestamp::get());
+        assert_ok!(ProofOfPersonhood::initiate_progressive_recovery(
+            RuntimeOrigin::signed(DAVE),
+            dave_did,
+            None,
+            None,
+        ));
+
+        let mut bob_recoveries = ProofOfPersonhood::pending_recoveries_for_guardian(&BOB);
+        bob_recoveries.sort();
+        let mut expected = vec![alice_did, dave_did];
+        expected.sort();
+        assert_eq!(bob_recoveries, expected);
+        assert_eq!(ProofOfPersonhood::pending_recoveries_for_guardian(&CHARLIE), vec![alice_did]);
+        assert!(ProofOfPersonhood::pending_recoveries_for_guardian(&DAVE).is_empty());
+
+        assert_ok!(ProofOfPersonhood::cancel_recovery(RuntimeOrigin::signed(ALICE), alice_did));
+        assert_eq!(ProofOfPersonhood::pending_recoveries_for_guardian(&BOB), vec![dave_did]);
+        assert!(ProofOfPersonhood::pending_recoveries_for_guardian(&CHARLIE).is_empty());
+    });
+}
+
+#[test]
+fn guardians_of_lists_relationships_for_a_did() {
+    new_test_ext().execute_with(|| {
+        let did = create_test_identity(ALICE, b"did:identity:alice");
+        seed_guardian(did, BOB, 10, 1_000);
+        seed_guardian(did, CHARLIE, 3, 2_000);
+        seed_guardian(H256::repeat_byte(0x99), DAVE, 7, 3_000);
+
+        let mut guardians = ProofOfPersonhood::guardians_of(&did);
+        guardians.sort_by_key(|(guardian, _)| guardian.clone());
+        let mut expected = vec![(BOB, 10u8, 1_000u64), (CHARLIE, 3, 2_000)];
+        expected.sort_by_key(|(guardian, _, _)| guardian.clone());
+
+        assert_eq!(guardians.len(), 2);
+        for ((guardian, relationship), (account, strength, established_at)) in guardians.iter().zip(expected) {
+            assert_eq!(*guardian, account);
+            assert_eq!(relationship.guardian, account);
+            assert_eq!(relationship.relationship_strength, strength);
+            assert_eq!(relationship.established_at, established_at);
+            assert_eq!(relationship.bonded_stake, 500);
+        }
+        assert!(ProofOfPersonhood::guardians_of(&H256::repeat_byte(0x77)).is_empty());
+    });
+}
+
+#[test]
+fn is_guardian_checks_a_single_relationship() {
+    new_test_ext().execute_with(|| {
+        let did = create_test_identity(ALICE, b"did:identity:alice");
+        seed_guardian(did, BOB, 10, 1_000);
+        seed_guardian(H256::repeat_byte(0x99), CHARLIE, 7, 3_000);
+
+        assert!(ProofOfPersonhood::is_guardian(&did, &BOB));
+        assert!(!ProofOfPersonhood::is_guardian(&did, &CHARLIE));
+        assert!(!ProofOfPersonhood::is_guardian(&H256::repeat_byte(0x99), &BOB));
+    });
+}
+
+#[test]
+fn registration_conflicts_are_reported_per_request() {
+    new_test_ext().execute_with(|| {
+        let registered_nullifier = H256::repeat_byte(0x11);
+        let registered_did = register_primary(ALICE, b"did:identity:alice", registered_nullifier);
+        let bound_nullifier = H256::repeat_byte(0x22);
+        NullifierClaims::<Test>::insert(&bound_nullifier, true);
+        let cooling_nullifier = H256::repeat_byte(0x33);
+        let cooldown_end = Timestamp::get() + 1_000;
+        RegistrationCooldown::<Test>::insert(&cooling_nullifier, cooldown_end);
+        let fresh_did = H256::repeat_byte(0x44);
+
+        let requests = frame_support::BoundedVec::truncate_from(vec![
+            (registered_did, H256::repeat_byte(0x55)),
+            (fresh_did, registered_nullifier),
+            (fresh_did, bound_nullifier),
+            (fresh_did, cooling_nullifier),
+            (fresh_did, H256::zero()),
+            (fresh_did, H256::repeat_byte(0x66)),
+        ]);
+        let conflicts = ProofOfPersonhood::check_registration_conflicts(requests);
+
+        assert_eq!(conflicts.len(), 6);
+        assert!(conflicts[0].did_registered && !conflicts[0].nullifier_registered);
+        assert!(conflicts[1].nullifier_registered && !conflicts[1].did_registered);
+        assert!(conflicts[2].nullifier_bound);
+        assert_eq!(conflicts[3].cooling_down_until, Some(cooldown_end));
+        assert!(conflicts[4].invalid_nullifier);
+        assert!(conflicts[5].is_clean());
+        assert!(conflicts[..5].iter().all(|conflict| !conflict.is_clean()));
+    });
+}
+
+#[test]
+fn score_statistics_mean_does_not_wrap_with_many_samples() {
+    new_test_ext().execute_with(|| {
+        let did = H256::repeat_byte(0x01);
+        ScoreStatistics::<Test>::insert(
+            &did,
+            ScoreStats {
+                mean: 9_000,
+                std_dev: 0,
+                min: 90,
+                max: 90,
+                samples: 1_000_000,
+                last_score: 90,
+                last_timestamp: 0,
+            },
+        );
+
+        // 9_000 * 1_000_000 overflows u32; the mean must stay at 90.00
+        assert_ok!(ProofOfPersonhood::update_score_statistics(&did, 90, 3_600));
+        let stats = ProofOfPersonhood::score_statistics(&did).unwrap();
+        assert_eq!(stats.mean, 9_000);
+        assert_eq!(stats.samples, 1_000_001);
+
+        for _ in 0..10 {
+            assert_ok!(ProofOfPersonhood::update_score_statistics(&did, 100, 3_600));
+        }
+        let stats = ProofOfPersonhood::score_statistics(&did).unwrap();
+        assert_eq!(stats.mean, 9_000);
+        assert_eq!(stats.max, 100);
+    });
+}
+
+#[test]
+fn capped_score_statistics_track_recent_scores() {
+    new_test_ext().execute_with(|| {
+        MaxScoreStatSamples::set(10);
+        let did = H256::repeat_byte(0x01);
+        ScoreStatistics::<Test>::insert(
+            &did,
+            ScoreStats {
+                mean: 5_000,
+                std_dev: 0,
+                min: 50,
+                max: 50,
+                samples: 1_000,
+                last_score: 50,
+                last_timestamp: 0,
+            },
+        );
+
+        for _ in 0..10 {
+            assert_ok!(ProofOfPersonhood::update_score_statistics(&did, 90, 3_600));
+        }
+
+        // A cumulative average over 1_010 samples would still sit near 50.39
+        let stats = ProofOfPersonhood::score_statistics(&did).unwrap();
+        assert!(stats.mean > 7_000, "mean {} did not follow recent scores", stats.mean);
+        assert_eq!(stats.samples, 1_010);
+    });
+}
+
+#[test]
+fn classify_score_flags_spike_without_mutating_stats() {
+    new_test_ext().execute_with(|| {
+        let did = H256::repeat_byte(0x01);
+        let stats = ScoreStats {
+            mean: 4_000,
+            std_dev: 500,
+            min: 35,
+            max: 45,
+            samples: 20,
+            last_score: 40,
+            last_timestamp: 0,
+        };
+        ScoreStatistics::<Test>::insert(&did, stats.clone());
+
+        assert_eq!(
+            ProofOfPersonhood::classify_score(&did, 90, 7_200),
+            AnomalyType::SuddenSpike { deviation: 10 }
+        );
+        assert_eq!(ProofOfPersonhood::classify_score(&did, 41, 7_200), AnomalyType::Normal);
+        assert_eq!(ProofOfPersonhood::score_statistics(&did), Some(stats));
+    });
+}
+
+#[test]
+fn economic_stake_evidence_rejects_malformed_and_unaffordable_amounts() {
+    new_test_ext().execute_with(|| {
+        let did = start_progressive_recovery(ALICE, b"did:identity:alice");
+
+        for malformed in [vec![], vec![1u8, 2, 3], [5_000u64.encode(), vec![0xFF]].concat()] {
+            assert_noop!(
+                ProofOfPersonhood::submit_recovery_evidence(
+                    RuntimeOrigin::signed(BOB),
+                    did,
+                    EvidenceType::EconomicStake,
+                    malformed,
+                ),
+                Error::<Test>::InvalidStakeEncoding
+            );
+        }
+
+        assert_noop!(
+            ProofOfPersonhood::submit_recovery_evidence(
+                RuntimeOrigin::signed(BOB),
+                did,
+                EvidenceType::EconomicStake,
+                (INITIAL_BALANCE + 1).encode(),
+            ),
+            Error::<Test>::InsufficientStakeBalance
+        );
+
+        assert_ok!(ProofOfPersonhood::submit_recovery_evidence(
+            RuntimeOrigin::signed(BOB),
+            did,
+            EvidenceType::EconomicStake,
+            5_000u64.encode(),
+        ));
+        assert_eq!(Balances::reserved_balance(&BOB), 5_000);
+    });
+}
+
+#[test]
+fn fraud_challenge_bond_must_be_affordable() {
+    new_test_ext().execute_with(|| {
+        let target = create_test_identity(ALICE, b"did:identity:alice");
+        MLScores::<Test>::insert(&target, (90u8, Timestamp::get()));
+        create_test_identity(BOB, b"did:identity:bob");
+
+        // Bond is five recovery deposits, more than BOB holds
+        RecoveryDeposit::set(INITIAL_BALANCE);
+        assert_noop!(
+            ProofOfPersonhood::submit_fraud_challenge(RuntimeOrigin::signed(BOB), target, vec![1, 2, 3], 40),
+            Error::<Test>::InsufficientChallengeBond
+        );
+    });
+}
+
+#[test]
+fn oracle_reputation_gain_is_capped_per_window() {
+    new_test_ext().execute_with(|| {
+        for oracle_id in 1..=3u8 {
+            assert_ok!(ProofOfPersonhood::register_oracle(
+                RuntimeOrigin::root(),
+                Some(oracle_id),
+                H256::repeat_byte(0x10),
+                [oracle_id; 32],
+                None,
+            ));
+        }
+        MLOracles::<Test>::mutate(1, |oracle| oracle.as_mut().unwrap().reputation = 80);
+        assert_ok!(ProofOfPersonhood::set_consensus_threshold(RuntimeOrigin::root(), 3));
+
+        let run_round = |round: u64| {
+            let did = H256::from_low_u64_be(round);
+            let features = sample_features();
+            PendingMLPatterns::<Test>::insert(&did, features.clone());
+            for oracle_id in 1..=3u8 {
+                assert_ok!(ProofOfPersonhood::store_oracle_response(
+                    RuntimeOrigin::none(),
+                    oracle_id,
+                    did,
+                    70,
+                    round * 10 + oracle_id as u64,
+                    ProofOfPersonhood::features_commitment(&features),
+                ));
+            }
+        };
+
+        // Thirty consensus rounds in one block only earn the per-window maximum
+        for round in 1..=30 {
+            run_round(round);
+        }
+        let oracle = ProofOfPersonhood::ml_oracles(1).unwrap();
+        assert_eq!(oracle.reputation, 85);
+        assert_eq!(oracle.consensus_matches, 30);
+
+        Timestamp::set_timestamp(Timestamp::get() + 3_600);
+        run_round(31);
+        assert_eq!(ProofOfPersonhood::ml_oracles(1).unwrap().reputation, 86);
+    });
+}
+
+#[test]
+fn ml_subsystem_health_reflects_oracles_and_backlog() {
+    new_test_ext().execute_with(|| {
+        for oracle_id in 1..=3u8 {
+            assert_ok!(ProofOfPersonhood::register_oracle(
+                RuntimeOrigin::root(),
+                Some(oracle_id),
+                H256::repeat_byte(0x10),
+                [oracle_id; 32],
+                None,
+            ));
+        }
+        MLOracles::<Test>::mutate(1, |oracle| oracle.as_mut().unwrap().reputation = 90);
+        MLOracles::<Test>::mutate(2, |oracle| oracle.as_mut().unwrap().reputation = 80);
+        MLOracles::<Test>::mutate(3, |oracle| oracle.as_mut().unwrap().active = false);
+        assert_ok!(ProofOfPersonhood::set_consensus_threshold(RuntimeOrigin::root(), 2));
+
+        let features = sample_features();
+        for seed in 1..=4u64 {
+            PendingMLPatterns::<Test>::insert(H256::from_low_u64_be(seed), features.clone());
+        }
+
+        let respond = |did: H256, oracle_id: u8, score: u8| {
+            assert_ok!(ProofOfPersonhood::store_oracle_response(
+                RuntimeOrigin::none(),
+                oracle_id,
+                did,
+                score,
+                did.to_low_u64_be() * 10 + oracle_id as u64,
+                ProofOfPersonhood::features_commitment(&features),
+            ));
+        };
+
+        // One agreeing round, one that fails on variance (oracle 1 is the outlier)
+        respond(H256::from_low_u64_be(1), 1, 70);
+        respond(H256::from_low_u64_be(1), 2, 70);
+        respond(H256::from_low_u64_be(2), 1, 10);
+        respond(H256::from_low_u64_be(2), 2, 90);
+
+        assert_eq!(
+            ProofOfPersonhood::ml_subsystem_health(),
+            MlHealthReport {
+                active_oracles: 2,
+                // (91 - 5 + 81) / 2
+                average_reputation: 83,
+                consensus_successes: 1,
+                consensus_failures: 1,
+                consensus_success_rate: 50,
+                pending_patterns: 3,
+            }
+        );
+    });
+}
+
+#[test]
+fn consensus_outcome_counters_decay_across_windows() {
+    new_test_ext().execute_with(|| {
+        for oracle_id in 1..=2u8 {
+            assert_ok!(ProofOfPersonhood::register_oracle(
+                RuntimeOrigin::root(),
+                Some(oracle_id),
+                H256::repeat_byte(0x10),
+                [oracle_id; 32],
+                None,
+            ));
+        }
+        assert_ok!(ProofOfPersonhood::set_consensus_threshold(RuntimeOrigin::root(), 2));
+
+        let run_round = |round: u64, agree: bool| {
+            let did = H256::from_low_u64_be(round);
+            let features = sample_features();
+            PendingMLPatterns::<Test>::insert(&did, features.clone());
+            for (oracle_id, score) in [(1u8, 70u8), (2u8, if agree { 70 } else { 10 })] {
+                assert_ok!(ProofOfPersonhood::store_oracle_response(
+                    RuntimeOrigin::none(),
+                    oracle_id,
+                    did,
+                    score,
+                    round * 10 + oracle_id as u64,
+                    ProofOfPersonhood::features_commitment(&features),
+                ));
+            }
+        };
+
+        for round in 1..=4 {
+            run_round(round, true);
+        }
+        run_round(5, false);
+        run_round(6, false);
+        assert_eq!(ProofOfPersonhood::consensus_outcomes(), (4, 2));
+
+        // One elapsed window halves both counters
+        Timestamp::set_timestamp(Timestamp::get() + 3_600);
+        assert_eq!(ProofOfPersonhood::consensus_outcomes(), (2, 1));
+        run_round(7, false);
+        assert_eq!(ProofOfPersonhood::consensus_outcomes(), (2, 2));
+
+        // Several idle windows age everything out
+        Timestamp::set_timestamp(Timestamp::get() + 5 * 3_600);
+        assert_eq!(ProofOfPersonhood::consensus_outcomes(), (0, 0));
+        assert_eq!(ProofOfPersonhood::ml_subsystem_health().consensus_success_rate, 0);
+    });
+}
+
+#[test]
+fn score_percentiles_follow_the_global_distribution() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(
+            ProofOfPersonhood::score_percentiles(),
+            ScorePercentiles { p1: 0, median: 0, p99: 0, samples: 0 }
+        );
+
+        let mut distribution = vec![0u32; 101];
+        distribution[5] = 2;
+        distribution[60] = 96;
+        distribution[95] = 2;
+        GlobalScoreDistribution::<Test>::mutate(|dist| *dist = distribution.try_into().unwrap());
+
+        assert_eq!(
+            ProofOfPersonhood::score_percentiles(),
+            ScorePercentiles { p1: 5, median: 60, p99: 95, samples: 100 }
+        );
+    });
+}
+
+#[test]
+fn trend_tolerance_controls_drift_classification() {
+    new_test_ext().execute_with(|| {
+        let history: Vec<StoredBehavioralPattern> = (0..3)
+            .map(|i| StoredBehavioralPattern {
+                features: BehavioralFeatures { typing_speed_wpm: 100, ..sample_features() },
+                recorded_at: i,
+                sample_count: 1,
+                confidence_score: 100,
+            })
+            .collect();
+        // 15% off a flat trend, far from the mean on key hold time
+        let borderline = BehavioralFeatures {
+            typing_speed_wpm: 115,
+            avg_key_hold_time_ms: 400,
+            ..sample_features()
+        };
+        let did = H256::repeat_byte(0x34);
+
+        assert!(matches!(
+            ProofOfPersonhood::detect_pattern_drift(&did, &borderline, &history),
+            DriftAnalysis::GradualDrift { accept_update: true, .. }
+        ));
+
+        TrendDeviationTolerancePercent::set(10);
+        assert!(matches!(
+            ProofOfPersonhood::detect_pattern_drift(&did, &borderline, &history),
+            DriftAnalysis::SuddenChange { .. }
+        ));
+    });
+}
+
+#[test]
+fn trend_check_handles_a_zero_prediction() {
+    new_test_ext().execute_with(|| {
+        // Falling to 0 WPM extrapolates to a negative prediction
+        let history: Vec<StoredBehavioralPattern> = [40u32, 20, 0]
+            .into_iter()
+            .enumerate()
+            .map(|(i, speed)| StoredBehavioralPattern {
+                features: BehavioralFeatures { typing_speed_wpm: speed, ..sample_features() },
+                recorded_at: i as u64,
+                sample_count: 1,
+                confidence_score: 100,
+            })
+            .collect();
+        let did = H256::repeat_byte(0x35);
+        let with_speed = |typing_speed_wpm| BehavioralFeatures {
+            typing_speed_wpm,
+            avg_key_hold_time_ms: 400,
+            ..sample_features()
+        };
+
+        // Within the absolute floor of a 0 WPM prediction
+        assert!(matches!(
+            ProofOfPersonhood::detect_pattern_drift(&did, &with_speed(2), &history),
+            DriftAnalysis::GradualDrift { .. }
+        ));
+        assert!(matches!(
+            ProofOfPersonhood::detect_pattern_drift(&did, &with_speed(30), &history),
+            DriftAnalysis::SuddenChange { .. }
+        ));
+    });
+}
+
+#[test]
+fn envelope_rejection_waits_for_minimum_samples() {
+    new_test_ext().execute_with(|| {
+        MinSamplesForEnvelopeRejection::set(5);
+        let did = H256::repeat_byte(0x39);
+        let baseline = sample_features().encode();
+        // Just outside the initial prior bounds on all three timing features
+        let shifted = BehavioralFeatures {
+            typing_speed_wpm: 72,
+            avg_key_hold_time_ms: 125,
+            avg_transition_time_ms: 170,
+            ..sample_features()
+        }
+        .encode();
+        let quick_rejected = || {
+            System::events().iter().any(|record| {
+                matches!(
+                    record.event,
+                    RuntimeEvent::ProofOfPersonhood(Event::PatternRejected { .. })
+                )
+            })
+        };
+
+        assert_ok!(ProofOfPersonhood::verify_behavioral_pattern(&did, &baseline, false));
+        assert_ok!(ProofOfPersonhood::verify_behavioral_pattern(&did, &shifted, true));
+        assert!(!quick_rejected());
+
+        for _ in 0..7 {
+            assert_ok!(ProofOfPersonhood::verify_behavioral_pattern(&did, &baseline, false));
+        }
+        assert_eq!(BehavioralPatternSamples::<Test>::get(&did).len(), 8);
+
+        assert_eq!(ProofOfPersonhood::verify_behavioral_pattern(&did, &shifted, true).unwrap(), 0);
+        assert!(quick_rejected());
+    });
+}
+
+#[test]
+fn registration_rate_limit_rejects_second_attempt_in_window() {
+    use crate::rate_limit::{CheckRegistrationRate, REGISTRATION_RATE_LIMITED};
+    use frame_support::dispatch::GetDispatchInfo;
+    use sp_runtime::{
+        traits::TransactionExtension,
+        transaction_validity::{InvalidTransaction, TransactionSource},
+    };
+
+    new_test_ext().execute_with(|| {
+        let call = RuntimeCall::ProofOfPersonhood(Call::register_personhood {
+            did: H256::repeat_byte(0x40),
+            nullifier: H256::repeat_byte(0x41),
+            commitment: H256::repeat_byte(0x42),
+            uniqueness_proof: vec![0u8; 64],
+        });
+        let info = call.get_dispatch_info();
+        let check = CheckRegistrationRate::<Test>::new();
+        let validate = |who: AccountId| {
+            check
+                .validate_only(RuntimeOrigin::signed(who), &call, &info, 0, TransactionSource::External, 0)
+                .map(|_| ())
+        };
+
+        // The first attempt is counted when the transaction is prepared for inclusion
+        assert_ok!(validate(ALICE));
+        assert_ok!(check.clone().validate_and_prepare(RuntimeOrigin::signed(ALICE), &call, &info, 0, 0));
+        assert_eq!(ProofOfPersonhood::registration_attempts(&ALICE), 1);
+
+        assert_eq!(
+            validate(ALICE),
+            Err(InvalidTransaction::Custom(REGISTRATION_RATE_LIMITED).into())
+        );
+        assert_ok!(validate(BOB));
+
+        // Other calls are unaffected
+        let other = RuntimeCall::ProofOfPersonhood(Call::record_activity {});
+        let other_info = other.get_dispatch_info();
+        assert_ok!(check
+            .validate_only(RuntimeOrigin::signed(ALICE), &other, &other_info, 0, TransactionSource::External, 0)
+            .map(|_| ()));
+
+        Timestamp::set_timestamp(Timestamp::get() + 3_600);
+        assert_ok!(validate(ALICE));
+    });
+}
+
+#[test]
+fn behavioral_export_round_trips_for_the_controller() {
+    new_test_ext().execute_with(|| {
+        let did = create_test_identity(ALICE, b"did:example:export");
+        let valid_until = Timestamp::get() + 60;
+        assert_eq!(ProofOfPersonhood::export_behavioral_data_for(&did, &ALICE, valid_until).unwrap(), None);
+
+        let pattern = sample_features().encode();
+        for _ in 0..2 {
+            assert_ok!(ProofOfPersonhood::verify_behavioral_pattern(&did, &pattern, false));
+        }
+
+        let export = ProofOfPersonhood::export_behavioral_data_for(&did, &ALICE, valid_until).unwrap().unwrap();
+        assert_eq!(export.samples, BehavioralPatternSamples::<Test>::get(&did).into_inner());
+        assert_eq!(export.samples.len(), 2);
+        assert_eq!(export.envelope, BehavioralEnvelopes::<Test>::get(&did));
+        assert!(export.envelope.is_some());
+        assert_eq!(BehavioralExport::decode(&mut &export.encode()[..]).unwrap(), export);
+
+        assert!(matches!(
+            ProofOfPersonhood::export_behavioral_data_for(&did, &BOB, valid_until),
+            Err(Error::<Test>::NotAuthorized)
+        ));
+    });
+}
+
+#[test]
+fn behavioral_export_requests_expire() {
+    new_test_ext().execute_with(|| {
+        let did = create_test_identity(ALICE, b"did:example:export");
+        let now = Timestamp::get();
+
+        // Requests may not be signed to outlive the validity cap
+        assert!(matches!(
+            ProofOfPersonhood::export_behavioral_data_for(&did, &ALICE, now + MAX_BEHAVIORAL_EXPORT_VALIDITY + 1),
+            Err(Error::<Test>::BehavioralExportExpired)
+        ));
+        assert_ok!(ProofOfPersonhood::export_behavioral_data_for(&did, &ALICE, now + 60));
+
+        Timestamp::set_timestamp(now + 61);
+        assert!(matches!(
+            ProofOfPersonhood::export_behavioral_data_for(&did, &ALICE, now + 60),
+            Err(Error::<Test>::BehavioralExportExpired)
+        ));
+
+        // Expiry and nonce are both covered by the signed message
+        assert_ne!(
+            ProofOfPersonhood::behavioral_export_payload(&did, now + 60, 1),
+            ProofOfPersonhood::behavioral_export_payload(&did, now + 60, 2)
+        );
+        assert_ne!(
+            ProofOfPersonhood::behavioral_export_payload(&did, now + 60, 1),
+            ProofOfPersonhood::behavioral_export_payload(&did, now + 120, 1)
+        );
+    });
+}
+
+#[test]
+fn fraud_challenge_freshness_uses_the_chain_clock() {
+    new_offchain_test_ext().execute_with(|| {
+        let target = create_test_identity(ALICE, b"did:identity:alice");
+        create_test_identity(BOB, b"did:identity:bob");
+        for oracle_id in 1..=2u8 {
+            assert_ok!(ProofOfPersonhood::register_oracle(
+                RuntimeOrigin::root(),
+                Some(oracle_id),
+                H256::repeat_byte(0x10),
+                [oracle_id; 32],
+                None,
+            ));
+        }
+        assert_ok!(ProofOfPersonhood::set_consensus_threshold(RuntimeOrigin::root(), 2));
+
+        let features = sample_features();
+        PendingMLPatterns::<Test>::insert(&target, features.clone());
+        for oracle_id in 1..=2u8 {
+            assert_ok!(ProofOfPersonhood::store_oracle_response(
+                RuntimeOrigin::none(),
+                oracle_id,
+                target,
+                70,
+                oracle_id as u64,
+                ProofOfPersonhood::features_commitment(&features),
+            ));
+        }
+        assert_eq!(ProofOfPersonhood::ml_scores(&target), Some((70, Timestamp::get())));
+
+        // The node's clock is nowhere near chain time, which must not matter on-chain
+        assert_eq!(sp_io::offchain::timestamp().unix_millis(), 0);
+        assert_ok!(ProofOfPersonhood::submit_fraud_challenge(
+            RuntimeOrigin::signed(BOB),
+            target,
+            vec![1, 2, 3],
+            40,
+        ));
+
+        Timestamp::set_timestamp(Timestamp::get() + 7 * 24 * 60 * 60 + 1);
+        assert_noop!(
+            ProofOfPersonhood::submit_fraud_challenge(RuntimeOrigin::signed(BOB), target, vec![1, 2, 3], 40),
+            Error::<Test>::ChallengeWindowExpired
+        );
+    });
+}
+
+/// SEV-SNP attestation report and its [VCEK][ASK][ARK] chain, in AMD's formats: a 0x4A0-byte
+/// report signed with P-384 / SHA-384, certificates signed with RSASSA-PSS / SHA-384 under an
+/// RSA-4096 root
+const SEV_SNP_REPORT: &[u8] = include_bytes!("test_vectors/sev_snp_report.bin");
+const SEV_VCEK: &[u8] = include_bytes!("test_vectors/sev_vcek.der");
+const SEV_ASK: &[u8] = include_bytes!("test_vectors/sev_ask.der");
+const SEV_ARK: &[u8] = include_bytes!("test_vectors/sev_ark.der");
+
+/// Offset of the attestation report inside a TEE quote
+const SEV_REPORT_OFFSET: usize = 131;
+
+/// TEE quote whose header carries the report's measurement and REPORT_DATA, then the chain
+fn sev_quote(chain: &[&[u8]]) -> Vec<u8> {
+    let mut quote = vec![2u8, 4, 0];
+    quote.extend_from_slice(&[0u8; 64]);
+    quote.extend_from_slice(&sp_io::hashing::blake2_256(&SEV_SNP_REPORT[0x90..0xC0]));
+    quote.extend_from_slice(&SEV_SNP_REPORT[0x50..0x70]);
+    quote.extend_from_slice(SEV_SNP_REPORT);
+    chain.iter().for_each(|certificate| quote.extend_from_slice(certificate));
+    quote
+}
+
+fn sev_ark_modulus() -> Vec<u8> {
+    let (ark, _) = crate::x509::Certificate::parse(SEV_ARK).unwrap();
+    ark.rsa_public_key().unwrap().0.to_vec()
+}
+
+#[test]
+fn sev_snp_report_is_verified_through_the_vcek_chain() {
+    new_test_ext().execute_with(|| {
+        let quote = sev_quote(&[SEV_VCEK, SEV_ASK, SEV_ARK]);
+
+        assert_eq!(
+            ProofOfPersonhood::verify_sev_quote_signature(&quote),
+            Err("AMD root key not trusted")
+        );
+        assert_ok!(ProofOfPersonhood::add_amd_root_key(RuntimeOrigin::root(), sev_ark_modulus()));
+        assert_eq!(ProofOfPersonhood::verify_sev_quote_signature(&quote), Ok(()));
+
+        // CHIP_ID is covered by the VCEK signature
+        let mut tampered_body = quote.clone();
+        tampered_body[SEV_REPORT_OFFSET + 0x1A0] ^= 0x01;
+        assert_eq!(
+            ProofOfPersonhood::verify_sev_quote_signature(&tampered_body),
+            Err("AMD SEV signature verification failed")
+        );
+
+        let mut tampered_signature = quote.clone();
+        tampered_signature[SEV_REPORT_OFFSET + 0x2A0] ^= 0x01;
+        assert!(ProofOfPersonhood::verify_sev_quote_signature(&tampered_signature).is_err());
+
+        // R is a 72-byte little-endian field, but a P-384 scalar only fills 48 bytes of it
+        let mut oversized_r = quote.clone();
+        oversized_r[SEV_REPORT_OFFSET + 0x2A0 + 60] = 0x01;
+        assert_eq!(
+            ProofOfPersonhood::verify_sev_quote_signature(&oversized_r),
+            Err("Invalid P-384 signature format")
+        );
+
+        // The header must describe the report that was signed
+        let mut foreign_commitment = quote.clone();
+        foreign_commitment[99] ^= 0x01;
+        assert_eq!(
+            ProofOfPersonhood::verify_sev_quote_signature(&foreign_commitment),
+            Err("AMD SEV report data mismatch")
+        );
+        let mut foreign_measurement = quote.clone();
+        foreign_measurement[67] ^= 0x01;
+        assert_eq!(
+            ProofOfPersonhood::verify_sev_quote_signature(&foreign_measurement),
+            Err("AMD SEV measurement mismatch")
+        );
+
+        // The VCEK must be issued by the ASK, not directly by the root
+        assert_eq!(
+            ProofOfPersonhood::verify_sev_quote_signature(&sev_quote(&[SEV_VCEK, SEV_ARK, SEV_ARK])),
+            Err("AMD certificate signature verification failed")
+        );
+        // Nor may the VCEK stand in for the root
+        assert!(ProofOfPersonhood::verify_sev_quote_signature(&sev_quote(&[SEV_VCEK, SEV_ASK, SEV_VCEK])).is_err());
+
+        assert_eq!(
+            ProofOfPersonhood::verify_sev_quote_signature(&quote[..SEV_REPORT_OFFSET + 0x4A0 - 1]),
+            Err("AMD SEV quote too short")
+        );
+    });
+}
+
+#[test]
+fn v1_migration_drops_point_shaped_amd_root_keys() {
+    new_test_ext().execute_with(|| {
+        StorageVersion::new(0).put::<ProofOfPersonhood>();
+        let stale = [0x42u8; 96];
+        frame_support::storage::unhashed::put(
+            &AMDRootKeys::<Test>::hashed_key_for(sp_io::hashing::blake2_256(&stale)),
+            &stale,
+        );
+
+        crate::migrations::v1::MigrateToV1::<Test>::on_runtime_upgrade();
+
+        assert_eq!(AMDRootKeys::<Test>::iter_keys().count(), 0);
+        assert_eq!(ProofOfPersonhood::on_chain_storage_version(), StorageVersion::new(1));
+    });
+}
+
+#[test]
+fn v2_migration_gives_legacy_bindings_an_unused_primary_modality() {
+    new_test_ext().execute_with(|| {
+        StorageVersion::new(1).put::<ProofOfPersonhood>();
+        let legacy = |did: H256, bound: Vec<(H256, BiometricModality)>| {
+            let old = crate::migrations::v2::OldBiometricBinding::<Test> {
+                primary_did: did,
+                primary_nullifier: H256::repeat_byte(0x11),
+                bound_nullifiers: frame_support::BoundedVec::truncate_from(bound),
+                created_at: 1,
+                updated_at: 2,
+                controller: ALICE,
+            };
+            frame_support::storage::unhashed::put(&PersonhoodBindings::<Test>::hashed_key_for(did), &old);
+        };
+        let plain = H256::repeat_byte(0x01);
+        let with_fingerprint = H256::repeat_byte(0x02);
+        legacy(plain, vec![(H256::repeat_byte(0x21), BiometricModality::Iris)]);
+        legacy(with_fingerprint, vec![(H256::repeat_byte(0x22), BiometricModality::Fingerprint)]);
+
+        crate::migrations::v2::MigrateToV2::<Test>::on_runtime_upgrade();
+
+        let binding = ProofOfPersonhood::personhood_bindings(&plain).unwrap();
+        assert_eq!(binding.primary_modality, BiometricModality::Fingerprint);
+        assert_eq!(binding.bound_nullifiers.to_vec(), vec![(H256::repeat_byte(0x21), BiometricModality::Iris)]);
+        assert_eq!((binding.created_at, binding.updated_at, binding.controller), (1, 2, ALICE));
+        assert_eq!(
+            ProofOfPersonhood::personhood_bindings(&with_fingerprint).unwrap().primary_modality,
+            BiometricModality::Iris
+        );
+        assert_eq!(ProofOfPersonhood::on_chain_storage_version(), StorageVersion::new(2));
+    });
+}
+
+#[test]
+fn v3_migration_counts_pending_ml_patterns() {
+    new_test_ext().execute_with(|| {
+        StorageVersion::new(2).put::<ProofOfPersonhood>();
+        // Entries written while the map was uncounted
+        for seed in 1..=3u8 {
+            frame_support::storage::unhashed::put(
+                &PendingMLPatterns::<Test>::hashed_key_for(H256::repeat_byte(seed)),
+                &sample_features(),
+            );
+        }
+        assert_eq!(PendingMLPatterns::<Test>::count(), 0);
+
+        crate::migrations::v3::MigrateToV3::<Test>::on_runtime_upgrade();
+
+        assert_eq!(PendingMLPatterns::<Test>::count(), 3);
+        assert_eq!(ProofOfPersonhood::on_chain_storage_version(), StorageVersion::new(3));
+    });
+}
+
+#[test]
+fn v4_migration_drops_proof_hash_keyed_nonces() {
+    new_test_ext().execute_with(|| {
+        StorageVersion::new(3).put::<ProofOfPersonhood>();
+        // Keyed on blake2(old_did || new_nullifier || proof), valued with the consumption time
+        UsedRecoveryProofNonces::<Test>::insert(H256::repeat_byte(0xAB), 1_000);
+
+        crate::migrations::v4::MigrateToV4::<Test>::on_runtime_upgrade();
+
+        assert_eq!(UsedRecoveryProofNonces::<Test>::iter().count(), 0);
+        assert_eq!(ProofOfPersonhood::on_chain_storage_version(), StorageVersion::new(4));
+    });
+}
+
+#[test]
+fn v5_migration_indexes_votes_on_open_challenges() {
+    new_test_ext().execute_with(|| {
+        let target = create_test_identity(ALICE, b"did:identity:alice");
+        MLScores::<Test>::insert(&target, (90u8, Timestamp::get()));
+        create_test_identity(BOB, b"did:identity:bob");
+        let open = submit_challenge(BOB, target);
+        let settled = submit_challenge(BOB, target);
+        assert_ok!(ProofOfPersonhood::resolve_fraud_challenge(
+            RuntimeOrigin::root(),
+            settled,
+            ChallengeResolution::DismissedGoodFaith,
+        ));
+
+        StorageVersion::new(4).put::<ProofOfPersonhood>();
+        // Votes recorded before the voter index existed
+        ChallengeVotes::<Test>::insert(&open, &CHARLIE, true);
+        ChallengeVotes::<Test>::insert(&settled, &DAVE, false);
+
+        crate::migrations::v5::MigrateToV5::<Test>::on_runtime_upgrade();
+
+        assert!(VoterChallenges::<Test>::contains_key(&CHARLIE, &open));
+        assert!(!VoterChallenges::<Test>::contains_key(&DAVE, &settled));
+        assert_eq!(ProofOfPersonhood::on_chain_storage_version(), StorageVersion::new(5));
+    });
+}
+
+fn der(tag: u8, contents: &[u8]) -> Vec<u8> {
+    let mut encoded = vec![tag];
+    match contents.len() {
+        len @ 0..=0x7F => encoded.push(len as u8),
+        len @ 0x80..=0xFF => encoded.extend_from_slice(&[0x81, len as u8]),
+        len => encoded.extend_from_slice(&[0x82, (len >> 8) as u8, len as u8]),
+    }
+    encoded.extend_from_slice(contents);
+    encoded
+}
+
+fn pck_certificate(curve_oid: &[u8], key: &[u8; 64]) -> Vec<u8> {
+    let oid = |bytes: &[u8]| der(0x06, bytes);
+    let ecdsa_with_sha256 = der(0x30, &oid(&[0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x04, 0x03, 0x02]));
+
+    // The issuer carries a decoy P-256 OID and `03 42` BIT STRING header ahead of the real key
+    let mut issuer_name = oid(&[0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x03, 0x01, 0x07]);
+    issuer_name.extend_from_slice(&[0x03, 0x42, 0x00, 0x04]);
+    issuer_name.extend_from_slice(&[0xEE; 140]);
+    let subject_name = der(0x0C, &[b'S'; 300]);
+
+    let mut algorithm = oid(&[0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x02, 0x01]);
+    algorithm.extend_from_slice(&oid(curve_oid));
+    let mut point = vec![0x00, 0x04];
+    point.extend_from_slice(key);
+    let mut spki = der(0x30, &algorithm);
+    spki.extend_from_slice(&der(0x03, &point));
+
+    let mut tbs = der(0xA0, &der(0x02, &[0x02]));
+    tbs.extend_from_slice(&der(0x02, &[0x11; 20]));
+    tbs.extend_from_slice(&ecdsa_with_sha256);
+    tbs.extend_from_slice(&der(0x30, &issuer_name));
+    tbs.extend_from_slice(&der(0x30, &[0x17; 30]));
+    tbs.extend_from_slice(&der(0x30, &subject_name));
+    tbs.extend_from_slice(&der(0x30, &spki));
+    tbs.extend_from_slice(&der(0xA3, &der(0x30, &[0x55; 90])));
+
+    let mut certificate = der(0x30, &tbs);
+    certificate.extend_from_slice(&ecdsa_with_sha256);
+    certificate.extend_from_slice(&der(0x03, &[0x00; 72]));
+    der(0x30, &certificate)
+}
+
+/// PCK leaf in Intel's layout: Processor CA issuer, CRL distribution point, key usage and
+/// the SGX extension (PPID, TCB, PCE-ID, FMSPC, SGX type) after the SubjectPublicKeyInfo
+const INTEL_PCK_CERT: &[u8] = include_bytes!("test_vectors/intel_pck.der");
+/// Its P-256 key as uncompressed X || Y
+const INTEL_PCK_KEY: &[u8] = include_bytes!("test_vectors/intel_pck_key.bin");
+
+#[test]
+fn intel_pck_certificate_key_is_read_from_subject_public_key_info() {
+    let key = crate::x509::p256_public_key_from_certificate(INTEL_PCK_CERT).unwrap();
+    assert_eq!(&key[..], INTEL_PCK_KEY);
+
+    assert_eq!(
+        crate::x509::p256_public_key_from_certificate(&INTEL_PCK_CERT[..INTEL_PCK_CERT.len() - 80]),
+        Err("DER: value exceeds input")
+    );
+    assert_eq!(
+        crate::x509::p256_public_key_from_certificate(&INTEL_PCK_CERT[..1]),
+        Err("DER: truncated length")
+    );
+}
+
+#[test]
+fn pck_certificate_key_is_read_from_subject_public_key_info() {
+    let prime256v1 = [0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x03, 0x01, 0x07];
+    let mut key = [0u8; 64];
+    key.iter_mut().enumerate().for_each(|(i, byte)| *byte = i as u8);
+    let certificate = pck_certificate(&prime256v1, &key);
+
+    assert_eq!(crate::x509::p256_public_key_from_certificate(&certificate), Ok(key));
+
+    let secp384r1 = [0x2B, 0x81, 0x04, 0x00, 0x22];
+    assert_eq!(
+        crate::x509::p256_public_key_from_certificate(&pck_certificate(&secp384r1, &key)),
+        Err("X.509: curve is not prime256v1")
+    );
+}
@@ -17,7 +17,7 @@ pub trait WeightInfo {
     fn register_primary_personhood() -> Weight;
     fn bind_additional_biometric() -> Weight;
     fn register_historical_key() -> Weight;
-    fn store_ml_score() -> Weight;
+    fn store_ml_score(o: u32) -> Weight;
     fn set_ml_service_url() -> Weight;
     fn queue_for_ml_scoring() -> Weight;
     fn add_ml_service_key() -> Weight;
@@ -27,11 +27,20 @@ pub trait WeightInfo {
     fn set_consensus_threshold() -> Weight;
     fn set_variance_tolerance() -> Weight;
     fn submit_fraud_challenge() -> Weight;
-    fn resolve_fraud_challenge() -> Weight;
+    fn resolve_fraud_challenge(o: u32) -> Weight;
     fn update_tee_attestation() -> Weight;
     fn add_intel_root_key() -> Weight;
     fn add_amd_root_key() -> Weight;
     fn set_intel_ias_endpoint() -> Weight;
+    fn adjust_guardian_bond(c: u32) -> Weight;
+    fn clear_behavioral_data() -> Weight;
+    fn rotate_oracle_key() -> Weight;
+    fn set_consensus_agreement_threshold() -> Weight;
+    fn recompute_behavioral_envelope() -> Weight;
+    fn report_ml_failure() -> Weight;
+    fn remove_guardian() -> Weight;
+    fn vote_on_challenge(c: u32) -> Weight;
+    fn unbind_biometric() -> Weight;
 }
 
 pub struct SubstrateWeight<T>(core::marker::PhantomData<T>);
@@ -126,10 +135,13 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
             .saturating_add(T::DbWeight::get().writes(1))
     }
 
-    fn store_ml_score() -> Weight {
+    fn store_ml_score(o: u32) -> Weight {
         Weight::from_parts(20_000_000, 0)
+            .saturating_add(Weight::from_parts(5_000_000, 0).saturating_mul(o as u64))
             .saturating_add(T::DbWeight::get().reads(1))
+            .saturating_add(T::DbWeight::get().reads(2_u64.saturating_mul(o as u64)))
             .saturating_add(T::DbWeight::get().writes(1))
+            .saturating_add(T::DbWeight::get().writes(2_u64.saturating_mul(o as u64)))
     }
 
     fn set_ml_service_url() -> Weight {
@@ -184,10 +196,13 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
             .saturating_add(T::DbWeight::get().writes(2))
     }
 
-    fn resolve_fraud_challenge() -> Weight {
+    fn resolve_fraud_challenge(o: u32) -> Weight {
         Weight::from_parts(50_000_000, 0)
+            .saturating_add(Weight::from_parts(5_000_000, 0).saturating_mul(o as u64))
             .saturating_add(T::DbWeight::get().reads(4))
+            .saturating_add(T::DbWeight::get().reads(2_u64.saturating_mul(o as u64)))
             .saturating_add(T::DbWeight::get().writes(3))
+            .saturating_add(T::DbWeight::get().writes(o as u64))
     }
 
     fn update_tee_attestation() -> Weight {
@@ -214,6 +229,61 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
             .saturating_add(T::DbWeight::get().writes(1))
     }
 
+    fn adjust_guardian_bond(c: u32) -> Weight {
+        // `c` challenge votes visited, each read and possibly pruned
+        Weight::from_parts(35_000_000, 0)
+            .saturating_add(T::DbWeight::get().reads(6))
+            .saturating_add(T::DbWeight::get().writes(3))
+            .saturating_add(T::DbWeight::get().reads_writes(2 * c as u64, c as u64))
+    }
+    
+    fn clear_behavioral_data() -> Weight {
+        // Up to one oracle response per u8 oracle ID
+        Weight::from_parts(35_000_000, 0)
+            .saturating_add(T::DbWeight::get().reads(1))
+            .saturating_add(T::DbWeight::get().writes(7 + u8::MAX as u64 + 1))
+    }
+    
+    fn rotate_oracle_key() -> Weight {
+        Weight::from_parts(25_000_000, 0)
+            .saturating_add(T::DbWeight::get().reads(2))
+            .saturating_add(T::DbWeight::get().writes(3))
+    }
+    
+    fn set_consensus_agreement_threshold() -> Weight {
+        Weight::from_parts(10_000_000, 0)
+            .saturating_add(T::DbWeight::get().writes(1))
+    }
+    
+    fn recompute_behavioral_envelope() -> Weight {
+        Weight::from_parts(30_000_000, 0)
+            .saturating_add(T::DbWeight::get().reads(2))
+            .saturating_add(T::DbWeight::get().writes(1))
+    }
+    
+    fn report_ml_failure() -> Weight {
+        Weight::from_parts(15_000_000, 0)
+            .saturating_add(T::DbWeight::get().reads(3))
+    }
+    
+    fn remove_guardian() -> Weight {
+        Weight::from_parts(30_000_000, 0)
+            .saturating_add(T::DbWeight::get().reads(3))
+            .saturating_add(T::DbWeight::get().writes(3))
+    }
+    
+    fn vote_on_challenge(c: u32) -> Weight {
+        Weight::from_parts(35_000_000, 0)
+            .saturating_add(T::DbWeight::get().reads(6))
+            .saturating_add(T::DbWeight::get().writes(2))
+            .saturating_add(T::DbWeight::get().reads_writes(2 * c as u64, c as u64))
+    }
+    
+    fn unbind_biometric() -> Weight {
+        Weight::from_parts(25_000_000, 0)
+            .saturating_add(T::DbWeight::get().reads(1))
+            .saturating_add(T::DbWeight::get().writes(4))
+    }
 }
 
 impl WeightInfo for () {
@@ -232,7 +302,9 @@ impl WeightInfo for () {
     fn register_primary_personhood() -> Weight { Weight::from_parts(10_000, 0) }
     fn bind_additional_biometric() -> Weight { Weight::from_parts(10_000, 0) }
     fn register_historical_key() -> Weight { Weight::from_parts(10_000, 0) }
-    fn store_ml_score() -> Weight { Weight::from_parts(10_000, 0) }
+    fn store_ml_score(o: u32) -> Weight {
+        Weight::from_parts(10_000, 0).saturating_add(Weight::from_parts(1_000, 0).saturating_mul(o as u64))
+    }
     fn set_ml_service_url() -> Weight { Weight::from_parts(10_000, 0) }
     fn queue_for_ml_scoring() -> Weight { Weight::from_parts(10_000, 0) }
     fn add_ml_service_key() -> Weight { Weight::from_parts(10_000, 0) }
@@ -242,9 +314,20 @@ impl WeightInfo for () {
     fn set_consensus_threshold() -> Weight { Weight::from_parts(10_000, 0) }
     fn set_variance_tolerance() -> Weight { Weight::from_parts(10_000, 0) }
     fn submit_fraud_challenge() -> Weight { Weight::from_parts(10_000, 0) }
-    fn resolve_fraud_challenge() -> Weight { Weight::from_parts(10_000, 0) }
+    fn resolve_fraud_challenge(o: u32) -> Weight {
+        Weight::from_parts(10_000, 0).saturating_add(Weight::from_parts(1_000, 0).saturating_mul(o as u64))
+    }
     fn update_tee_attestation() -> Weight { Weight::from_parts(10_000, 0) }
     fn add_intel_root_key() -> Weight { Weight::from_parts(10_000, 0) }
     fn add_amd_root_key() -> Weight { Weight::from_parts(10_000, 0) }
     fn set_intel_ias_endpoint() -> Weight { Weight::from_parts(10_000, 0) } 
+    fn adjust_guardian_bond(_c: u32) -> Weight { Weight::from_parts(10_000, 0) }
+    fn clear_behavioral_data() -> Weight { Weight::from_parts(10_000, 0) }
+    fn rotate_oracle_key() -> Weight { Weight::from_parts(10_000, 0) }
+    fn set_consensus_agreement_threshold() -> Weight { Weight::from_parts(10_000, 0) }
+    fn recompute_behavioral_envelope() -> Weight { Weight::from_parts(10_000, 0) }
+    fn report_ml_failure() -> Weight { Weight::from_parts(10_000, 0) }
+    fn remove_guardian() -> Weight { Weight::from_parts(10_000, 0) }
+    fn vote_on_challenge(_c: u32) -> Weight { Weight::from_parts(10_000, 0) }
+    fn unbind_biometric() -> Weight { Weight::from_parts(10_000, 0) }
 }
\ No newline at end of file
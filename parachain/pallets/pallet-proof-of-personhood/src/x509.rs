@@ -0,0 +1,167 @@
+//! Minimal DER walker over X.509 certificates: the signed TBSCertificate, the issuer's
+//! signature, and the P-256, P-384 or RSA key in the SubjectPublicKeyInfo
+
+const TAG_INTEGER: u8 = 0x02;
+const TAG_BIT_STRING: u8 = 0x03;
+const TAG_OID: u8 = 0x06;
+const TAG_SEQUENCE: u8 = 0x30;
+/// `[0] EXPLICIT Version` in TBSCertificate
+const TAG_VERSION: u8 = 0xA0;
+
+/// id-ecPublicKey (1.2.840.10045.2.1)
+const OID_EC_PUBLIC_KEY: &[u8] = &[0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x02, 0x01];
+/// prime256v1 (1.2.840.10045.3.1.7)
+const OID_PRIME256V1: &[u8] = &[0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x03, 0x01, 0x07];
+/// secp384r1 (1.3.132.0.34)
+const OID_SECP384R1: &[u8] = &[0x2B, 0x81, 0x04, 0x00, 0x22];
+/// rsaEncryption (1.2.840.113549.1.1.1)
+const OID_RSA_ENCRYPTION: &[u8] = &[0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x01, 0x01];
+/// id-RSASSA-PSS (1.2.840.113549.1.1.10), used by AMD for both keys and signatures
+pub const OID_RSASSA_PSS: &[u8] = &[0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x01, 0x0A];
+
+/// Split one DER TLV off `input`, returning (tag, contents, rest)
+fn read_tlv(input: &[u8]) -> Result<(u8, &[u8], &[u8]), &'static str> {
+    let (&tag, input) = input.split_first().ok_or("DER: truncated tag")?;
+    let (&first, mut input) = input.split_first().ok_or("DER: truncated length")?;
+
+    let length = if first & 0x80 == 0 {
+        first as usize
+    } else {
+        // Long form; four length octets is far beyond any certificate we accept
+        let octets = (first & 0x7F) as usize;
+        if octets == 0 {
+            return Err("DER: indefinite length not allowed");
+        }
+        if octets > 4 || input.len() < octets {
+            return Err("DER: invalid length encoding");
+        }
+        let length = input[..octets].iter().fold(0usize, |acc, byte| (acc << 8) | *byte as usize);
+        input = &input[octets..];
+        length
+    };
+
+    if input.len() < length {
+        return Err("DER: value exceeds input");
+    }
+    Ok((tag, &input[..length], &input[length..]))
+}
+
+/// Read a TLV that must carry `expected` as its tag
+fn expect_tlv<'a>(input: &'a [u8], expected: u8, what: &'static str) -> Result<(&'a [u8], &'a [u8]), &'static str> {
+    let (tag, contents, rest) = read_tlv(input)?;
+    if tag != expected {
+        return Err(what);
+    }
+    Ok((contents, rest))
+}
+
+/// The parts of a DER-encoded X.509 certificate needed to check a chain
+pub struct Certificate<'a> {
+    /// TBSCertificate TLV, the bytes the issuer signed
+    pub tbs: &'a [u8],
+    /// OID of the issuer's signature algorithm
+    pub signature_algorithm: &'a [u8],
+    /// Issuer signature (BIT STRING contents after the unused-bits octet)
+    pub signature: &'a [u8],
+    /// SubjectPublicKeyInfo contents
+    spki: &'a [u8],
+}
+
+impl<'a> Certificate<'a> {
+    /// Parse one certificate off the front of `der`, returning it and the bytes after it
+    pub fn parse(der: &'a [u8]) -> Result<(Self, &'a [u8]), &'static str> {
+        let (certificate, rest) = expect_tlv(der, TAG_SEQUENCE, "X.509: certificate is not a SEQUENCE")?;
+        let (tbs_contents, after_tbs) = expect_tlv(certificate, TAG_SEQUENCE, "X.509: TBSCertificate is not a SEQUENCE")?;
+        let tbs = &certificate[..certificate.len() - after_tbs.len()];
+
+        let (algorithm, after_algorithm) = expect_tlv(after_tbs, TAG_SEQUENCE, "X.509: missing signature algorithm")?;
+        let (signature_algorithm, _) = expect_tlv(algorithm, TAG_OID, "X.509: signature algorithm is not an OID")?;
+        let (signature_bits, _) = expect_tlv(after_algorithm, TAG_BIT_STRING, "X.509: missing signature BIT STRING")?;
+        let signature = match signature_bits {
+            [0x00, signature @ ..] => signature,
+            _ => return Err("X.509: signature BIT STRING has unused bits"),
+        };
+
+        // version (optional), serialNumber, signature, issuer, validity, subject
+        let mut fields = tbs_contents;
+        let (tag, _, rest_fields) = read_tlv(fields)?;
+        if tag == TAG_VERSION {
+            fields = rest_fields;
+        }
+        let (_, rest_fields) = expect_tlv(fields, TAG_INTEGER, "X.509: missing serial number")?;
+        let (_, rest_fields) = expect_tlv(rest_fields, TAG_SEQUENCE, "X.509: missing signature algorithm")?;
+        let (_, rest_fields) = expect_tlv(rest_fields, TAG_SEQUENCE, "X.509: missing issuer")?;
+        let (_, rest_fields) = expect_tlv(rest_fields, TAG_SEQUENCE, "X.509: missing validity")?;
+        let (_, rest_fields) = expect_tlv(rest_fields, TAG_SEQUENCE, "X.509: missing subject")?;
+        let (spki, _) = expect_tlv(rest_fields, TAG_SEQUENCE, "X.509: missing SubjectPublicKeyInfo")?;
+
+        Ok((Certificate { tbs, signature_algorithm, signature, spki }, rest))
+    }
+
+    /// Key algorithm OID, its parameters, and the public key BIT STRING contents
+    fn key_parts(&self) -> Result<(&'a [u8], &'a [u8], &'a [u8]), &'static str> {
+        let (algorithm, rest) = expect_tlv(self.spki, TAG_SEQUENCE, "X.509: missing key algorithm")?;
+        let (algorithm_oid, parameters) = expect_tlv(algorithm, TAG_OID, "X.509: key algorithm is not an OID")?;
+        let (bits, _) = expect_tlv(rest, TAG_BIT_STRING, "X.509: missing public key BIT STRING")?;
+        Ok((algorithm_oid, parameters, bits))
+    }
+
+    /// SEC1 point coordinates (X || Y) of an EC key on `curve_oid`
+    fn ec_point(&self, curve_oid: &[u8], wrong_curve: &'static str, bad_point: &'static str) -> Result<&'a [u8], &'static str> {
+        let (algorithm_oid, parameters, bits) = self.key_parts()?;
+        if algorithm_oid != OID_EC_PUBLIC_KEY {
+            return Err("X.509: key is not id-ecPublicKey");
+        }
+        let (curve, _) = expect_tlv(parameters, TAG_OID, "X.509: missing curve parameters")?;
+        if curve != curve_oid {
+            return Err(wrong_curve);
+        }
+
+        // BIT STRING: 0 unused bits, then the SEC1 uncompressed point 0x04 || X || Y
+        match bits {
+            [0x00, 0x04, point @ ..] => Ok(point),
+            _ => Err(bad_point),
+        }
+    }
+
+    /// Uncompressed P-256 public key (X || Y)
+    pub fn p256_public_key(&self) -> Result<[u8; 64], &'static str> {
+        const BAD_POINT: &str = "X.509: public key is not an uncompressed P-256 point";
+        let point = self.ec_point(OID_PRIME256V1, "X.509: curve is not prime256v1", BAD_POINT)?;
+        point.try_into().map_err(|_| BAD_POINT)
+    }
+
+    /// Uncompressed P-384 public key (X || Y)
+    pub fn p384_public_key(&self) -> Result<[u8; 96], &'static str> {
+        const BAD_POINT: &str = "X.509: public key is not an uncompressed P-384 point";
+        let point = self.ec_point(OID_SECP384R1, "X.509: curve is not secp384r1", BAD_POINT)?;
+        point.try_into().map_err(|_| BAD_POINT)
+    }
+
+    /// RSA modulus and public exponent, big-endian without sign padding
+    pub fn rsa_public_key(&self) -> Result<(&'a [u8], &'a [u8]), &'static str> {
+        let (algorithm_oid, _, bits) = self.key_parts()?;
+        if algorithm_oid != OID_RSA_ENCRYPTION && algorithm_oid != OID_RSASSA_PSS {
+            return Err("X.509: key is not an RSA key");
+        }
+        let key = match bits {
+            [0x00, key @ ..] => key,
+            _ => return Err("X.509: RSA key BIT STRING has unused bits"),
+        };
+
+        // RSAPublicKey ::= SEQUENCE { modulus INTEGER, publicExponent INTEGER }
+        let (key, _) = expect_tlv(key, TAG_SEQUENCE, "X.509: RSA key is not a SEQUENCE")?;
+        let (modulus, rest) = expect_tlv(key, TAG_INTEGER, "X.509: missing RSA modulus")?;
+        let (exponent, _) = expect_tlv(rest, TAG_INTEGER, "X.509: missing RSA exponent")?;
+        let strip = |integer: &'a [u8]| match integer {
+            [0x00, unsigned @ ..] => unsigned,
+            _ => integer,
+        };
+        Ok((strip(modulus), strip(exponent)))
+    }
+}
+
+/// Uncompressed P-256 public key (X || Y) of a DER-encoded X.509 certificate
+pub fn p256_public_key_from_certificate(der: &[u8]) -> Result<[u8; 64], &'static str> {
+    Certificate::parse(der)?.0.p256_public_key()
+}
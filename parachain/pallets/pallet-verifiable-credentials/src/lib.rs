@@ -5,8 +5,16 @@ pub use pallet::*;
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
 
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
 pub mod weights;
 
+pub mod migrations;
+
 #[frame_support::pallet]
 pub mod pallet {
     use frame_support::{
@@ -28,7 +36,11 @@ pub mod pallet {
     use frame_support::parameter_types;
     use serde::{Deserialize, Serialize};
 
+    /// The in-code storage version
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(3);
+
     #[pallet::pallet]
+    #[pallet::storage_version(STORAGE_VERSION)]
     pub struct Pallet<T>(_);
 
     #[pallet::config]
@@ -40,6 +52,19 @@ pub mod pallet {
         type MaxFields: Get<u32>;
         type MaxFieldsToReveal: Get<u32>;
         type MaxCredentialCleanupPerBlock: Get<u32>;
+        /// Whether plaintext fields are capped at `MaxPlaintextFieldSize`
+        type StrictFieldPrivacy: Get<bool>;
+        /// Maximum size of a plaintext field when strict field privacy is enabled
+        type MaxPlaintextFieldSize: Get<u32>;
+        /// Minimum size of a field marked as encrypted
+        type MinCiphertextLength: Get<u32>;
+        /// Maximum time (seconds) between issuance and expiration
+        type MaxCredentialValidity: Get<u64>;
+        /// Revoked credential IDs stored per chunk of a revocation tree bucket; buckets grow by
+        /// whole chunks, so this bounds storage values, not how many IDs a bucket can hold
+        type MaxRevokedPerChunk: Get<u32>;
+        /// Maximum credentials revoked by a single `revoke_subject_credentials` call
+        type MaxRevocationsPerCall: Get<u32>;
     }
 
     #[pallet::genesis_config]
@@ -95,6 +120,7 @@ pub mod pallet {
         pub fields: BoundedVec<BoundedVec<u8, T::MaxFieldSize>, T::MaxFields>,
         pub required_fields: BoundedVec<bool, T::MaxFields>,
         pub fields_to_reveal: BoundedVec<u32, T::MaxFieldsToReveal>,
+        pub encrypted_fields: BoundedVec<bool, T::MaxFields>,
     }
 
     impl<T: Config> Clone for Credential<T> {
@@ -112,6 +138,7 @@ pub mod pallet {
                 fields: self.fields.clone(),
                 required_fields: self.required_fields.clone(),
                 fields_to_reveal: self.fields_to_reveal.clone(),
+                encrypted_fields: self.encrypted_fields.clone(),
             }
         }
     }
@@ -143,6 +170,38 @@ pub mod pallet {
         pub timestamp: u64,
     }
 
+    /// Levels of the revocation tree; the top bits of a credential ID select one of 2^depth buckets
+    pub const REVOCATION_TREE_DEPTH: u32 = 24;
+
+    /// Levels of the tree over a bucket's chunks; every u32 chunk index has a leaf, so a bucket
+    /// never runs out of room
+    pub const REVOCATION_CHUNK_DEPTH: u32 = 32;
+
+    /// Maximum credentials whose status one `statuses_of` call reports
+    pub const MAX_STATUS_QUERIES: u32 = 100;
+
+    /// Compact commitment to the set of revoked credential IDs
+    #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen, Default)]
+    pub struct RevocationAccumulator {
+        /// Incremented on every change to the revoked set
+        pub version: u32,
+        /// Root of the sparse revocation tree over bucket leaves (zero when empty)
+        pub root: H256,
+        /// Number of revoked IDs committed to
+        pub size: u32,
+    }
+
+    /// Proof that a credential ID is absent from its bucket of the revocation tree
+    #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+    pub struct NonRevocationProof {
+        pub credential_id: H256,
+        pub version: u32,
+        /// Every revoked ID in the credential's bucket, chunk by chunk as stored
+        pub bucket: Vec<Vec<H256>>,
+        /// Sibling hashes from the bucket's leaf up to the root
+        pub path: Vec<H256>,
+    }
+
     /// ZK Proof type for selective disclosure
     #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
     pub enum ZkCredentialType {
@@ -175,6 +234,17 @@ pub mod pallet {
         ValueQuery,
     >;
 
+    /// Storage: Reverse index CredentialId -> expiry slot in `Expiries`
+    #[pallet::storage]
+    #[pallet::getter(fn credential_expiry_slot)]
+    pub type CredentialExpirySlot<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        H256,
+        u64,
+        OptionQuery,
+    >;
+
     /// Storage: Credentials owned by a DID
     #[pallet::storage]
     #[pallet::getter(fn credentials_of)]
@@ -219,6 +289,113 @@ pub mod pallet {
         ValueQuery,
     >;
 
+    /// Storage: Credential types whose credentials can never be revoked
+    #[pallet::storage]
+    #[pallet::getter(fn non_revocable_types)]
+    pub type NonRevocableTypes<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        CredentialType,
+        bool,
+        ValueQuery,
+    >;
+
+    /// Storage: Whether issuers may issue a credential type to their own DID
+    /// (unset means only `Custom` allows it)
+    #[pallet::storage]
+    pub type SelfIssuancePolicy<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        CredentialType,
+        bool,
+        OptionQuery,
+    >;
+
+    /// Storage: Pending issuer-wide revocations, as (next index into `IssuedBy`, last credential processed)
+    #[pallet::storage]
+    #[pallet::getter(fn issuer_revocation_cursor)]
+    pub type IssuerRevocationCursor<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        H256,
+        (u32, Option<H256>),
+        OptionQuery,
+    >;
+
+    /// Storage: Revoked credential IDs per revocation tree bucket, split into (bucket, chunk)
+    #[pallet::storage]
+    #[pallet::getter(fn revoked_credential_chunk)]
+    pub type RevokedCredentialBuckets<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        u32, // Bucket
+        Blake2_128Concat,
+        u32, // Chunk
+        BoundedVec<H256, T::MaxRevokedPerChunk>,
+        ValueQuery,
+    >;
+
+    /// Storage: Number of revoked IDs held by each revocation tree bucket
+    #[pallet::storage]
+    pub type RevokedBucketLengths<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        u32,
+        u32,
+        ValueQuery,
+    >;
+
+    /// Storage: Chunk of its bucket each revoked credential ID is stored in
+    #[pallet::storage]
+    pub type RevokedCredentialChunks<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        H256,
+        u32,
+        OptionQuery,
+    >;
+
+    /// Storage: Non-empty nodes of each bucket's chunk tree by (bucket, level, index); level 0
+    /// holds chunk digests and the top level is the bucket's leaf in the revocation tree
+    #[pallet::storage]
+    pub type RevocationChunkNodes<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        (u32, u32, u32),
+        H256,
+        ValueQuery,
+    >;
+
+    /// Storage: Non-empty revocation tree nodes by (level, index); level 0 holds bucket leaves
+    #[pallet::storage]
+    pub type RevocationTreeNodes<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        (u32, u32),
+        H256,
+        ValueQuery,
+    >;
+
+    /// Storage: Current revocation accumulator, updated on each revocation
+    #[pallet::storage]
+    #[pallet::getter(fn revocation_accumulator)]
+    pub type RevocationAccumulatorState<T: Config> = StorageValue<
+        _,
+        RevocationAccumulator,
+        ValueQuery,
+    >;
+
+    /// Storage: Default validity period (seconds) applied when a credential of this type is issued with `expires_at = 0`
+    #[pallet::storage]
+    #[pallet::getter(fn default_expiry)]
+    pub type DefaultExpiry<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        CredentialType,
+        u64,
+        OptionQuery,
+    >;
+
     #[pallet::storage]
     #[pallet::getter(fn disclosure_records)]
     pub type DisclosureRecords<T: Config> = StorageMap<
@@ -229,6 +406,30 @@ pub mod pallet {
         OptionQuery,
     >;
 
+    /// Storage: Per-credential index of disclosure IDs in recording order
+    #[pallet::storage]
+    #[pallet::getter(fn credential_disclosure_ids)]
+    pub type CredentialDisclosureIds<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        H256, // credential_id
+        Twox64Concat,
+        u32, // sequence number
+        H256, // disclosure_id
+        OptionQuery,
+    >;
+
+    /// Storage: Number of disclosures recorded for each credential
+    #[pallet::storage]
+    #[pallet::getter(fn credential_disclosure_count)]
+    pub type CredentialDisclosureCount<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        H256,
+        u32,
+        ValueQuery,
+    >;
+
     /// Storage for tracking which fields were revealed (for analytics)
     #[pallet::storage]
     #[pallet::getter(fn field_disclosure_count)]
@@ -252,10 +453,19 @@ pub mod pallet {
             credential_type: CredentialType 
         },
         CredentialRevoked { credential_id: H256, issuer: H256 },
+        CredentialRenewed { credential_id: H256, expires_at: u64 },
         CredentialVerified { credential_id: H256, verifier: T::AccountId },
         SchemaCreated { schema_id: H256, creator: H256 },
         TrustedIssuerAdded { credential_type: CredentialType, issuer: H256 },
         TrustedIssuerRemoved { credential_type: CredentialType, issuer: H256 },
+        RevocationAccumulatorUpdated { version: u32, root: H256, size: u32 },
+        DefaultExpirySet { credential_type: CredentialType, validity: Option<u64> },
+        CredentialTypeRevocabilitySet { credential_type: CredentialType, revocable: bool },
+        SelfIssuancePolicySet { credential_type: CredentialType, allowed: bool },
+        IssuerRevocationQueued { issuer: H256, total: u32 },
+        IssuerRevocationProgress { issuer: H256, processed: u32, total: u32 },
+        IssuerRevocationCompleted { issuer: H256 },
+        IssuerRevocationCancelled { issuer: H256, processed: u32 },
         SelectiveDisclosure { credential_id: H256, fields_count: u32, disclosure_id: H256, timestamp: u64 },
         DisclosureProofVerified { credential_id: H256, verifier: T::AccountId, fields_revealed: u32 },
         CredentialVerificationFailed { 
@@ -303,6 +513,12 @@ pub mod pallet {
         TooManyFieldsToReveal,    // too many reveal indices (exceeds MaxFieldsToReveal)
         InvalidFieldsLength,      // fields.len() != required_fields.len()
         InvalidRevealIndex,       // fields_to_reveal contains an index >= fields.len()
+        PlaintextFieldTooLarge,   // a plaintext field exceeded MaxPlaintextFieldSize in strict mode
+        CiphertextTooShort,       // an encrypted field is shorter than MinCiphertextLength
+        CredentialNotRevocable,   // the credential's type is marked non-revocable
+        SelfIssuanceNotAllowed,   // issuer and subject are the same DID for a type that forbids it
+        IssuerRevocationInProgress, // the issuer's credentials are already being revoked
+        NoIssuerRevocation,       // no revocation is queued for the issuer
     }
 
     parameter_types! {
@@ -315,12 +531,21 @@ pub mod pallet {
             let now = <T as Config>::TimeProvider::now()
                 .saturated_into::<u64>();
             
-            let items_removed = Self::cleanup_expired_credentials(now);
+            let (items_removed, ids_pruned) = Self::cleanup_expired_credentials(now);
             
             T::DbWeight::get().reads_writes(
                 1 + items_removed as u64,
                 items_removed as u64 * 3
             )
+            .saturating_add(Self::revocation_tree_weight().saturating_mul(ids_pruned as u64))
+        }
+
+        fn on_idle(_n: BlockNumberFor<T>, remaining_weight: Weight) -> Weight {
+            Self::process_issuer_revocation(remaining_weight)
+        }
+
+        fn integrity_test() {
+            assert!(T::MaxRevokedPerChunk::get() > 0, "MaxRevokedPerChunk must be non-zero");
         }
     }
 
@@ -339,6 +564,7 @@ pub mod pallet {
             fields: Vec<Vec<u8>>,
             required_fields: Vec<bool>,
             fields_to_reveal: Vec<u32>,
+            encrypted_fields: Vec<bool>,
         ) -> DispatchResult {
             let who = ensure_signed(origin)?;
             
@@ -359,51 +585,26 @@ pub mod pallet {
                 TrustedIssuers::<T>::get((&credential_type, &issuer_did)),
                 Error::<T>::IssuerNotTrusted
             );
-
-            // 4. Validate Expiration
             ensure!(
-                Self::validate_expiration_timestamp(expires_at),
-                Error::<T>::InvalidCredentialStatus
+                issuer_did != subject_did || Self::is_self_issuance_allowed(&credential_type),
+                Error::<T>::SelfIssuanceNotAllowed
+            );
+            ensure!(
+                !IssuerRevocationCursor::<T>::contains_key(&issuer_did),
+                Error::<T>::IssuerRevocationInProgress
             );
 
             let now = <T as Config>::TimeProvider::now().saturated_into::<u64>();
 
-            // 5. Convert fields to BoundedVec properly
-            let inner_fields_result: Result<Vec<BoundedVec<u8, T::MaxFieldSize>>, Error<T>> = fields
-                .into_iter()
-                .map(|f| {
-                    BoundedVec::<u8, T::MaxFieldSize>::try_from(f)
-                        .map_err(|_| Error::<T>::FieldTooLarge)
-                })
-                .collect();
+            // 4. Validate Expiration (0 = type default, or no expiry if none is set)
+            let expires_at = Self::resolve_expiry(&credential_type, expires_at, now)?;
 
-            let inner_fields = inner_fields_result?;
+            // 5. Convert fields to BoundedVec and check their consistency
+            let (bounded_fields, bounded_required, bounded_reveal) =
+                Self::bound_credential_fields(fields, required_fields, fields_to_reveal)?;
 
-            let bounded_fields: BoundedVec<BoundedVec<u8, T::MaxFieldSize>, T::MaxFields> =
-                BoundedVec::try_from(inner_fields)
-                    .map_err(|_| Error::<T>::TooManyFields)?;
-
-            let bounded_required: BoundedVec<bool, T::MaxFields> =
-                BoundedVec::try_from(required_fields)
-                    .map_err(|_| Error::<T>::TooManyFields)?;
-
-            let bounded_reveal: BoundedVec<u32, T::MaxFieldsToReveal> =
-                BoundedVec::try_from(fields_to_reveal)
-                    .map_err(|_| Error::<T>::TooManyFieldsToReveal)?;
-
-            // 6. Consistency checks
-            ensure!(
-                bounded_fields.len() == bounded_required.len(),
-                Error::<T>::InvalidFieldsLength
-            );
-
-            let fields_len_u32: u32 = bounded_fields.len()
-                .try_into()
-                .expect("bounded_fields.len() fits into u32");
-
-            for idx in bounded_reveal.iter() {
-                ensure!(*idx < fields_len_u32, Error::<T>::InvalidRevealIndex);
-            }
+            // 6. Privacy checks
+            let bounded_encrypted = Self::bound_encrypted_fields(&bounded_fields, encrypted_fields)?;
 
             // 7. Create Credential
             let credential = Credential::<T> {
@@ -419,6 +620,7 @@ pub mod pallet {
                 fields: bounded_fields,
                 fields_to_reveal: bounded_reveal,
                 required_fields: bounded_required,
+                encrypted_fields: bounded_encrypted,
             };
 
             let credential_id = Self::generate_credential_id(&credential);
@@ -441,14 +643,7 @@ pub mod pallet {
             })?;
 
             // 11. Track expiration
-            if expires_at > 0 {
-                let expiry_block = expires_at / 6;
-                
-                Expiries::<T>::try_mutate(expiry_block, |list| -> DispatchResult {
-                    let _ = list.try_push(credential_id);
-                    Ok(())
-                })?;
-            }
+            Self::schedule_expiry(&credential_id, expires_at);
 
             Self::deposit_event(Event::CredentialIssued { 
                 credential_id, 
@@ -462,7 +657,7 @@ pub mod pallet {
 
         /// Revoke a credential (only issuer can revoke)
         #[pallet::call_index(1)]
-        #[pallet::weight(<T as Config>::WeightInfo::revoke_credential())]
+        #[pallet::weight(<T as Config>::WeightInfo::revoke_credential().saturating_add(Pallet::<T>::revocation_tree_weight()))]
         pub fn revoke_credential(
             origin: OriginFor<T>,
             credential_id: H256,
@@ -477,8 +672,13 @@ pub mod pallet {
 
                 ensure!(cred.issuer == issuer_did, Error::<T>::NotAuthorized);
                 ensure!(cred.status == CredentialStatus::Active, Error::<T>::InvalidCredentialStatus);
+                ensure!(
+                    !NonRevocableTypes::<T>::get(&cred.credential_type),
+                    Error::<T>::CredentialNotRevocable
+                );
 
                 cred.status = CredentialStatus::Revoked;
+                Self::record_revocation(credential_id);
 
                 Self::deposit_event(Event::CredentialRevoked { credential_id, issuer: issuer_did });
 
@@ -486,7 +686,98 @@ pub mod pallet {
             })
         }
 
-        /// Verify a credential
+        /// Revoke the caller's credentials for a subject, up to `MaxRevocationsPerCall` per call
+        #[pallet::call_index(10)]
+        #[pallet::weight(
+            <T as Config>::WeightInfo::revoke_subject_credentials(T::MaxRevocationsPerCall::get())
+                .saturating_add(Pallet::<T>::revocation_tree_weight().saturating_mul(T::MaxRevocationsPerCall::get() as u64))
+        )]
+        pub fn revoke_subject_credentials(
+            origin: OriginFor<T>,
+            subject_did: H256,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let (issuer_did, _) = IdentityRegistryPallet::<T>::get_identity_by_account(&who)
+                .ok_or(Error::<T>::IssuerIdentityNotFound)?;
+
+            // Only this issuer's active, revocable credentials; call again for the rest
+            let to_revoke: Vec<H256> = CredentialsOf::<T>::get(&subject_did)
+                .into_iter()
+                .filter(|credential_id| {
+                    Credentials::<T>::get(credential_id).map_or(false, |cred| {
+                        cred.issuer == issuer_did
+                            && cred.status == CredentialStatus::Active
+                            && !NonRevocableTypes::<T>::get(&cred.credential_type)
+                    })
+                })
+                .take(T::MaxRevocationsPerCall::get() as usize)
+                .collect();
+
+            ensure!(!to_revoke.is_empty(), Error::<T>::CredentialNotFound);
+
+            for credential_id in to_revoke {
+                Credentials::<T>::mutate(&credential_id, |cred_opt| {
+                    if let Some(cred) = cred_opt {
+                        cred.status = CredentialStatus::Revoked;
+                    }
+                });
+                Self::record_revocation(credential_id);
+
+                Self::deposit_event(Event::CredentialRevoked { credential_id, issuer: issuer_did });
+            }
+
+            Ok(())
+        }
+
+        /// Extend or shorten a credential's validity (only issuer can renew)
+        #[pallet::call_index(7)]
+        #[pallet::weight(<T as Config>::WeightInfo::renew_credential())]
+        pub fn renew_credential(
+            origin: OriginFor<T>,
+            credential_id: H256,
+            new_expires_at: u64,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let (issuer_did, _) = IdentityRegistryPallet::<T>::get_identity_by_account(&who)
+                .ok_or(Error::<T>::IssuerIdentityNotFound)?;
+
+            ensure!(
+                Self::validate_expiration_timestamp(new_expires_at),
+                Error::<T>::InvalidCredentialStatus
+            );
+
+            Credentials::<T>::try_mutate(&credential_id, |cred_opt| -> DispatchResult {
+                let cred = cred_opt.as_mut().ok_or(Error::<T>::CredentialNotFound)?;
+
+                ensure!(cred.issuer == issuer_did, Error::<T>::NotAuthorized);
+                ensure!(cred.status == CredentialStatus::Active, Error::<T>::InvalidCredentialStatus);
+
+                cred.expires_at = new_expires_at;
+                cred.metadata_hash = Self::generate_metadata_hash(
+                    cred.issued_at,
+                    new_expires_at,
+                    &cred.status,
+                );
+                Ok(())
+            })?;
+
+            // Move the credential from its old expiry slot to the new one
+            Self::unschedule_expiry(&credential_id);
+            Self::schedule_expiry(&credential_id, new_expires_at);
+
+            Self::deposit_event(Event::CredentialRenewed { credential_id, expires_at: new_expires_at });
+
+            Ok(())
+        }
+
+        /// Verify a credential; an expired or revoked credential is reported through
+        /// `CredentialVerificationFailed` rather than an error, so the outcome is kept on chain
+        ///
+        /// Breaking: such credentials used to fail with `CredentialExpired`/`CredentialRevoked`.
+        /// The call now succeeds for them, so callers must check for `CredentialVerified`
+        /// instead of treating a successful extrinsic as a valid credential
         #[pallet::call_index(2)]
         #[pallet::weight(<T as Config>::WeightInfo::verify_credential())]
         pub fn verify_credential(
@@ -507,10 +798,22 @@ pub mod pallet {
                     &CredentialStatus::Expired,
                 );
                 Credentials::<T>::insert(&credential_id, credential.clone());
-                return Err(Error::<T>::CredentialExpired.into());
+                Self::deposit_event(Event::CredentialVerificationFailed {
+                    credential_id,
+                    reason: CredentialStatus::Expired,
+                    verifier: who,
+                });
+                return Ok(());
             }
 
-            ensure!(credential.status == CredentialStatus::Active, Error::<T>::CredentialRevoked);
+            if credential.status != CredentialStatus::Active {
+                Self::deposit_event(Event::CredentialVerificationFailed {
+                    credential_id,
+                    reason: credential.status,
+                    verifier: who,
+                });
+                return Ok(());
+            }
 
             ensure!(
                 IdentityRegistryPallet::<T>::is_identity_active(&credential.issuer),
@@ -539,7 +842,11 @@ pub mod pallet {
             let (creator_did, _) = IdentityRegistryPallet::<T>::get_identity_by_account(&who)
                 .ok_or(Error::<T>::IssuerIdentityNotFound)?;
 
-            // Validate schema parameters early
+            // Validate schema parameters early; the field limit matches credential issuance
+            ensure!(
+                fields.len() <= Self::max_schema_fields() as usize,
+                Error::<T>::TooManyFields
+            );
             ensure!(
                 Self::validate_schema_params(&fields, &required_fields),
                 Error::<T>::InvalidSchema
@@ -624,6 +931,112 @@ pub mod pallet {
             Ok(())
         }
 
+        /// Set or clear the default validity period for a credential type (requires root/governance)
+        #[pallet::call_index(8)]
+        #[pallet::weight(<T as Config>::WeightInfo::set_default_expiry())]
+        pub fn set_default_expiry(
+            origin: OriginFor<T>,
+            credential_type: CredentialType,
+            validity: Option<u64>,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+
+            match validity {
+                Some(period) => {
+                    ensure!(
+                        period > 0 && period <= T::MaxCredentialValidity::get(),
+                        Error::<T>::InvalidCredentialStatus
+                    );
+                    DefaultExpiry::<T>::insert(&credential_type, period);
+                },
+                None => DefaultExpiry::<T>::remove(&credential_type),
+            }
+
+            Self::deposit_event(Event::DefaultExpirySet { credential_type, validity });
+
+            Ok(())
+        }
+
+        /// Mark a credential type as revocable or not (requires root/governance)
+        #[pallet::call_index(9)]
+        #[pallet::weight(<T as Config>::WeightInfo::set_credential_type_revocable())]
+        pub fn set_credential_type_revocable(
+            origin: OriginFor<T>,
+            credential_type: CredentialType,
+            revocable: bool,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+
+            if revocable {
+                NonRevocableTypes::<T>::remove(&credential_type);
+            } else {
+                NonRevocableTypes::<T>::insert(&credential_type, true);
+            }
+
+            Self::deposit_event(Event::CredentialTypeRevocabilitySet { credential_type, revocable });
+
+            Ok(())
+        }
+
+        /// Allow or forbid issuers issuing a credential type to themselves (requires root/governance)
+        #[pallet::call_index(11)]
+        #[pallet::weight(<T as Config>::WeightInfo::set_self_issuance_allowed())]
+        pub fn set_self_issuance_allowed(
+            origin: OriginFor<T>,
+            credential_type: CredentialType,
+            allowed: bool,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+
+            SelfIssuancePolicy::<T>::insert(&credential_type, allowed);
+
+            Self::deposit_event(Event::SelfIssuancePolicySet { credential_type, allowed });
+
+            Ok(())
+        }
+
+        /// Revoke every credential an issuer has issued (requires root/governance);
+        /// processed in `on_idle` over as many blocks as it takes
+        #[pallet::call_index(12)]
+        #[pallet::weight(<T as Config>::WeightInfo::revoke_issuer_credentials())]
+        pub fn revoke_issuer_credentials(
+            origin: OriginFor<T>,
+            issuer_did: H256,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+
+            ensure!(
+                !IssuerRevocationCursor::<T>::contains_key(&issuer_did),
+                Error::<T>::IssuerRevocationInProgress
+            );
+            let total = IssuedBy::<T>::decode_len(&issuer_did).unwrap_or(0) as u32;
+            ensure!(total > 0, Error::<T>::CredentialNotFound);
+
+            IssuerRevocationCursor::<T>::insert(&issuer_did, (0u32, None::<H256>));
+
+            Self::deposit_event(Event::IssuerRevocationQueued { issuer: issuer_did, total });
+
+            Ok(())
+        }
+
+        /// Drop a queued issuer revocation (requires root/governance); credentials already
+        /// revoked stay revoked and the issuer can issue again
+        #[pallet::call_index(13)]
+        #[pallet::weight(<T as Config>::WeightInfo::cancel_issuer_revocation())]
+        pub fn cancel_issuer_revocation(
+            origin: OriginFor<T>,
+            issuer_did: H256,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+
+            let (processed, _) = IssuerRevocationCursor::<T>::take(&issuer_did)
+                .ok_or(Error::<T>::NoIssuerRevocation)?;
+
+            Self::deposit_event(Event::IssuerRevocationCancelled { issuer: issuer_did, processed });
+
+            Ok(())
+        }
+
         /// Selective disclosure with  ZK proof verification
         #[pallet::call_index(6)]
         #[pallet::weight(<T as Config>::WeightInfo::selective_disclosure())]
@@ -708,6 +1121,13 @@ pub mod pallet {
 
             DisclosureRecords::<T>::insert(&disclosure_id, disclosure_request);
 
+            let sequence = CredentialDisclosureCount::<T>::mutate(&credential_id, |count| {
+                let sequence = *count;
+                *count = count.saturating_add(1);
+                sequence
+            });
+            CredentialDisclosureIds::<T>::insert(&credential_id, sequence, disclosure_id);
+
             Self::record_field_disclosure(&credential_id, &fields_to_reveal);
 
             Self::deposit_event(Event::DisclosureProofVerified {
@@ -749,6 +1169,20 @@ pub mod pallet {
         pub fn get_credentials_count(subject_did: H256) -> u32 {
             CredentialsOf::<T>::get(&subject_did).len() as u32
         }
+
+        /// Indices of the fields marked as encrypted in a credential
+        pub fn get_encrypted_fields(credential_id: &H256) -> Vec<u32> {
+            Credentials::<T>::get(credential_id)
+                .map(|credential| {
+                    credential.encrypted_fields
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, encrypted)| **encrypted)
+                        .map(|(idx, _)| idx as u32)
+                        .collect()
+                })
+                .unwrap_or_default()
+        }
     }
 
     impl<T: Config> Pallet<T> {
@@ -897,15 +1331,126 @@ pub mod pallet {
             Ok(())
         }
 
+        /// Bound raw field vectors and check reveal indices against the field count
+        fn bound_credential_fields(
+            fields: Vec<Vec<u8>>,
+            required_fields: Vec<bool>,
+            fields_to_reveal: Vec<u32>,
+        ) -> Result<(
+            BoundedVec<BoundedVec<u8, T::MaxFieldSize>, T::MaxFields>,
+            BoundedVec<bool, T::MaxFields>,
+            BoundedVec<u32, T::MaxFieldsToReveal>,
+        ), Error<T>> {
+            let inner_fields = fields
+                .into_iter()
+                .map(|f| {
+                    BoundedVec::<u8, T::MaxFieldSize>::try_from(f)
+                        .map_err(|_| Error::<T>::FieldTooLarge)
+                })
+                .collect::<Result<Vec<_>, Error<T>>>()?;
+
+            let bounded_fields: BoundedVec<BoundedVec<u8, T::MaxFieldSize>, T::MaxFields> =
+                BoundedVec::try_from(inner_fields)
+                    .map_err(|_| Error::<T>::TooManyFields)?;
+
+            let bounded_required: BoundedVec<bool, T::MaxFields> =
+                BoundedVec::try_from(required_fields)
+                    .map_err(|_| Error::<T>::TooManyFields)?;
+
+            let bounded_reveal: BoundedVec<u32, T::MaxFieldsToReveal> =
+                BoundedVec::try_from(fields_to_reveal)
+                    .map_err(|_| Error::<T>::TooManyFieldsToReveal)?;
+
+            ensure!(
+                bounded_fields.len() == bounded_required.len(),
+                Error::<T>::InvalidFieldsLength
+            );
+
+            let fields_len_u32: u32 = bounded_fields.len()
+                .try_into()
+                .expect("bounded_fields.len() fits into u32");
+
+            for idx in bounded_reveal.iter() {
+                ensure!(*idx < fields_len_u32, Error::<T>::InvalidRevealIndex);
+            }
+
+            Ok((bounded_fields, bounded_required, bounded_reveal))
+        }
+
+        /// Bound the per-field encryption flags and check them against the field values
+        fn bound_encrypted_fields(
+            fields: &[BoundedVec<u8, T::MaxFieldSize>],
+            encrypted_fields: Vec<bool>,
+        ) -> Result<BoundedVec<bool, T::MaxFields>, Error<T>> {
+            let bounded_encrypted: BoundedVec<bool, T::MaxFields> =
+                BoundedVec::try_from(encrypted_fields)
+                    .map_err(|_| Error::<T>::TooManyFields)?;
+
+            ensure!(
+                fields.len() == bounded_encrypted.len(),
+                Error::<T>::InvalidFieldsLength
+            );
+
+            Self::validate_field_privacy(fields, &bounded_encrypted)?;
+            Ok(bounded_encrypted)
+        }
+
+        /// Resolve a requested expiry (0 = type default) and check it is acceptable
+        fn resolve_expiry(
+            credential_type: &CredentialType,
+            expires_at: u64,
+            now: u64,
+        ) -> Result<u64, Error<T>> {
+            let expires_at = if expires_at == 0 {
+                DefaultExpiry::<T>::get(credential_type)
+                    .map(|validity| now.saturating_add(validity))
+                    .unwrap_or(0)
+            } else {
+                expires_at
+            };
+            ensure!(
+                Self::validate_expiration_timestamp(expires_at),
+                Error::<T>::InvalidCredentialStatus
+            );
+            Ok(expires_at)
+        }
+
+        /// Enforce size discipline on plaintext and encrypted fields
+        fn validate_field_privacy(
+            fields: &[BoundedVec<u8, T::MaxFieldSize>],
+            encrypted_fields: &[bool],
+        ) -> Result<(), Error<T>> {
+            for (field, encrypted) in fields.iter().zip(encrypted_fields.iter()) {
+                if *encrypted {
+                    ensure!(
+                        field.len() as u32 >= T::MinCiphertextLength::get(),
+                        Error::<T>::CiphertextTooShort
+                    );
+                } else if T::StrictFieldPrivacy::get() {
+                    ensure!(
+                        field.len() as u32 <= T::MaxPlaintextFieldSize::get(),
+                        Error::<T>::PlaintextFieldTooLarge
+                    );
+                }
+            }
+            Ok(())
+        }
+
+        /// Fields a schema may declare: `MaxFields`, so every schema can be issued against,
+        /// capped by the 100-field bound of `CredentialSchema`
+        pub fn max_schema_fields() -> u32 {
+            /// Maximum fields per credential schema (storage bound)
+            const MAX_SCHEMA_FIELDS: u32 = 100;
+
+            T::MaxFields::get().min(MAX_SCHEMA_FIELDS)
+        }
+
         /// Validate credential schema parameters
         fn validate_schema_params(
             fields: &[Vec<u8>],
             required_fields: &[bool],
         ) -> bool {
-            /// Maximum fields per credential schema
-            const MAX_SCHEMA_FIELDS: u32 = 100;
-
-            /// Maximum length of individual field name
+            /// Maximum length of individual field name (a name, not a value, so independent of `MaxFieldSize`)
             const MAX_FIELD_NAME_LENGTH: u32 = 64;
             
             if fields.len() != required_fields.len() {
@@ -916,7 +1461,7 @@ pub mod pallet {
                 return false;
             }
 
-            if fields.len() > MAX_SCHEMA_FIELDS as usize {
+            if fields.len() > Self::max_schema_fields() as usize {
                 return false;
             }
 
@@ -979,6 +1524,30 @@ pub mod pallet {
             }
         }
 
+        /// Current status of each credential, reporting lapsed credentials as expired
+        pub fn statuses_of(
+            credential_ids: BoundedVec<H256, ConstU32<MAX_STATUS_QUERIES>>,
+        ) -> Vec<(H256, Option<CredentialStatus>)> {
+            let now = <T as crate::pallet::Config>::TimeProvider::now().saturated_into::<u64>();
+
+            credential_ids
+                .into_iter()
+                .map(|credential_id| {
+                    let status = Credentials::<T>::get(&credential_id).map(|credential| {
+                        if credential.status == CredentialStatus::Active
+                            && credential.expires_at > 0
+                            && now > credential.expires_at
+                        {
+                            CredentialStatus::Expired
+                        } else {
+                            credential.status
+                        }
+                    });
+                    (credential_id, status)
+                })
+                .collect()
+        }
+
         fn generate_metadata_hash(
             issued_at: u64,
             expires_at: u64,
@@ -1068,6 +1637,8 @@ pub mod pallet {
         }
 
         /// Get all disclosures for a credential
+        /// Scans every disclosure record; only suitable for off-chain tooling on small states
+        #[deprecated(note = "full storage scan; use `get_credential_disclosures_paginated`")]
         pub fn get_credential_disclosures(
             credential_id: &H256,
         ) -> Vec<(H256, SelectiveDisclosureRequest)> {
@@ -1076,6 +1647,26 @@ pub mod pallet {
                 .collect()
         }
 
+        /// Get one page of a credential's disclosures in recording order
+        pub fn get_credential_disclosures_paginated(
+            credential_id: &H256,
+            page: u32,
+            page_size: u32,
+        ) -> Vec<(H256, SelectiveDisclosureRequest)> {
+            let page_size = page_size.min(100);
+            let count = CredentialDisclosureCount::<T>::get(credential_id);
+
+            let start = page.saturating_mul(page_size);
+            let end = start.saturating_add(page_size).min(count);
+
+            (start..end)
+                .filter_map(|sequence| CredentialDisclosureIds::<T>::get(credential_id, sequence))
+                .filter_map(|disclosure_id| {
+                    DisclosureRecords::<T>::get(&disclosure_id).map(|request| (disclosure_id, request))
+                })
+                .collect()
+        }
+
         /// Check if a specific field has been disclosed
         pub fn has_field_been_disclosed(
             credential_id: &H256,
@@ -1088,9 +1679,7 @@ pub mod pallet {
         pub fn get_disclosure_statistics(
             credential_id: &H256,
         ) -> (u32, u32) {
-            let disclosures = DisclosureRecords::<T>::iter()
-                .filter(|(_, req)| req.credential_id == *credential_id)
-                .count() as u32;
+            let disclosures = CredentialDisclosureCount::<T>::get(credential_id);
 
             let unique_fields = FieldDisclosureCount::<T>::iter_prefix(credential_id)
                 .count() as u32;
@@ -1161,8 +1750,28 @@ pub mod pallet {
             // Example: Update credential format to add new fields
         }
         
+        /// Add a credential to the expiry slot for `expires_at` (0 = never expires)
+        fn schedule_expiry(credential_id: &H256, expires_at: u64) {
+            if expires_at == 0 {
+                return;
+            }
+            let expiry_block = expires_at / 6;
+            let pushed = Expiries::<T>::mutate(expiry_block, |list| list.try_push(*credential_id).is_ok());
+            if pushed {
+                CredentialExpirySlot::<T>::insert(credential_id, expiry_block);
+            }
+        }
+
+        /// Remove a credential from its current expiry slot using the reverse index
+        fn unschedule_expiry(credential_id: &H256) {
+            if let Some(slot) = CredentialExpirySlot::<T>::take(credential_id) {
+                Expiries::<T>::mutate(slot, |list| list.retain(|id| id != credential_id));
+            }
+        }
+
         /// Clean up expired credentials based on the Expiries queue
-        pub fn cleanup_expired_credentials(current_time_u64: u64) -> u32 {
+        /// Returns (credentials removed, revoked IDs pruned from the revocation tree)
+        pub fn cleanup_expired_credentials(current_time_u64: u64) -> (u32, u32) {
             // Convert current time to approximate block number
             let current_block_approx = current_time_u64 / 6;
             
@@ -1170,7 +1779,10 @@ pub mod pallet {
             let expired_ids = Expiries::<T>::take(current_block_approx);
             
             let mut count = 0;
+            let mut pruned = 0;
             for cred_id in expired_ids {
+                CredentialExpirySlot::<T>::remove(&cred_id);
+
                 // 1. Get the credential first so we know who owns it (Subject/Issuer)
                 if let Some(credential) = Credentials::<T>::take(&cred_id) {
                     
@@ -1189,10 +1801,16 @@ pub mod pallet {
                         }
                     });
 
+                    // 4. A deleted credential no longer needs a slot in the revocation tree
+                    if credential.status == CredentialStatus::Revoked {
+                        Self::update_revocation_tree(cred_id, false);
+                        pruned += 1;
+                    }
+
                     count += 1;
                 }
             }
-            count
+            (count, pruned)
         }
     }
 
@@ -1207,6 +1825,60 @@ pub mod pallet {
             CredentialsOf::<T>::get(&subject_did).to_vec()
         }
         
+        /// Dry-run every `issue_credential` check without writing to storage
+        pub fn validate_issuance(
+            issuer_did: &H256,
+            subject_did: &H256,
+            credential_type: &CredentialType,
+            expires_at: u64,
+            fields: Vec<Vec<u8>>,
+            required_fields: Vec<bool>,
+            fields_to_reveal: Vec<u32>,
+            encrypted_fields: Vec<bool>,
+        ) -> Result<(), Error<T>> {
+            let issuer_identity = pallet_identity_registry::pallet::Identities::<T>::get(issuer_did)
+                .ok_or(Error::<T>::IssuerIdentityNotFound)?;
+            ensure!(issuer_identity.active, Error::<T>::IssuerInactive);
+
+            ensure!(
+                IdentityRegistryPallet::<T>::is_identity_active(subject_did),
+                Error::<T>::SubjectIdentityNotFound
+            );
+
+            ensure!(
+                TrustedIssuers::<T>::get((credential_type, issuer_did)),
+                Error::<T>::IssuerNotTrusted
+            );
+            ensure!(
+                issuer_did != subject_did || Self::is_self_issuance_allowed(credential_type),
+                Error::<T>::SelfIssuanceNotAllowed
+            );
+            ensure!(
+                !IssuerRevocationCursor::<T>::contains_key(issuer_did),
+                Error::<T>::IssuerRevocationInProgress
+            );
+
+            let now = <T as Config>::TimeProvider::now().saturated_into::<u64>();
+            Self::resolve_expiry(credential_type, expires_at, now)?;
+
+            let (bounded_fields, _, _) =
+                Self::bound_credential_fields(fields, required_fields, fields_to_reveal)?;
+            Self::bound_encrypted_fields(&bounded_fields, encrypted_fields)?;
+
+            ensure!(
+                !CredentialsOf::<T>::get(subject_did).is_full() && !IssuedBy::<T>::get(issuer_did).is_full(),
+                Error::<T>::TooManyCredentials
+            );
+
+            Ok(())
+        }
+
+        /// Whether an issuer may issue `credential_type` to its own DID
+        pub fn is_self_issuance_allowed(credential_type: &CredentialType) -> bool {
+            SelfIssuancePolicy::<T>::get(credential_type)
+                .unwrap_or(*credential_type == CredentialType::Custom)
+        }
+
         /// Check if issuer is trusted for credential type
         pub fn is_issuer_trusted(issuer_did: &H256, cred_type: &CredentialType) -> bool {
             TrustedIssuers::<T>::get((cred_type, issuer_did))
@@ -1228,6 +1900,309 @@ pub mod pallet {
     }
 
     impl<T: Config> Pallet<T> {
+        /// Build a proof that `credential_id` is not in the current revocation set
+        /// Returns `None` if the credential has been revoked
+        pub fn build_non_revocation_proof(credential_id: H256) -> Option<NonRevocationProof> {
+            if RevokedCredentialChunks::<T>::contains_key(&credential_id) {
+                return None;
+            }
+            let bucket_index = Self::revocation_bucket_index(&credential_id);
+            let chunks = Self::revocation_chunk_count(RevokedBucketLengths::<T>::get(bucket_index));
+            let bucket = (0..chunks)
+                .map(|chunk| RevokedCredentialBuckets::<T>::get(bucket_index, chunk).into_inner())
+                .collect();
+
+            let path = (0..REVOCATION_TREE_DEPTH)
+                .map(|level| RevocationTreeNodes::<T>::get((level, (bucket_index >> level) ^ 1)))
+                .collect();
+
+            Some(NonRevocationProof {
+                credential_id,
+                version: RevocationAccumulatorState::<T>::get().version,
+                bucket,
+                path,
+            })
+        }
+
+        /// Check a non-revocation proof against an accumulator, without reading storage
+        pub fn verify_non_revocation_proof(
+            proof: &NonRevocationProof,
+            accumulator: &RevocationAccumulator,
+        ) -> bool {
+            if proof.version != accumulator.version || proof.path.len() != REVOCATION_TREE_DEPTH as usize {
+                return false;
+            }
+
+            // The chunks must hold only IDs of the credential's own bucket, and not the credential
+            let bucket_index = Self::revocation_bucket_index(&proof.credential_id);
+            let ids = || proof.bucket.iter().flatten();
+            if proof.bucket.len() as u64 > 1u64 << REVOCATION_CHUNK_DEPTH
+                || !ids().all(|id| Self::revocation_bucket_index(id) == bucket_index)
+                || ids().any(|id| *id == proof.credential_id)
+            {
+                return false;
+            }
+
+            // Rebuild the bucket's leaf from its chunk tree, then walk the path to the root
+            let mut level: Vec<H256> = proof.bucket.iter().map(|chunk| Self::revocation_chunk_digest(chunk)).collect();
+            for _ in 0..REVOCATION_CHUNK_DEPTH {
+                level = level
+                    .chunks(2)
+                    .map(|pair| Self::revocation_node(&pair[0], pair.get(1).unwrap_or(&H256::zero())))
+                    .collect();
+            }
+            let mut node = level.first().copied().unwrap_or_else(H256::zero);
+            let mut index = bucket_index;
+            for sibling in proof.path.iter() {
+                node = if index % 2 == 0 {
+                    Self::revocation_node(&node, sibling)
+                } else {
+                    Self::revocation_node(sibling, &node)
+                };
+                index /= 2;
+            }
+            node == accumulator.root
+        }
+
+        /// Weight of one revocation tree update, at the worst-case chunk size
+        pub fn revocation_tree_weight() -> Weight {
+            <T as Config>::WeightInfo::update_revocation_tree(T::MaxRevokedPerChunk::get())
+        }
+
+        /// Advance one queued issuer revocation by up to `MaxCredentialCleanupPerBlock`
+        /// credentials, within `remaining_weight`
+        pub fn process_issuer_revocation(remaining_weight: Weight) -> Weight {
+            let db = T::DbWeight::get();
+            let mut consumed = db.reads(1);
+            if remaining_weight.any_lt(consumed) {
+                return Weight::zero();
+            }
+            let Some((issuer, (next_index, last_processed))) = IssuerRevocationCursor::<T>::iter().next() else {
+                return consumed;
+            };
+
+            let per_credential = <T as Config>::WeightInfo::revoke_credential()
+                .saturating_add(Self::revocation_tree_weight());
+            let overhead = db.reads_writes(1, 1);
+            let affordable = remaining_weight
+                .saturating_sub(consumed.saturating_add(overhead))
+                .checked_div_per_component(&per_credential)
+                .unwrap_or(0);
+            let budget = affordable.min(T::MaxCredentialCleanupPerBlock::get() as u64) as usize;
+            if budget == 0 {
+                return consumed;
+            }
+            consumed = consumed.saturating_add(overhead);
+
+            let issued = IssuedBy::<T>::get(&issuer);
+            // Expiry cleanup can shift the list under the cursor; rescan from the start if
+            // the last processed credential is no longer where we left it
+            let start = match last_processed {
+                Some(last) if next_index > 0 && issued.get(next_index as usize - 1) == Some(&last) => next_index as usize,
+                Some(last) => issued.iter().position(|id| *id == last).map_or(0, |pos| pos + 1),
+                None => next_index as usize,
+            }
+            .min(issued.len());
+            let end = start.saturating_add(budget).min(issued.len());
+
+            for credential_id in issued.iter().take(end).skip(start) {
+                consumed = consumed.saturating_add(per_credential);
+
+                let revocable = Credentials::<T>::get(credential_id).map_or(false, |cred| {
+                    cred.status == CredentialStatus::Active
+                        && !NonRevocableTypes::<T>::get(&cred.credential_type)
+                });
+                if !revocable {
+                    continue;
+                }
+                Self::record_revocation(*credential_id);
+                Credentials::<T>::mutate(credential_id, |cred_opt| {
+                    if let Some(cred) = cred_opt {
+                        cred.status = CredentialStatus::Revoked;
+                    }
+                });
+
+                Self::deposit_event(Event::CredentialRevoked { credential_id: *credential_id, issuer });
+            }
+
+            let total = issued.len() as u32;
+            Self::deposit_event(Event::IssuerRevocationProgress { issuer, processed: end as u32, total });
+
+            if end >= issued.len() {
+                IssuerRevocationCursor::<T>::remove(&issuer);
+                Self::deposit_event(Event::IssuerRevocationCompleted { issuer });
+            } else {
+                let last_processed = end.checked_sub(1).map(|last| issued[last]);
+                IssuerRevocationCursor::<T>::insert(&issuer, (end as u32, last_processed));
+            }
+
+            consumed
+        }
+
+        /// Add a revoked ID to the revocation tree
+        fn record_revocation(credential_id: H256) {
+            Self::update_revocation_tree(credential_id, true)
+        }
+
+        /// Insert (`revoked`) or remove an ID in its bucket and rehash the bucket's path to the root
+        /// Buckets are uncapped, so a revocation can never be refused by the tree
+        pub(crate) fn update_revocation_tree(credential_id: H256, revoked: bool) {
+            let changed = if revoked {
+                Self::insert_revoked_id(credential_id)
+            } else {
+                Self::remove_revoked_id(credential_id)
+            };
+            let Some(root) = changed else {
+                return;
+            };
+
+            let accumulator = RevocationAccumulatorState::<T>::mutate(|accumulator| {
+                accumulator.version = accumulator.version.saturating_add(1);
+                accumulator.root = root;
+                accumulator.size = if revoked {
+                    accumulator.size.saturating_add(1)
+                } else {
+                    accumulator.size.saturating_sub(1)
+                };
+                accumulator.clone()
+            });
+
+            Self::deposit_event(Event::RevocationAccumulatorUpdated {
+                version: accumulator.version,
+                root: accumulator.root,
+                size: accumulator.size,
+            });
+        }
+
+        /// Append an ID to the open chunk of its bucket, returning the new root if it was absent
+        pub(crate) fn insert_revoked_id(credential_id: H256) -> Option<H256> {
+            if RevokedCredentialChunks::<T>::contains_key(&credential_id) {
+                return None;
+            }
+            let bucket_index = Self::revocation_bucket_index(&credential_id);
+            let length = RevokedBucketLengths::<T>::get(bucket_index);
+            let chunk = length / T::MaxRevokedPerChunk::get();
+
+            // The open chunk holds `length % MaxRevokedPerChunk` IDs, so it always has room
+            RevokedCredentialBuckets::<T>::mutate(bucket_index, chunk, |ids| {
+                let _ = ids.try_push(credential_id);
+            });
+            RevokedCredentialChunks::<T>::insert(&credential_id, chunk);
+            RevokedBucketLengths::<T>::insert(bucket_index, length.saturating_add(1));
+
+            let leaf = Self::refresh_revocation_chunk(bucket_index, chunk);
+            Some(Self::set_revocation_bucket_leaf(bucket_index, leaf))
+        }
+
+        /// Remove an ID from its bucket, moving the bucket's last ID into its place so chunks
+        /// stay full; returns the new root if the ID was present
+        fn remove_revoked_id(credential_id: H256) -> Option<H256> {
+            let chunk = RevokedCredentialChunks::<T>::take(&credential_id)?;
+            let bucket_index = Self::revocation_bucket_index(&credential_id);
+            let length = RevokedBucketLengths::<T>::get(bucket_index).saturating_sub(1);
+            let last_chunk = length / T::MaxRevokedPerChunk::get();
+
+            let last = RevokedCredentialBuckets::<T>::mutate(bucket_index, last_chunk, |ids| ids.pop());
+            if let Some(last) = last.filter(|last| *last != credential_id) {
+                RevokedCredentialBuckets::<T>::mutate(bucket_index, chunk, |ids| {
+                    if let Some(slot) = ids.iter_mut().find(|id| **id == credential_id) {
+                        *slot = last;
+                    }
+                });
+                RevokedCredentialChunks::<T>::insert(&last, chunk);
+            }
+            if length == 0 {
+                RevokedBucketLengths::<T>::remove(bucket_index);
+            } else {
+                RevokedBucketLengths::<T>::insert(bucket_index, length);
+            }
+
+            let mut leaf = Self::refresh_revocation_chunk(bucket_index, chunk);
+            if last_chunk != chunk {
+                leaf = Self::refresh_revocation_chunk(bucket_index, last_chunk);
+            }
+            Some(Self::set_revocation_bucket_leaf(bucket_index, leaf))
+        }
+
+        /// Rehash one chunk and its path up the bucket's chunk tree, returning the bucket's leaf
+        fn refresh_revocation_chunk(bucket_index: u32, chunk: u32) -> H256 {
+            let ids = RevokedCredentialBuckets::<T>::get(bucket_index, chunk);
+            if ids.is_empty() {
+                RevokedCredentialBuckets::<T>::remove(bucket_index, chunk);
+            }
+
+            let mut node = Self::revocation_chunk_digest(&ids);
+            let mut index = chunk;
+            for level in 0..REVOCATION_CHUNK_DEPTH {
+                if node.is_zero() {
+                    RevocationChunkNodes::<T>::remove((bucket_index, level, index));
+                } else {
+                    RevocationChunkNodes::<T>::insert((bucket_index, level, index), node);
+                }
+                let sibling = RevocationChunkNodes::<T>::get((bucket_index, level, index ^ 1));
+                node = if index % 2 == 0 {
+                    Self::revocation_node(&node, &sibling)
+                } else {
+                    Self::revocation_node(&sibling, &node)
+                };
+                index /= 2;
+            }
+            node
+        }
+
+        /// Store a bucket's leaf and rehash its path to the revocation tree root
+        fn set_revocation_bucket_leaf(bucket_index: u32, leaf: H256) -> H256 {
+            let mut node = leaf;
+            let mut index = bucket_index;
+            for level in 0..REVOCATION_TREE_DEPTH {
+                // Empty subtrees hash to zero and are not stored
+                if node.is_zero() {
+                    RevocationTreeNodes::<T>::remove((level, index));
+                } else {
+                    RevocationTreeNodes::<T>::insert((level, index), node);
+                }
+                let sibling = RevocationTreeNodes::<T>::get((level, index ^ 1));
+                node = if index % 2 == 0 {
+                    Self::revocation_node(&node, &sibling)
+                } else {
+                    Self::revocation_node(&sibling, &node)
+                };
+                index /= 2;
+            }
+            node
+        }
+
+        /// Bucket holding `credential_id`: the top `REVOCATION_TREE_DEPTH` bits of the ID
+        pub(crate) fn revocation_bucket_index(credential_id: &H256) -> u32 {
+            let id = credential_id.as_bytes();
+            u32::from_be_bytes([id[0], id[1], id[2], id[3]]) >> (32 - REVOCATION_TREE_DEPTH)
+        }
+
+        /// Digest committing to a chunk's IDs in stored order (zero when empty)
+        fn revocation_chunk_digest(ids: &[H256]) -> H256 {
+            if ids.is_empty() {
+                return H256::zero();
+            }
+            H256::from(sp_io::hashing::blake2_256(&ids.encode()))
+        }
+
+        /// Chunks in use by a bucket holding `length` IDs
+        fn revocation_chunk_count(length: u32) -> u32 {
+            length.div_ceil(T::MaxRevokedPerChunk::get())
+        }
+
+        fn hash_pair(left: &H256, right: &H256) -> H256 {
+            H256::from(sp_io::hashing::blake2_256(&[left.as_bytes(), right.as_bytes()].concat()))
+        }
+
+        /// Parent of two tree nodes; a fully empty subtree stays zero
+        fn revocation_node(left: &H256, right: &H256) -> H256 {
+            if left.is_zero() && right.is_zero() {
+                return H256::zero();
+            }
+            Self::hash_pair(left, right)
+        }
+
         /// Validate that expiration timestamp is reasonable
         fn validate_expiration_timestamp(expires_at: u64) -> bool {
             let now = <T as crate::pallet::Config>::TimeProvider::now().saturated_into::<u64>();
@@ -1237,8 +2212,8 @@ pub mod pallet {
                 return false;
             }
             
-            // credentials are not valid for more than 100 years
-            let max_validity = 100 * 365 * 24 * 60 * 60;  // 100 years in seconds
+            // credentials are not valid for longer than the configured maximum
+            let max_validity = T::MaxCredentialValidity::get();
             if expires_at != 0 && expires_at.saturating_sub(now) > max_validity {
                 return false;
             }
@@ -1284,6 +2259,10 @@ pub mod pallet {
             let bounded_reveal = BoundedVec::<u32, T::MaxFieldsToReveal>::try_from(fields_to_reveal)
                     .unwrap_or_default();
 
+            let bounded_encrypted = BoundedVec::<bool, T::MaxFields>::try_from(
+                vec![false; bounded_fields.len()]
+            ).unwrap_or_default();
+
             let credential = Credential::<T> {
                 subject: subject_did,
                 issuer: issuer_did,
@@ -1297,6 +2276,7 @@ pub mod pallet {
                 fields: bounded_fields,
                 required_fields: bounded_required,
                 fields_to_reveal: bounded_reveal,
+                encrypted_fields: bounded_encrypted,
             };
             
             let credential_id = Self::generate_credential_id(&credential);
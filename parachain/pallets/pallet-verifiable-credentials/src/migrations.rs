@@ -0,0 +1,162 @@
+//! Storage migrations for the verifiable credentials pallet
+
+use crate::pallet::{
+    Config, Credential, CredentialDisclosureCount, CredentialDisclosureIds, CredentialStatus,
+    CredentialType, Credentials, DisclosureRecords, Pallet, RevocationAccumulatorState,
+    RevocationTreeNodes,
+};
+use codec::{Decode, Encode};
+use frame_support::{
+    migrations::VersionedMigration,
+    traits::{Get, UncheckedOnRuntimeUpgrade},
+    weights::Weight,
+    Blake2_128Concat, BoundedVec,
+};
+use sp_core::H256;
+use sp_std::{marker::PhantomData, vec, vec::Vec};
+
+/// v1: credentials carry per-field `encrypted_fields` markers
+pub mod v1 {
+    use super::*;
+
+    /// Credential as stored before `encrypted_fields` was added
+    #[derive(Encode, Decode)]
+    pub struct OldCredential<T: Config> {
+        pub subject: H256,
+        pub issuer: H256,
+        pub credential_type: CredentialType,
+        pub data_hash: H256,
+        pub issued_at: u64,
+        pub expires_at: u64,
+        pub status: CredentialStatus,
+        pub signature: H256,
+        pub metadata_hash: H256,
+        pub fields: BoundedVec<BoundedVec<u8, T::MaxFieldSize>, T::MaxFields>,
+        pub required_fields: BoundedVec<bool, T::MaxFields>,
+        pub fields_to_reveal: BoundedVec<u32, T::MaxFieldsToReveal>,
+    }
+
+    /// Existing credentials were issued in plaintext: mark every field unencrypted
+    pub struct UncheckedMigrateToV1<T>(PhantomData<T>);
+
+    impl<T: Config> UncheckedOnRuntimeUpgrade for UncheckedMigrateToV1<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let mut translated = 0u64;
+            Credentials::<T>::translate::<OldCredential<T>, _>(|_, old| {
+                translated += 1;
+                // Same bound as `fields`, so the flags always fit
+                let encrypted_fields = BoundedVec::truncate_from(vec![false; old.fields.len()]);
+                Some(Credential {
+                    subject: old.subject,
+                    issuer: old.issuer,
+                    credential_type: old.credential_type,
+                    data_hash: old.data_hash,
+                    issued_at: old.issued_at,
+                    expires_at: old.expires_at,
+                    status: old.status,
+                    signature: old.signature,
+                    metadata_hash: old.metadata_hash,
+                    fields: old.fields,
+                    required_fields: old.required_fields,
+                    fields_to_reveal: old.fields_to_reveal,
+                    encrypted_fields,
+                })
+            });
+            T::DbWeight::get().reads_writes(translated, translated)
+        }
+    }
+
+    pub type MigrateToV1<T> = VersionedMigration<
+        0,
+        1,
+        UncheckedMigrateToV1<T>,
+        Pallet<T>,
+        <T as frame_system::Config>::DbWeight,
+    >;
+}
+
+/// v2: disclosures are indexed per credential
+pub mod v2 {
+    use super::*;
+
+    /// Index the disclosures recorded before `CredentialDisclosureIds` existed, in
+    /// timestamp order (ties broken by disclosure ID), and set each credential's count
+    pub struct UncheckedMigrateToV2<T>(PhantomData<T>);
+
+    impl<T: Config> UncheckedOnRuntimeUpgrade for UncheckedMigrateToV2<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let mut disclosures: Vec<(H256, u64, H256)> = DisclosureRecords::<T>::iter()
+                .map(|(disclosure_id, request)| (request.credential_id, request.timestamp, disclosure_id))
+                .collect();
+            disclosures.sort();
+
+            let indexed = disclosures.len() as u64;
+            for (credential_id, _, disclosure_id) in disclosures {
+                let sequence = CredentialDisclosureCount::<T>::mutate(&credential_id, |count| {
+                    let sequence = *count;
+                    *count = count.saturating_add(1);
+                    sequence
+                });
+                CredentialDisclosureIds::<T>::insert(&credential_id, sequence, disclosure_id);
+            }
+
+            // One read per record, then a count read-modify-write and an index write each
+            T::DbWeight::get().reads_writes(indexed.saturating_mul(2), indexed.saturating_mul(2))
+        }
+    }
+
+    pub type MigrateToV2<T> = VersionedMigration<
+        1,
+        2,
+        UncheckedMigrateToV2<T>,
+        Pallet<T>,
+        <T as frame_system::Config>::DbWeight,
+    >;
+}
+
+/// v3: revocation tree buckets are stored in chunks and no longer capped
+pub mod v3 {
+    use super::*;
+
+    /// Buckets as stored before chunking: one sorted, capped list per bucket
+    #[frame_support::storage_alias]
+    pub type RevokedCredentialBuckets<T: Config> =
+        StorageMap<Pallet<T>, Blake2_128Concat, u32, Vec<H256>, ValueQuery>;
+
+    /// Re-insert every revoked ID into the chunked buckets and rebuild the tree, whose
+    /// bucket leaves now commit to a chunk tree instead of the sorted list
+    pub struct UncheckedMigrateToV3<T>(PhantomData<T>);
+
+    impl<T: Config> UncheckedOnRuntimeUpgrade for UncheckedMigrateToV3<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let revoked: Vec<H256> = RevokedCredentialBuckets::<T>::drain()
+                .flat_map(|(_, bucket)| bucket)
+                .collect();
+            let cleared = RevocationTreeNodes::<T>::clear(u32::MAX, None).unique;
+
+            let mut root = H256::zero();
+            for credential_id in revoked.iter() {
+                if let Some(new_root) = Pallet::<T>::insert_revoked_id(*credential_id) {
+                    root = new_root;
+                }
+            }
+            RevocationAccumulatorState::<T>::mutate(|accumulator| {
+                accumulator.version = accumulator.version.saturating_add(1);
+                accumulator.root = root;
+            });
+
+            let rebuilt = revoked.len() as u64;
+            T::DbWeight::get()
+                .reads_writes(u64::from(cleared), u64::from(cleared))
+                .saturating_add(Pallet::<T>::revocation_tree_weight().saturating_mul(rebuilt))
+        }
+    }
+
+    pub type MigrateToV3<T> = VersionedMigration<
+        2,
+        3,
+        UncheckedMigrateToV3<T>,
+        Pallet<T>,
+        <T as frame_system::Config>::DbWeight,
+    >;
+}
@@ -0,0 +1,103 @@
+use crate as pallet_verifiable_credentials;
+use frame_support::{
+    assert_ok, derive_impl, parameter_types,
+    traits::{ConstU32, ConstU64},
+};
+use pallet_zk_credentials::pallet::{ProofType, VerificationKeyData, VerifyingKeys};
+use sp_core::H256;
+use sp_runtime::BuildStorage;
+
+type Block = frame_system::mocking::MockBlock<Test>;
+
+pub const ISSUER: u64 = 1;
+pub const SUBJECT: u64 = 2;
+pub const VERIFIER: u64 = 3;
+
+// Configure a mock runtime for testing
+frame_support::construct_runtime!(
+    pub enum Test {
+        System: frame_system,
+        Timestamp: pallet_timestamp,
+        IdentityRegistry: pallet_identity_registry,
+        ZkCredentials: pallet_zk_credentials,
+        VerifiableCredentials: pallet_verifiable_credentials,
+    }
+);
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for Test {
+    type Block = Block;
+}
+
+impl pallet_timestamp::Config for Test {
+    type Moment = u64;
+    type OnTimestampSet = ();
+    type MinimumPeriod = ConstU64<1>;
+    type WeightInfo = ();
+}
+
+impl pallet_identity_registry::pallet::Config for Test {
+    type TimeProvider = Timestamp;
+    type WeightInfo = ();
+}
+
+impl pallet_zk_credentials::pallet::Config for Test {
+    type WeightInfo = ();
+}
+
+parameter_types! {
+    pub static StrictFieldPrivacy: bool = false;
+    pub static MaxCredentialValidity: u64 = 100 * 365 * 24 * 60 * 60;
+}
+
+impl pallet_verifiable_credentials::pallet::Config for Test {
+    type TimeProvider = Timestamp;
+    type ZkCredentials = Test;
+    type WeightInfo = ();
+    type MaxFieldSize = ConstU32<256>;
+    type MaxFields = ConstU32<16>;
+    type MaxFieldsToReveal = ConstU32<16>;
+    type MaxCredentialCleanupPerBlock = ConstU32<10>;
+    type StrictFieldPrivacy = StrictFieldPrivacy;
+    type MaxPlaintextFieldSize = ConstU32<32>;
+    type MinCiphertextLength = ConstU32<28>;
+    type MaxCredentialValidity = MaxCredentialValidity;
+    type MaxRevokedPerChunk = ConstU32<2>;
+    type MaxRevocationsPerCall = ConstU32<2>;
+}
+
+// Test helpers
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    let storage = frame_system::GenesisConfig::<Test>::default()
+        .build_storage()
+        .unwrap();
+
+    let mut ext: sp_io::TestExternalities = storage.into();
+    ext.execute_with(|| {
+        System::set_block_number(1);
+        Timestamp::set_timestamp(1_700_000_000);
+    });
+    ext
+}
+
+pub fn create_test_identity(account: u64, did: &[u8]) -> H256 {
+    assert_ok!(IdentityRegistry::create_identity(
+        RuntimeOrigin::signed(account),
+        did.to_vec(),
+        H256::from_low_u64_be(account),
+    ));
+    IdentityRegistry::hash_did(did)
+}
+
+/// Register a placeholder verifying key so selective disclosure can look one up
+pub fn register_verifying_key(proof_type: ProofType) {
+    VerifyingKeys::<Test>::insert(
+        &proof_type,
+        VerificationKeyData {
+            proof_type: proof_type.clone(),
+            vk_data: vec![1u8; 32].try_into().unwrap(),
+            registered_by: H256::zero(),
+            registered_at: 0,
+        },
+    );
+}
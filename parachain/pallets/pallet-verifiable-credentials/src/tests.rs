@@ -1,349 +1,1006 @@
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate as pallet_verifiable_credentials;
-    use frame_support::{
-        assert_ok, assert_noop, parameter_types,
-        traits::{ConstU32, ConstU64, Time},
-    };
-    use frame_system as system;
-    use sp_core::H256;
-    use sp_runtime::{
-        testing::Header,
-        traits::{BlakeTwo256, IdentityLookup},
-    };
-    use pallet_identity_registry;
-
-    type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
-    type Block = frame_system::mocking::MockBlock<Test>;
-
-    // Configure a mock runtime for testing
-    frame_support::construct_runtime!(
-        pub enum Test where
-            Block = Block,
-            NodeBlock = Block,
-            UncheckedExtrinsic = UncheckedExtrinsic,
-        {
-            System: frame_system,
-            IdentityRegistry: pallet_identity_registry,
-            VerifiableCredentials: pallet_verifiable_credentials,
-            Timestamp: pallet_timestamp,
-        }
-    );
-
-    parameter_types! {
-        pub const BlockHashCount: u64 = 250;
-    }
-
-    impl system::Config for Test {
-        type BaseCallFilter = frame_support::traits::Everything;
-        type BlockWeights = ();
-        type BlockLength = ();
-        type DbWeight = ();
-        type RuntimeOrigin = RuntimeOrigin;
-        type RuntimeCall = RuntimeCall;
-        type Index = u64;
-        type BlockNumber = u64;
-        type Hash = H256;
-        type Hashing = BlakeTwo256;
-        type AccountId = u64;
-        type Lookup = IdentityLookup<Self::AccountId>;
-        type Header = Header;
-        type RuntimeEvent = RuntimeEvent;
-        type BlockHashCount = BlockHashCount;
-        type Version = ();
-        type PalletInfo = PalletInfo;
-        type AccountData = ();
-        type OnNewAccount = ();
-        type OnKilledAccount = ();
-        type SystemWeightInfo = ();
-        type SS58Prefix = ();
-        type OnSetCode = ();
-        type MaxConsumers = ConstU32<16>;
-    }
-
-    impl pallet_timestamp::Config for Test {
-        type Moment = u64;
-        type OnTimestampSet = ();
-        type MinimumPeriod = ConstU64<5>;
-        type WeightInfo = ();
-    }
-
-    impl pallet_identity_registry::Config for Test {
-        type RuntimeEvent = RuntimeEvent;
-        type TimeProvider = Timestamp;
-    }
-
-    impl pallet_verifiable_credentials::Config for Test {
-        type RuntimeEvent = RuntimeEvent;
-        type TimeProvider = Timestamp;
-    }
-
-    // Test helpers
-    fn new_test_ext() -> sp_io::TestExternalities {
-        system::GenesisConfig::default()
-            .build_storage::<Test>()
-            .unwrap()
-            .into()
-    }
-
-    fn create_test_identity(account: u64, did: Vec<u8>) -> H256 {
-        let public_key = H256::from_low_u64_be(account);
+use crate::{mock::*, pallet::*};
+use frame_support::{
+    assert_noop, assert_ok,
+    traits::{Get, GetStorageVersion, Hooks, OnRuntimeUpgrade, StorageVersion},
+    weights::Weight,
+    BoundedVec,
+};
+use pallet_zk_credentials::pallet::ProofType;
+use sp_core::H256;
+
+fn setup_trusted_issuer() -> (H256, H256) {
+    let issuer_did = create_test_identity(ISSUER, b"did:identity:university");
+    let subject_did = create_test_identity(SUBJECT, b"did:identity:student");
+    assert_ok!(VerifiableCredentials::add_trusted_issuer(
+        RuntimeOrigin::root(),
+        CredentialType::Education,
+        issuer_did
+    ));
+    (issuer_did, subject_did)
+}
+
+fn issue_with_fields(
+    subject_did: H256,
+    expires_at: u64,
+    fields: Vec<Vec<u8>>,
+    encrypted_fields: Vec<bool>,
+) -> frame_support::dispatch::DispatchResult {
+    let required_fields = vec![true; fields.len()];
+    VerifiableCredentials::issue_credential(
+        RuntimeOrigin::signed(ISSUER),
+        subject_did,
+        CredentialType::Education,
+        H256::from_low_u64_be(123),
+        expires_at,
+        H256::from_low_u64_be(456),
+        fields,
+        required_fields,
+        vec![],
+        encrypted_fields,
+    )
+}
+
+fn issue_simple(subject_did: H256, expires_at: u64) -> H256 {
+    assert_ok!(issue_with_fields(subject_did, expires_at, vec![], vec![]));
+    *VerifiableCredentials::credentials_of(&subject_did).last().unwrap()
+}
+
+// Tests
+#[test]
+fn test_create_identity_works() {
+    new_test_ext().execute_with(|| {
+        let did = b"did:identity:alice".to_vec();
+        let public_key = H256::from_low_u64_be(1);
+
         assert_ok!(IdentityRegistry::create_identity(
-            RuntimeOrigin::signed(account),
+            RuntimeOrigin::signed(ISSUER),
             did.clone(),
             public_key
         ));
-        IdentityRegistry::hash_did(&did)
-    }
-
-    // Tests
-    #[test]
-    fn test_create_identity_works() {
-        new_test_ext().execute_with(|| {
-            let account = 1u64;
-            let did = b"did:identity:alice".to_vec();
-            let public_key = H256::from_low_u64_be(1);
-
-            assert_ok!(IdentityRegistry::create_identity(
-                RuntimeOrigin::signed(account),
-                did.clone(),
-                public_key
-            ));
-
-            let did_hash = IdentityRegistry::hash_did(&did);
-            let identity = IdentityRegistry::identities(&did_hash).unwrap();
 
-            assert_eq!(identity.controller, account);
-            assert_eq!(identity.public_key, public_key);
-            assert_eq!(identity.active, true);
-        });
-    }
+        let did_hash = IdentityRegistry::hash_did(&did);
+        let identity = IdentityRegistry::identities(&did_hash).unwrap();
+
+        assert_eq!(identity.controller, ISSUER);
+        assert_eq!(identity.public_key, public_key);
+        assert_eq!(identity.active, true);
+    });
+}
+
+#[test]
+fn test_issue_credential_works() {
+    new_test_ext().execute_with(|| {
+        let (_, subject_did) = setup_trusted_issuer();
+
+        assert_ok!(issue_with_fields(subject_did, 1_735_689_600, vec![], vec![]));
+
+        // Verify credential was created
+        let subject_creds = VerifiableCredentials::credentials_of(&subject_did);
+        assert_eq!(subject_creds.len(), 1);
+    });
+}
+
+#[test]
+fn test_revoke_credential_works() {
+    new_test_ext().execute_with(|| {
+        let (_, subject_did) = setup_trusted_issuer();
+        let credential_id = issue_simple(subject_did, 0);
+
+        assert_ok!(VerifiableCredentials::revoke_credential(
+            RuntimeOrigin::signed(ISSUER),
+            credential_id
+        ));
 
-    #[test]
-    fn test_issue_credential_works() {
-        new_test_ext().execute_with(|| {
-            // Setup
-            let issuer_account = 1u64;
-            let subject_account = 2u64;
+        let credential = VerifiableCredentials::credentials(&credential_id).unwrap();
+        assert_eq!(credential.status, CredentialStatus::Revoked);
+    });
+}
 
-            // Create identities
-            let issuer_did = create_test_identity(issuer_account, b"did:identity:university".to_vec());
-            let subject_did = create_test_identity(subject_account, b"did:identity:student".to_vec());
+#[test]
+fn test_verify_credential_fails_when_revoked() {
+    new_test_ext().execute_with(|| {
+        let (_, subject_did) = setup_trusted_issuer();
+        let credential_id = issue_simple(subject_did, 0);
 
-            // Add issuer as trusted (needs root)
-            assert_ok!(VerifiableCredentials::add_trusted_issuer(
-                RuntimeOrigin::root(),
-                CredentialType::Education,
-                issuer_did
-            ));
+        assert_ok!(VerifiableCredentials::revoke_credential(
+            RuntimeOrigin::signed(ISSUER),
+            credential_id
+        ));
 
-            // Issue credential
-            let data_hash = H256::from_low_u64_be(123);
-            let signature = H256::from_low_u64_be(456);
-            let expires_at = 1735689600u64;
+        // Try to verify - should fail
+        assert_ok!(VerifiableCredentials::verify_credential(
+            RuntimeOrigin::signed(VERIFIER),
+            credential_id
+        ));
+        System::assert_last_event(
+            Event::<Test>::CredentialVerificationFailed {
+                credential_id,
+                reason: CredentialStatus::Revoked,
+                verifier: VERIFIER,
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn verify_credential_reports_expiry_as_a_failure_event() {
+    new_test_ext().execute_with(|| {
+        let (_, subject_did) = setup_trusted_issuer();
+        let now = Timestamp::get();
+        let credential_id = issue_simple(subject_did, now + 60);
+
+        Timestamp::set_timestamp(now + 120);
+        assert_ok!(VerifiableCredentials::verify_credential(
+            RuntimeOrigin::signed(VERIFIER),
+            credential_id
+        ));
 
-            assert_ok!(VerifiableCredentials::issue_credential(
-                RuntimeOrigin::signed(issuer_account),
-                subject_did,
+        System::assert_last_event(
+            Event::<Test>::CredentialVerificationFailed {
+                credential_id,
+                reason: CredentialStatus::Expired,
+                verifier: VERIFIER,
+            }
+            .into(),
+        );
+        assert_eq!(
+            VerifiableCredentials::credentials(&credential_id).unwrap().status,
+            CredentialStatus::Expired
+        );
+    });
+}
+
+#[test]
+fn test_untrusted_issuer_cannot_issue() {
+    new_test_ext().execute_with(|| {
+        create_test_identity(ISSUER, b"did:identity:university");
+        let subject_did = create_test_identity(SUBJECT, b"did:identity:student");
+
+        // Don't add as trusted issuer
+        assert_noop!(
+            issue_with_fields(subject_did, 0, vec![], vec![]),
+            Error::<Test>::IssuerNotTrusted
+        );
+    });
+}
+
+#[test]
+fn schema_field_limit_matches_credential_issuance() {
+    new_test_ext().execute_with(|| {
+        let (_, subject_did) = setup_trusted_issuer();
+        let max_fields = <Test as Config>::MaxFields::get() as usize;
+        let names = |count: usize| (0..count).map(|i| format!("field{}", i).into_bytes()).collect::<Vec<_>>();
+
+        assert_ok!(VerifiableCredentials::create_schema(
+            RuntimeOrigin::signed(ISSUER),
+            CredentialType::Education,
+            names(max_fields),
+            vec![true; max_fields],
+        ));
+        assert_noop!(
+            VerifiableCredentials::create_schema(
+                RuntimeOrigin::signed(ISSUER),
                 CredentialType::Education,
-                data_hash,
-                expires_at,
-                signature
+                names(max_fields + 1),
+                vec![true; max_fields + 1],
+            ),
+            Error::<Test>::TooManyFields
+        );
+        assert_noop!(
+            issue_with_fields(subject_did, 0, names(max_fields + 1), vec![false; max_fields + 1]),
+            Error::<Test>::TooManyFields
+        );
+    });
+}
+
+#[test]
+fn test_selective_disclosure() {
+    new_test_ext().execute_with(|| {
+        let (_, subject_did) = setup_trusted_issuer();
+        assert_ok!(VerifiableCredentials::create_schema(
+            RuntimeOrigin::signed(ISSUER),
+            CredentialType::Education,
+            vec![b"institution".to_vec(), b"studentId".to_vec(), b"status".to_vec()],
+            vec![true, true, true],
+        ));
+        register_verifying_key(ProofType::StudentStatus);
+        let credential_id = issue_simple(subject_did, 0);
+
+        // Perform selective disclosure
+        let fields_to_reveal = vec![0, 2]; // Only reveal certain fields
+        let proof = H256::from_low_u64_be(789);
+
+        assert_ok!(VerifiableCredentials::selective_disclosure(
+            RuntimeOrigin::signed(SUBJECT),
+            credential_id,
+            fields_to_reveal,
+            proof
+        ));
+        assert!(VerifiableCredentials::has_field_been_disclosed(&credential_id, 2));
+    });
+}
+
+#[test]
+fn credential_disclosures_can_be_paged() {
+    new_test_ext().execute_with(|| {
+        let (_, subject_did) = setup_trusted_issuer();
+        assert_ok!(VerifiableCredentials::create_schema(
+            RuntimeOrigin::signed(ISSUER),
+            CredentialType::Education,
+            vec![b"institution".to_vec(), b"studentId".to_vec(), b"status".to_vec()],
+            vec![true, true, true],
+        ));
+        register_verifying_key(ProofType::StudentStatus);
+        let credential_id = issue_simple(subject_did, 0);
+
+        for seed in 1..=5u64 {
+            assert_ok!(VerifiableCredentials::selective_disclosure(
+                RuntimeOrigin::signed(SUBJECT),
+                credential_id,
+                vec![0, 2],
+                H256::from_low_u64_be(seed),
             ));
+        }
 
-            // Verify credential was created
-            let subject_creds = VerifiableCredentials::credentials_of(&subject_did);
-            assert_eq!(subject_creds.len(), 1);
-        });
-    }
+        let proofs_on_page = |page| {
+            VerifiableCredentials::get_credential_disclosures_paginated(&credential_id, page, 2)
+                .into_iter()
+                .map(|(_, request)| request.proof.to_low_u64_be())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(proofs_on_page(0), vec![1, 2]);
+        assert_eq!(proofs_on_page(1), vec![3, 4]);
+        assert_eq!(proofs_on_page(2), vec![5]);
+        assert!(proofs_on_page(3).is_empty());
+        assert_eq!(VerifiableCredentials::get_disclosure_statistics(&credential_id), (5, 2));
+    });
+}
+
+#[test]
+fn test_create_credential_schema() {
+    new_test_ext().execute_with(|| {
+        create_test_identity(ISSUER, b"did:identity:university");
+
+        let fields = vec![
+            b"institution".to_vec(),
+            b"studentId".to_vec(),
+            b"status".to_vec(),
+            b"gpa".to_vec(),
+        ];
+        let required_fields = vec![true, true, true, false];
+
+        assert_ok!(VerifiableCredentials::create_schema(
+            RuntimeOrigin::signed(ISSUER),
+            CredentialType::Education,
+            fields.clone(),
+            required_fields.clone()
+        ));
+
+        let schema = VerifiableCredentials::get_schema_for_type(&CredentialType::Education).unwrap();
+        assert_eq!(schema.fields.len(), 4);
+    });
+}
+
+#[test]
+fn strict_mode_rejects_oversized_plaintext_field() {
+    new_test_ext().execute_with(|| {
+        let (_, subject_did) = setup_trusted_issuer();
+        let large_plaintext = vec![0x41u8; 64];
+
+        // Without strict mode only MaxFieldSize applies
+        assert_ok!(issue_with_fields(subject_did, 0, vec![large_plaintext.clone()], vec![false]));
+
+        StrictFieldPrivacy::set(true);
+        assert_noop!(
+            issue_with_fields(subject_did, 0, vec![large_plaintext], vec![false]),
+            Error::<Test>::PlaintextFieldTooLarge
+        );
+    });
+}
+
+#[test]
+fn encrypted_fields_require_minimum_ciphertext_and_are_queryable() {
+    new_test_ext().execute_with(|| {
+        let (_, subject_did) = setup_trusted_issuer();
+
+        assert_noop!(
+            issue_with_fields(subject_did, 0, vec![vec![0xEEu8; 8]], vec![true]),
+            Error::<Test>::CiphertextTooShort
+        );
+
+        assert_ok!(issue_with_fields(
+            subject_did,
+            0,
+            vec![b"plain".to_vec(), vec![0xEEu8; 48]],
+            vec![false, true],
+        ));
+        let credential_id = VerifiableCredentials::credentials_of(&subject_did)[0];
+        assert_eq!(VerifiableCredentials::get_encrypted_fields(&credential_id), vec![1]);
+    });
+}
+
+#[test]
+fn credential_beyond_configured_validity_is_rejected() {
+    new_test_ext().execute_with(|| {
+        let (_, subject_did) = setup_trusted_issuer();
+        let year = 365 * 24 * 60 * 60;
+        let now = Timestamp::get();
+
+        // The default 100-year cap accepts a two-year credential
+        assert_ok!(issue_with_fields(subject_did, now + 2 * year, vec![], vec![]));
+
+        MaxCredentialValidity::set(year);
+        assert_noop!(
+            issue_with_fields(subject_did, now + 2 * year, vec![], vec![]),
+            Error::<Test>::InvalidCredentialStatus
+        );
+        assert_ok!(issue_with_fields(subject_did, now + year / 2, vec![], vec![]));
+    });
+}
+
+#[test]
+fn renewing_moves_credential_between_expiry_slots() {
+    new_test_ext().execute_with(|| {
+        let (_, subject_did) = setup_trusted_issuer();
+        let now = Timestamp::get();
+        let credential_id = issue_simple(subject_did, now + 600);
+        let old_slot = (now + 600) / 6;
+        assert_eq!(VerifiableCredentials::credential_expiry_slot(&credential_id), Some(old_slot));
+
+        assert_ok!(VerifiableCredentials::renew_credential(
+            RuntimeOrigin::signed(ISSUER),
+            credential_id,
+            now + 6_000,
+        ));
 
-    #[test]
-    fn test_revoke_credential_works() {
-        new_test_ext().execute_with(|| {
-            // Setup
-            let issuer_account = 1u64;
-            let subject_account = 2u64;
+        let new_slot = (now + 6_000) / 6;
+        assert!(!VerifiableCredentials::expiries(old_slot).contains(&credential_id));
+        assert!(VerifiableCredentials::expiries(new_slot).contains(&credential_id));
+        assert_eq!(VerifiableCredentials::credential_expiry_slot(&credential_id), Some(new_slot));
+        assert_eq!(VerifiableCredentials::credentials(&credential_id).unwrap().expires_at, now + 6_000);
+
+        // Cleanup of the new slot clears the reverse index too
+        VerifiableCredentials::cleanup_expired_credentials(now + 6_000);
+        assert_eq!(VerifiableCredentials::credential_expiry_slot(&credential_id), None);
+    });
+}
+
+#[test]
+fn zero_expiry_uses_credential_type_default() {
+    new_test_ext().execute_with(|| {
+        let (_, subject_did) = setup_trusted_issuer();
+        let year = 365 * 24 * 60 * 60;
+        let now = Timestamp::get();
+
+        // Without a default, zero still means no expiry
+        let no_expiry = issue_simple(subject_did, 0);
+        assert_eq!(VerifiableCredentials::credentials(&no_expiry).unwrap().expires_at, 0);
+
+        assert_ok!(VerifiableCredentials::set_default_expiry(
+            RuntimeOrigin::root(),
+            CredentialType::Education,
+            Some(year),
+        ));
+        let defaulted = issue_simple(subject_did, 0);
+        assert_eq!(VerifiableCredentials::credentials(&defaulted).unwrap().expires_at, now + year);
+        assert_eq!(VerifiableCredentials::credential_expiry_slot(&defaulted), Some((now + year) / 6));
 
-            let issuer_did = create_test_identity(issuer_account, b"did:identity:university".to_vec());
-            let subject_did = create_test_identity(subject_account, b"did:identity:student".to_vec());
+        // An explicit expiry still wins
+        let explicit = issue_simple(subject_did, now + 600);
+        assert_eq!(VerifiableCredentials::credentials(&explicit).unwrap().expires_at, now + 600);
 
-            // Add trusted issuer and issue credential
-            assert_ok!(VerifiableCredentials::add_trusted_issuer(
+        assert_noop!(
+            VerifiableCredentials::set_default_expiry(
                 RuntimeOrigin::root(),
                 CredentialType::Education,
-                issuer_did
-            ));
+                Some(MaxCredentialValidity::get() + 1),
+            ),
+            Error::<Test>::InvalidCredentialStatus
+        );
+    });
+}
+
+#[test]
+fn non_revocation_proofs_track_the_revocation_accumulator() {
+    new_test_ext().execute_with(|| {
+        let (_, subject_did) = setup_trusted_issuer();
+        let credentials: Vec<H256> = (0..5).map(|_| issue_simple(subject_did, 0)).collect();
+
+        // Empty set: every credential gets a trivially valid proof
+        let proof = VerifiableCredentials::build_non_revocation_proof(credentials[0]).unwrap();
+        assert!(VerifiableCredentials::verify_non_revocation_proof(
+            &proof,
+            &VerifiableCredentials::revocation_accumulator()
+        ));
 
-            let data_hash = H256::from_low_u64_be(123);
-            let signature = H256::from_low_u64_be(456);
+        for revoked in &credentials[1..4] {
+            assert_ok!(VerifiableCredentials::revoke_credential(RuntimeOrigin::signed(ISSUER), *revoked));
+        }
+        let accumulator = VerifiableCredentials::revocation_accumulator();
+        assert_eq!(accumulator.version, 3);
+        assert_eq!(accumulator.size, 3);
 
-            assert_ok!(VerifiableCredentials::issue_credential(
-                RuntimeOrigin::signed(issuer_account),
-                subject_did,
-                CredentialType::Education,
-                data_hash,
-                0,
-                signature
-            ));
+        // A stale proof no longer verifies against the new accumulator
+        assert!(!VerifiableCredentials::verify_non_revocation_proof(&proof, &accumulator));
 
-            let subject_creds = VerifiableCredentials::credentials_of(&subject_did);
-            let credential_id = subject_creds[0];
+        for active in [credentials[0], credentials[4]] {
+            let proof = VerifiableCredentials::build_non_revocation_proof(active).unwrap();
+            assert!(VerifiableCredentials::verify_non_revocation_proof(&proof, &accumulator));
+        }
+        for revoked in &credentials[1..4] {
+            assert!(VerifiableCredentials::build_non_revocation_proof(*revoked).is_none());
+        }
 
-            // Revoke credential
-            assert_ok!(VerifiableCredentials::revoke_credential(
-                RuntimeOrigin::signed(issuer_account),
-                credential_id
-            ));
+        // A proof for an active credential cannot be replayed for a revoked one
+        let mut forged = VerifiableCredentials::build_non_revocation_proof(credentials[0]).unwrap();
+        forged.credential_id = credentials[2];
+        assert!(!VerifiableCredentials::verify_non_revocation_proof(&forged, &accumulator));
+    });
+}
+
+/// IDs sharing their top bits land in the same revocation tree bucket
+fn in_bucket(low: u64) -> H256 {
+    let mut id = H256::repeat_byte(0xAB);
+    id.0[24..].copy_from_slice(&low.to_be_bytes());
+    id
+}
+
+#[test]
+fn revocation_tree_buckets_grow_by_chunks_and_prune() {
+    new_test_ext().execute_with(|| {
+        // The mock stores two IDs per chunk; a bucket keeps accepting IDs past any chunk
+        for low in 0..5 {
+            VerifiableCredentials::update_revocation_tree(in_bucket(low), true);
+        }
+        assert_eq!(RevokedBucketLengths::<Test>::iter().next().map(|(_, length)| length), Some(5));
+
+        // A neighbour proves non-revocation against every chunk of its bucket
+        let accumulator = VerifiableCredentials::revocation_accumulator();
+        assert_eq!(accumulator.size, 5);
+        let proof = VerifiableCredentials::build_non_revocation_proof(in_bucket(5)).unwrap();
+        assert_eq!(proof.bucket.iter().map(Vec::len).collect::<Vec<_>>(), vec![2, 2, 1]);
+        assert!(VerifiableCredentials::verify_non_revocation_proof(&proof, &accumulator));
+        assert!(VerifiableCredentials::build_non_revocation_proof(in_bucket(3)).is_none());
+
+        // Leaving a revoked ID out of the bucket does not prove it unrevoked
+        let mut hidden = proof.clone();
+        hidden.credential_id = hidden.bucket[0].remove(0);
+        assert!(!VerifiableCredentials::verify_non_revocation_proof(&hidden, &accumulator));
+
+        // Removing from the middle moves the bucket's last ID into the gap
+        VerifiableCredentials::update_revocation_tree(in_bucket(1), false);
+        let accumulator = VerifiableCredentials::revocation_accumulator();
+        let proof = VerifiableCredentials::build_non_revocation_proof(in_bucket(1)).unwrap();
+        assert_eq!(proof.bucket, vec![vec![in_bucket(0), in_bucket(4)], vec![in_bucket(2), in_bucket(3)]]);
+        assert!(VerifiableCredentials::verify_non_revocation_proof(&proof, &accumulator));
+
+        for low in [0, 2, 3, 4] {
+            VerifiableCredentials::update_revocation_tree(in_bucket(low), false);
+        }
+        let accumulator = VerifiableCredentials::revocation_accumulator();
+        assert_eq!((accumulator.size, accumulator.root), (0, H256::zero()));
+        assert_eq!(RevocationTreeNodes::<Test>::iter().count(), 0);
+        assert_eq!(RevocationChunkNodes::<Test>::iter().count(), 0);
+        assert_eq!(RevokedCredentialChunks::<Test>::iter().count(), 0);
+        assert_eq!(RevokedBucketLengths::<Test>::iter().count(), 0);
+    });
+}
+
+#[test]
+fn deleting_an_expired_credential_prunes_its_revocation() {
+    new_test_ext().execute_with(|| {
+        let (_, subject_did) = setup_trusted_issuer();
+        let now = Timestamp::get();
+        let credential_id = issue_simple(subject_did, now + 600);
+        assert_ok!(VerifiableCredentials::revoke_credential(RuntimeOrigin::signed(ISSUER), credential_id));
+        assert_eq!(VerifiableCredentials::revocation_accumulator().size, 1);
+
+        assert_eq!(VerifiableCredentials::cleanup_expired_credentials(now + 600), (1, 1));
+        assert!(VerifiableCredentials::credentials(&credential_id).is_none());
+        assert_eq!(VerifiableCredentials::revocation_accumulator().size, 0);
+        assert!(VerifiableCredentials::build_non_revocation_proof(credential_id).is_some());
+    });
+}
+
+#[test]
+fn non_revocable_credential_types_cannot_be_revoked() {
+    new_test_ext().execute_with(|| {
+        let (issuer_did, subject_did) = setup_trusted_issuer();
+        assert_ok!(VerifiableCredentials::add_trusted_issuer(
+            RuntimeOrigin::root(),
+            CredentialType::Employment,
+            issuer_did
+        ));
+        assert_ok!(VerifiableCredentials::set_credential_type_revocable(
+            RuntimeOrigin::root(),
+            CredentialType::Education,
+            false,
+        ));
 
-            // Verify credential is revoked
-            let credential = VerifiableCredentials::credentials(&credential_id).unwrap();
-            assert_eq!(credential.status, CredentialStatus::Revoked);
-        });
-    }
+        let attestation = issue_simple(subject_did, 0);
+        assert_noop!(
+            VerifiableCredentials::revoke_credential(RuntimeOrigin::signed(ISSUER), attestation),
+            Error::<Test>::CredentialNotRevocable
+        );
+
+        assert_ok!(VerifiableCredentials::issue_credential(
+            RuntimeOrigin::signed(ISSUER),
+            subject_did,
+            CredentialType::Employment,
+            H256::from_low_u64_be(123),
+            0,
+            H256::from_low_u64_be(456),
+            vec![],
+            vec![],
+            vec![],
+            vec![],
+        ));
+        let employment = *VerifiableCredentials::credentials_of(&subject_did).last().unwrap();
+        assert_ok!(VerifiableCredentials::revoke_credential(RuntimeOrigin::signed(ISSUER), employment));
+        assert_eq!(
+            VerifiableCredentials::credentials(&employment).unwrap().status,
+            CredentialStatus::Revoked
+        );
+    });
+}
+
+#[test]
+fn validate_issuance_reports_problems_without_issuing() {
+    new_test_ext().execute_with(|| {
+        let (issuer_did, subject_did) = setup_trusted_issuer();
+        let year = 365 * 24 * 60 * 60;
+        let now = Timestamp::get();
+        let fields = vec![b"degree".to_vec(), b"gpa".to_vec()];
+
+        assert_ok!(VerifiableCredentials::validate_issuance(
+            &issuer_did,
+            &subject_did,
+            &CredentialType::Education,
+            now + year,
+            fields.clone(),
+            vec![true, false],
+            vec![1],
+            vec![false, false],
+        ));
 
-    #[test]
-    fn test_verify_credential_fails_when_revoked() {
-        new_test_ext().execute_with(|| {
-            // Setup and issue credential
-            let issuer_account = 1u64;
-            let subject_account = 2u64;
-            let verifier_account = 3u64;
+        MaxCredentialValidity::set(year);
+        assert!(matches!(
+            VerifiableCredentials::validate_issuance(
+                &issuer_did,
+                &subject_did,
+                &CredentialType::Education,
+                now + 2 * year,
+                fields.clone(),
+                vec![true, false],
+                vec![1],
+                vec![false, false],
+            ),
+            Err(Error::<Test>::InvalidCredentialStatus)
+        ));
 
-            let issuer_did = create_test_identity(issuer_account, b"did:identity:university".to_vec());
-            let subject_did = create_test_identity(subject_account, b"did:identity:student".to_vec());
+        assert!(matches!(
+            VerifiableCredentials::validate_issuance(
+                &issuer_did,
+                &subject_did,
+                &CredentialType::Employment,
+                now + year,
+                fields.clone(),
+                vec![true, false],
+                vec![1],
+                vec![false, false],
+            ),
+            Err(Error::<Test>::IssuerNotTrusted)
+        ));
 
-            assert_ok!(VerifiableCredentials::add_trusted_issuer(
-                RuntimeOrigin::root(),
-                CredentialType::Education,
-                issuer_did
-            ));
+        assert!(matches!(
+            VerifiableCredentials::validate_issuance(
+                &issuer_did,
+                &subject_did,
+                &CredentialType::Education,
+                now + year,
+                fields.clone(),
+                vec![true, false],
+                vec![2],
+                vec![false, false],
+            ),
+            Err(Error::<Test>::InvalidRevealIndex)
+        ));
 
-            let data_hash = H256::from_low_u64_be(123);
-            let signature = H256::from_low_u64_be(456);
+        // Encryption flags get the same checks as at issuance
+        assert!(matches!(
+            VerifiableCredentials::validate_issuance(
+                &issuer_did,
+                &subject_did,
+                &CredentialType::Education,
+                now + year,
+                fields.clone(),
+                vec![true, false],
+                vec![1],
+                vec![false],
+            ),
+            Err(Error::<Test>::InvalidFieldsLength)
+        ));
+        assert!(matches!(
+            VerifiableCredentials::validate_issuance(
+                &issuer_did,
+                &subject_did,
+                &CredentialType::Education,
+                now + year,
+                fields,
+                vec![true, false],
+                vec![1],
+                vec![true, false],
+            ),
+            Err(Error::<Test>::CiphertextTooShort)
+        ));
 
-            assert_ok!(VerifiableCredentials::issue_credential(
-                RuntimeOrigin::signed(issuer_account),
-                subject_did,
-                CredentialType::Education,
-                data_hash,
-                0,
-                signature
-            ));
+        assert!(VerifiableCredentials::credentials_of(&subject_did).is_empty());
+        assert!(VerifiableCredentials::get_credentials_by_issuer(issuer_did).is_empty());
+    });
+}
+
+#[test]
+fn statuses_of_reports_each_credential() {
+    new_test_ext().execute_with(|| {
+        let (_, subject_did) = setup_trusted_issuer();
+        let now = Timestamp::get();
+
+        let active = issue_simple(subject_did, 0);
+        Timestamp::set_timestamp(now + 1);
+        let revoked = issue_simple(subject_did, 0);
+        assert_ok!(VerifiableCredentials::revoke_credential(RuntimeOrigin::signed(ISSUER), revoked));
+        Timestamp::set_timestamp(now + 2);
+        let lapsed = issue_simple(subject_did, now + 60);
+        let missing = H256::repeat_byte(0xEE);
+
+        // Past expiry but never touched, so storage still says Active
+        Timestamp::set_timestamp(now + 120);
+        assert_eq!(
+            VerifiableCredentials::credentials(&lapsed).unwrap().status,
+            CredentialStatus::Active
+        );
+
+        assert_eq!(
+            VerifiableCredentials::statuses_of(BoundedVec::truncate_from(vec![active, revoked, lapsed, missing])),
+            vec![
+                (active, Some(CredentialStatus::Active)),
+                (revoked, Some(CredentialStatus::Revoked)),
+                (lapsed, Some(CredentialStatus::Expired)),
+                (missing, None),
+            ]
+        );
+    });
+}
+
+#[test]
+fn revoke_subject_credentials_only_touches_callers_credentials() {
+    new_test_ext().execute_with(|| {
+        let (issuer_did, subject_did) = setup_trusted_issuer();
+        let other_issuer = create_test_identity(VERIFIER, b"did:identity:employer");
+        assert_ok!(VerifiableCredentials::add_trusted_issuer(
+            RuntimeOrigin::root(),
+            CredentialType::Education,
+            other_issuer
+        ));
+        let now = Timestamp::get();
+
+        let mut own = Vec::new();
+        for offset in 0..3 {
+            Timestamp::set_timestamp(now + offset);
+            own.push(issue_simple(subject_did, 0));
+        }
+        assert_ok!(VerifiableCredentials::issue_credential(
+            RuntimeOrigin::signed(VERIFIER),
+            subject_did,
+            CredentialType::Education,
+            H256::from_low_u64_be(789),
+            0,
+            H256::from_low_u64_be(456),
+            vec![],
+            vec![],
+            vec![],
+            vec![],
+        ));
+        let foreign = *VerifiableCredentials::credentials_of(&subject_did).last().unwrap();
 
-            let subject_creds = VerifiableCredentials::credentials_of(&subject_did);
-            let credential_id = subject_creds[0];
+        // Two per call in the mock, so the third needs a second call
+        assert_ok!(VerifiableCredentials::revoke_subject_credentials(
+            RuntimeOrigin::signed(ISSUER),
+            subject_did
+        ));
+        assert_eq!(
+            VerifiableCredentials::credentials(&own[2]).unwrap().status,
+            CredentialStatus::Active
+        );
+        assert_ok!(VerifiableCredentials::revoke_subject_credentials(
+            RuntimeOrigin::signed(ISSUER),
+            subject_did
+        ));
+        for credential_id in own.iter() {
+            assert_eq!(
+                VerifiableCredentials::credentials(credential_id).unwrap().status,
+                CredentialStatus::Revoked
+            );
+            System::assert_has_event(
+                Event::<Test>::CredentialRevoked { credential_id: *credential_id, issuer: issuer_did }.into(),
+            );
+        }
+        assert_noop!(
+            VerifiableCredentials::revoke_subject_credentials(RuntimeOrigin::signed(ISSUER), subject_did),
+            Error::<Test>::CredentialNotFound
+        );
+
+        assert_eq!(
+            VerifiableCredentials::credentials(&foreign).unwrap().status,
+            CredentialStatus::Active
+        );
+    });
+}
+
+#[test]
+fn issuer_revocation_completes_over_several_blocks() {
+    new_test_ext().execute_with(|| {
+        let (issuer_did, subject_did) = setup_trusted_issuer();
+        let now = Timestamp::get();
+
+        let mut issued = Vec::new();
+        for offset in 0..25 {
+            Timestamp::set_timestamp(now + offset);
+            issued.push(issue_simple(subject_did, 0));
+        }
 
-            // Revoke credential
-            assert_ok!(VerifiableCredentials::revoke_credential(
-                RuntimeOrigin::signed(issuer_account),
-                credential_id
-            ));
+        assert_ok!(VerifiableCredentials::revoke_issuer_credentials(RuntimeOrigin::root(), issuer_did));
+        assert_noop!(
+            VerifiableCredentials::revoke_issuer_credentials(RuntimeOrigin::root(), issuer_did),
+            Error::<Test>::IssuerRevocationInProgress
+        );
+        assert_noop!(issue_with_fields(subject_did, 0, vec![], vec![]), Error::<Test>::IssuerRevocationInProgress);
+        assert!(matches!(
+            VerifiableCredentials::validate_issuance(
+                &issuer_did,
+                &subject_did,
+                &CredentialType::Education,
+                0,
+                vec![],
+                vec![],
+                vec![],
+                vec![],
+            ),
+            Err(Error::<Test>::IssuerRevocationInProgress)
+        ));
 
-            // Try to verify - should fail
-            assert_noop!(
-                VerifiableCredentials::verify_credential(
-                    RuntimeOrigin::signed(verifier_account),
-                    credential_id
-                ),
-                Error::<Test>::CredentialRevoked
+        // Ten per block in the mock
+        for (block, processed) in [(1u64, 10u32), (2, 20)] {
+            VerifiableCredentials::on_idle(block, Weight::MAX);
+            System::assert_last_event(
+                Event::<Test>::IssuerRevocationProgress { issuer: issuer_did, processed, total: 25 }.into(),
             );
-        });
-    }
-
-    #[test]
-    fn test_untrusted_issuer_cannot_issue() {
-        new_test_ext().execute_with(|| {
-            let issuer_account = 1u64;
-            let subject_account = 2u64;
-
-            let issuer_did = create_test_identity(issuer_account, b"did:identity:university".to_vec());
-            let subject_did = create_test_identity(subject_account, b"did:identity:student".to_vec());
-
-            // Don't add as trusted issuer
-            let data_hash = H256::from_low_u64_be(123);
-            let signature = H256::from_low_u64_be(456);
-
-            // Try to issue - should fail
-            assert_noop!(
-                VerifiableCredentials::issue_credential(
-                    RuntimeOrigin::signed(issuer_account),
-                    subject_did,
-                    CredentialType::Education,
-                    data_hash,
-                    0,
-                    signature
-                ),
-                Error::<Test>::IssuerNotTrusted
+            assert_eq!(
+                VerifiableCredentials::credentials(&issued[processed as usize]).unwrap().status,
+                CredentialStatus::Active
             );
-        });
-    }
+        }
+        VerifiableCredentials::on_idle(3, Weight::MAX);
+        System::assert_last_event(Event::<Test>::IssuerRevocationCompleted { issuer: issuer_did }.into());
+
+        assert!(VerifiableCredentials::issuer_revocation_cursor(&issuer_did).is_none());
+        for credential_id in issued.iter() {
+            assert_eq!(
+                VerifiableCredentials::credentials(credential_id).unwrap().status,
+                CredentialStatus::Revoked
+            );
+        }
+        assert_eq!(VerifiableCredentials::revocation_accumulator().size, 25);
+    });
+}
+
+#[test]
+fn issuer_revocation_can_be_cancelled_part_way() {
+    new_test_ext().execute_with(|| {
+        let (issuer_did, subject_did) = setup_trusted_issuer();
+        let now = Timestamp::get();
+
+        let mut issued = Vec::new();
+        for offset in 0..15 {
+            Timestamp::set_timestamp(now + offset);
+            issued.push(issue_simple(subject_did, 0));
+        }
+
+        assert_noop!(
+            VerifiableCredentials::cancel_issuer_revocation(RuntimeOrigin::root(), issuer_did),
+            Error::<Test>::NoIssuerRevocation
+        );
+        assert_ok!(VerifiableCredentials::revoke_issuer_credentials(RuntimeOrigin::root(), issuer_did));
+        VerifiableCredentials::on_idle(1, Weight::MAX);
+
+        assert_noop!(
+            VerifiableCredentials::cancel_issuer_revocation(RuntimeOrigin::signed(ISSUER), issuer_did),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_ok!(VerifiableCredentials::cancel_issuer_revocation(RuntimeOrigin::root(), issuer_did));
+        System::assert_last_event(
+            Event::<Test>::IssuerRevocationCancelled { issuer: issuer_did, processed: 10 }.into(),
+        );
+        assert!(VerifiableCredentials::issuer_revocation_cursor(&issuer_did).is_none());
+
+        // Nothing further is revoked and the issuer is no longer locked out
+        VerifiableCredentials::on_idle(2, Weight::MAX);
+        assert_eq!(
+            VerifiableCredentials::credentials(&issued[9]).unwrap().status,
+            CredentialStatus::Revoked
+        );
+        assert_eq!(
+            VerifiableCredentials::credentials(&issued[10]).unwrap().status,
+            CredentialStatus::Active
+        );
+        Timestamp::set_timestamp(now + 15);
+        assert_ok!(issue_with_fields(subject_did, 0, vec![], vec![]));
+    });
+}
+
+#[test]
+fn issuer_revocation_is_not_blocked_by_a_crowded_bucket() {
+    new_test_ext().execute_with(|| {
+        let (issuer_did, subject_did) = setup_trusted_issuer();
+        let now = Timestamp::get();
+
+        let mut issued = Vec::new();
+        for offset in 0..3 {
+            Timestamp::set_timestamp(now + offset);
+            issued.push(issue_simple(subject_did, 0));
+        }
 
-    #[test]
-    fn test_selective_disclosure() {
-        new_test_ext().execute_with(|| {
-            let issuer_account = 1u64;
-            let subject_account = 2u64;
+        // Crowd the bucket the second credential lands in past several chunks
+        for low in 1..=8u8 {
+            let mut neighbour = issued[1];
+            neighbour.0[31] ^= low;
+            VerifiableCredentials::update_revocation_tree(neighbour, true);
+        }
 
-            let issuer_did = create_test_identity(issuer_account, b"did:identity:university".to_vec());
-            let subject_did = create_test_identity(subject_account, b"did:identity:student".to_vec());
+        assert_ok!(VerifiableCredentials::revoke_issuer_credentials(RuntimeOrigin::root(), issuer_did));
+        VerifiableCredentials::on_idle(1, Weight::MAX);
+        System::assert_last_event(Event::<Test>::IssuerRevocationCompleted { issuer: issuer_did }.into());
 
+        assert!(VerifiableCredentials::issuer_revocation_cursor(&issuer_did).is_none());
+        for credential_id in issued.iter() {
+            assert_eq!(
+                VerifiableCredentials::credentials(credential_id).unwrap().status,
+                CredentialStatus::Revoked
+            );
+            assert!(VerifiableCredentials::build_non_revocation_proof(*credential_id).is_none());
+        }
+        assert_eq!(VerifiableCredentials::revocation_accumulator().size, 11);
+    });
+}
+
+#[test]
+fn self_issuance_is_limited_to_allowed_types() {
+    new_test_ext().execute_with(|| {
+        let issuer_did = create_test_identity(ISSUER, b"did:identity:university");
+        for credential_type in [CredentialType::Employment, CredentialType::Custom] {
             assert_ok!(VerifiableCredentials::add_trusted_issuer(
                 RuntimeOrigin::root(),
-                CredentialType::Education,
+                credential_type,
                 issuer_did
             ));
-
-            let data_hash = H256::from_low_u64_be(123);
-            let signature = H256::from_low_u64_be(456);
-
-            assert_ok!(VerifiableCredentials::issue_credential(
-                RuntimeOrigin::signed(issuer_account),
-                subject_did,
-                CredentialType::Education,
-                data_hash,
+        }
+        let self_issue = |credential_type| {
+            VerifiableCredentials::issue_credential(
+                RuntimeOrigin::signed(ISSUER),
+                issuer_did,
+                credential_type,
+                H256::from_low_u64_be(123),
                 0,
-                signature
-            ));
-
-            let subject_creds = VerifiableCredentials::credentials_of(&subject_did);
-            let credential_id = subject_creds[0];
-
-            // Perform selective disclosure
-            let fields_to_reveal = vec![0, 2]; // Only reveal certain fields
-            let proof = H256::from_low_u64_be(789);
-
-            assert_ok!(VerifiableCredentials::selective_disclosure(
-                RuntimeOrigin::signed(subject_account),
-                credential_id,
-                fields_to_reveal,
-                proof
-            ));
-        });
-    }
-
-    #[test]
-    fn test_create_credential_schema() {
-        new_test_ext().execute_with(|| {
-            let creator_account = 1u64;
-            let creator_did = create_test_identity(creator_account, b"did:identity:university".to_vec());
-
-            let fields = vec![
-                b"institution".to_vec(),
-                b"studentId".to_vec(),
-                b"status".to_vec(),
-                b"gpa".to_vec(),
-            ];
-            let required_fields = vec![true, true, true, false];
-
-            assert_ok!(VerifiableCredentials::create_schema(
-                RuntimeOrigin::signed(creator_account),
-                CredentialType::Education,
-                fields.clone(),
-                required_fields.clone()
-            ));
-
-            // Check schema was created (would need to add getter for this)
-        });
-    }
-}
\ No newline at end of file
+                H256::from_low_u64_be(456),
+                vec![],
+                vec![],
+                vec![],
+                vec![],
+            )
+        };
+
+        assert_noop!(self_issue(CredentialType::Employment), Error::<Test>::SelfIssuanceNotAllowed);
+        assert_ok!(self_issue(CredentialType::Custom));
+
+        // Governance can opt a type in
+        assert_ok!(VerifiableCredentials::set_self_issuance_allowed(
+            RuntimeOrigin::root(),
+            CredentialType::Employment,
+            true
+        ));
+        assert_ok!(self_issue(CredentialType::Employment));
+    });
+}
+
+#[test]
+fn v1_migration_marks_existing_fields_unencrypted() {
+    new_test_ext().execute_with(|| {
+        StorageVersion::new(0).put::<VerifiableCredentials>();
+        let credential_id = H256::repeat_byte(0x42);
+        let field = |value: &[u8]| BoundedVec::truncate_from(value.to_vec());
+        let old = crate::migrations::v1::OldCredential::<Test> {
+            subject: H256::repeat_byte(0x01),
+            issuer: H256::repeat_byte(0x02),
+            credential_type: CredentialType::Education,
+            data_hash: H256::repeat_byte(0x03),
+            issued_at: 1,
+            expires_at: 0,
+            status: CredentialStatus::Active,
+            signature: H256::repeat_byte(0x04),
+            metadata_hash: H256::repeat_byte(0x05),
+            fields: BoundedVec::truncate_from(vec![field(b"degree"), field(b"gpa")]),
+            required_fields: BoundedVec::truncate_from(vec![true, false]),
+            fields_to_reveal: BoundedVec::truncate_from(vec![1]),
+        };
+        frame_support::storage::unhashed::put(&Credentials::<Test>::hashed_key_for(credential_id), &old);
+
+        crate::migrations::v1::MigrateToV1::<Test>::on_runtime_upgrade();
+
+        let credential = VerifiableCredentials::credentials(&credential_id).unwrap();
+        assert_eq!(credential.encrypted_fields.to_vec(), vec![false, false]);
+        assert_eq!(credential.fields, old.fields);
+        assert_eq!(credential.fields_to_reveal, old.fields_to_reveal);
+        assert_eq!(VerifiableCredentials::on_chain_storage_version(), StorageVersion::new(1));
+    });
+}
+
+#[test]
+fn v2_migration_indexes_recorded_disclosures() {
+    new_test_ext().execute_with(|| {
+        StorageVersion::new(1).put::<VerifiableCredentials>();
+        let credential_id = H256::repeat_byte(0x42);
+        let record = |disclosure: u8, timestamp: u64| {
+            DisclosureRecords::<Test>::insert(
+                H256::repeat_byte(disclosure),
+                SelectiveDisclosureRequest {
+                    credential_id,
+                    fields_to_reveal: BoundedVec::truncate_from(vec![0]),
+                    proof: H256::zero(),
+                    timestamp,
+                },
+            );
+        };
+        record(0x01, 30);
+        record(0x02, 10);
+        record(0x03, 20);
+        assert!(VerifiableCredentials::get_credential_disclosures_paginated(&credential_id, 0, 10).is_empty());
+
+        crate::migrations::v2::MigrateToV2::<Test>::on_runtime_upgrade();
+
+        assert_eq!(VerifiableCredentials::credential_disclosure_count(&credential_id), 3);
+        let ids: Vec<H256> = VerifiableCredentials::get_credential_disclosures_paginated(&credential_id, 0, 10)
+            .into_iter()
+            .map(|(disclosure_id, _)| disclosure_id)
+            .collect();
+        assert_eq!(ids, vec![H256::repeat_byte(0x02), H256::repeat_byte(0x03), H256::repeat_byte(0x01)]);
+        assert_eq!(VerifiableCredentials::on_chain_storage_version(), StorageVersion::new(2));
+    });
+}
+
+#[test]
+fn v3_migration_moves_revoked_buckets_into_chunks() {
+    new_test_ext().execute_with(|| {
+        StorageVersion::new(2).put::<VerifiableCredentials>();
+        let bucket = VerifiableCredentials::revocation_bucket_index(&in_bucket(0));
+        let revoked: Vec<H256> = (0..3).map(in_bucket).collect();
+        frame_support::storage::unhashed::put(
+            &crate::migrations::v3::RevokedCredentialBuckets::<Test>::hashed_key_for(bucket),
+            &revoked,
+        );
+        RevocationAccumulatorState::<Test>::mutate(|accumulator| accumulator.size = 3);
+
+        crate::migrations::v3::MigrateToV3::<Test>::on_runtime_upgrade();
+
+        let accumulator = VerifiableCredentials::revocation_accumulator();
+        assert_eq!(accumulator.size, 3);
+        assert_ne!(accumulator.root, H256::zero());
+        assert_eq!(VerifiableCredentials::revoked_credential_chunk(bucket, 1), vec![in_bucket(2)]);
+        for credential_id in revoked.iter() {
+            assert!(VerifiableCredentials::build_non_revocation_proof(*credential_id).is_none());
+        }
+        let proof = VerifiableCredentials::build_non_revocation_proof(in_bucket(3)).unwrap();
+        assert!(VerifiableCredentials::verify_non_revocation_proof(&proof, &accumulator));
+        assert_eq!(VerifiableCredentials::on_chain_storage_version(), StorageVersion::new(3));
+    });
+}
@@ -9,6 +9,14 @@ pub trait WeightInfo {
     fn add_trusted_issuer() -> Weight;
     fn remove_trusted_issuer() -> Weight;
     fn selective_disclosure() -> Weight;
+    fn renew_credential() -> Weight;
+    fn set_default_expiry() -> Weight;
+    fn set_credential_type_revocable() -> Weight;
+    fn revoke_subject_credentials(n: u32) -> Weight;
+    fn set_self_issuance_allowed() -> Weight;
+    fn revoke_issuer_credentials() -> Weight;
+    fn cancel_issuer_revocation() -> Weight;
+    fn update_revocation_tree(b: u32) -> Weight;
 }
 
 pub struct SubstrateWeight<T>(core::marker::PhantomData<T>);
@@ -51,8 +59,59 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
     
     fn selective_disclosure() -> Weight {
         Weight::from_parts(150_000_000, 0)
-            .saturating_add(T::DbWeight::get().reads(6))
-            .saturating_add(T::DbWeight::get().writes(2))
+            .saturating_add(T::DbWeight::get().reads(7))
+            .saturating_add(T::DbWeight::get().writes(4))
+    }
+    
+    fn renew_credential() -> Weight {
+        Weight::from_parts(70_000_000, 0)
+            .saturating_add(T::DbWeight::get().reads(2))
+            .saturating_add(T::DbWeight::get().writes(4))
+    }
+    
+    fn set_default_expiry() -> Weight {
+        Weight::from_parts(15_000_000, 0)
+            .saturating_add(T::DbWeight::get().writes(1))
+    }
+    
+    fn set_credential_type_revocable() -> Weight {
+        Weight::from_parts(15_000_000, 0)
+            .saturating_add(T::DbWeight::get().writes(1))
+    }
+    
+    fn revoke_subject_credentials(n: u32) -> Weight {
+        Weight::from_parts(40_000_000, 0)
+            .saturating_add(Weight::from_parts(60_000_000, 0).saturating_mul(n as u64))
+            .saturating_add(T::DbWeight::get().reads(2))
+            .saturating_add(T::DbWeight::get().reads(2_u64.saturating_mul(n as u64)))
+            .saturating_add(T::DbWeight::get().writes(3_u64.saturating_mul(n as u64)))
+    }
+    
+    fn set_self_issuance_allowed() -> Weight {
+        Weight::from_parts(15_000_000, 0)
+            .saturating_add(T::DbWeight::get().writes(1))
+    }
+    
+    fn revoke_issuer_credentials() -> Weight {
+        Weight::from_parts(20_000_000, 0)
+            .saturating_add(T::DbWeight::get().reads(2))
+            .saturating_add(T::DbWeight::get().writes(1))
+    }
+    
+    fn cancel_issuer_revocation() -> Weight {
+        Weight::from_parts(15_000_000, 0)
+            .saturating_add(T::DbWeight::get().reads(1))
+            .saturating_add(T::DbWeight::get().writes(1))
+    }
+    
+    fn update_revocation_tree(b: u32) -> Weight {
+        // Re-encodes and hashes up to two `b`-entry chunks, each with one sibling read and
+        // node write per chunk tree level (32), then per revocation tree level (24), plus
+        // the chunks, bucket length, ID slot and accumulator
+        Weight::from_parts(30_000_000, 0)
+            .saturating_add(Weight::from_parts(400_000, 0).saturating_mul(b as u64))
+            .saturating_add(T::DbWeight::get().reads(2 * 32 + 24 + 5))
+            .saturating_add(T::DbWeight::get().writes(2 * 32 + 24 + 5))
     }
 }
 
@@ -64,4 +123,16 @@ impl WeightInfo for () {
     fn add_trusted_issuer() -> Weight { Weight::from_parts(50_000_000, 0) }
     fn remove_trusted_issuer() -> Weight { Weight::from_parts(45_000_000, 0) }
     fn selective_disclosure() -> Weight { Weight::from_parts(150_000_000, 0) }
+    fn renew_credential() -> Weight { Weight::from_parts(70_000_000, 0) }
+    fn set_default_expiry() -> Weight { Weight::from_parts(15_000_000, 0) }
+    fn set_credential_type_revocable() -> Weight { Weight::from_parts(15_000_000, 0) }
+    fn revoke_subject_credentials(n: u32) -> Weight {
+        Weight::from_parts(40_000_000, 0).saturating_add(Weight::from_parts(60_000_000, 0).saturating_mul(n as u64))
+    }
+    fn set_self_issuance_allowed() -> Weight { Weight::from_parts(15_000_000, 0) }
+    fn revoke_issuer_credentials() -> Weight { Weight::from_parts(20_000_000, 0) }
+    fn cancel_issuer_revocation() -> Weight { Weight::from_parts(15_000_000, 0) }
+    fn update_revocation_tree(b: u32) -> Weight {
+        Weight::from_parts(30_000_000, 0).saturating_add(Weight::from_parts(200_000, 0).saturating_mul(b as u64))
+    }
 }
\ No newline at end of file
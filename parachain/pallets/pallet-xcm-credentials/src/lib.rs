@@ -5,8 +5,29 @@ pub use pallet::*;
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
 
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
 pub mod weights;
 
+/// Authoritative status of credentials issued on this chain, published to mirroring chains
+///
+/// Wire it to the issuing pallet in the runtime; `()` knows no credentials
+pub trait CredentialStatusProvider {
+    /// Lifecycle state and expiry (0 = no expiry) of a locally issued credential,
+    /// or `None` when it is unknown or in a state the mirror cannot represent
+    fn credential_status(credential_hash: &sp_core::H256) -> Option<(pallet::MirrorState, u64)>;
+}
+
+impl CredentialStatusProvider for () {
+    fn credential_status(_credential_hash: &sp_core::H256) -> Option<(pallet::MirrorState, u64)> {
+        None
+    }
+}
+
 #[frame_support::pallet]
 pub mod pallet {
     use frame_support::{
@@ -14,7 +35,7 @@ pub mod pallet {
         traits::Time,
     };
     use frame_system::pallet_prelude::*;
-    use codec::Encode;
+    use codec::{DecodeWithMemTracking, Encode};
     use sp_std::vec::Vec;
     use sp_core::H256;
     use sp_std::vec;
@@ -31,7 +52,8 @@ pub mod pallet {
     use crate::weights::WeightInfo;
     use sp_runtime::traits::SaturatedConversion;
     use sp_std::marker::PhantomData;
-    use frame_support::traits::EnsureOrigin;
+    use frame_support::traits::{EnsureOrigin, PalletInfo};
+    use crate::CredentialStatusProvider;
 
     #[pallet::pallet]
     pub struct Pallet<T>(_);
@@ -51,6 +73,8 @@ pub mod pallet {
         >;
         #[pallet::constant]
         type DefaultXcmFee: Get<Weight>;
+        /// Source of the status pushed by `publish_credential_status`
+        type CredentialStatus: CredentialStatusProvider;
     }
 
     #[pallet::genesis_config]
@@ -110,6 +134,25 @@ pub mod pallet {
         pub endpoint: Option<BoundedVec<u8, ConstU32<4096>>>,
     }
 
+    /// Lifecycle state of a credential mirrored from its issuing chain
+    #[derive(Clone, Encode, Decode, DecodeWithMemTracking, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub enum MirrorState {
+        Active,
+        Revoked,
+        Expired,
+    }
+
+    /// Local view of a credential issued on another parachain
+    #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub struct MirroredCredentialStatus {
+        /// Issuing parachain; only it may update the mirror
+        pub source_para_id: u32,
+        pub state: MirrorState,
+        /// Expiry reported by the issuing chain (0 = no expiry)
+        pub expires_at: u64,
+        pub updated_at: u64,
+    }
+
     /// Storage: Registered parachains
     #[pallet::storage]
     #[pallet::getter(fn registered_parachains)]
@@ -169,6 +212,17 @@ pub mod pallet {
         OptionQuery,
     >;
 
+    /// Storage: Status of credentials issued on other chains, kept in sync via XCM
+    #[pallet::storage]
+    #[pallet::getter(fn mirrored_credentials)]
+    pub type MirroredCredentials<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        H256, // credential hash
+        MirroredCredentialStatus,
+        OptionQuery,
+    >;
+
     #[pallet::event]
     #[pallet::generate_deposit(pub(super) fn deposit_event)]
     pub enum Event<T: Config> {
@@ -196,6 +250,18 @@ pub mod pallet {
             credential_hash: H256,
             source_para_id: u32,
         },
+        /// Credential status sent to a mirroring chain [credential_hash, destination_para_id, state]
+        CredentialStatusPublished {
+            credential_hash: H256,
+            destination_para_id: u32,
+            state: MirrorState,
+        },
+        /// Mirrored credential status updated [credential_hash, source_para_id, state]
+        MirroredCredentialUpdated {
+            credential_hash: H256,
+            source_para_id: u32,
+            state: MirrorState,
+        },
         /// XCM message sent [destination, message_hash]
         XcmMessageSent { destination: u32, message_hash: H256 },
     }
@@ -214,6 +280,8 @@ pub mod pallet {
         EncodingError,
         XcmDeliveryFailed,
         XcmValidationFailed,
+        MirrorSourceMismatch,
+        MirrorStateFinal,
     }
 
     #[pallet::call]
@@ -412,6 +480,86 @@ pub mod pallet {
 
             Ok(())
         }
+
+        /// Push the current status of an exported credential to the chain mirroring it
+        /// The status is read from `CredentialStatus`, so any account may relay it
+        #[pallet::call_index(6)]
+        #[pallet::weight(<T as Config>::WeightInfo::publish_credential_status())]
+        pub fn publish_credential_status(
+            origin: OriginFor<T>,
+            credential_hash: H256,
+            destination_para_id: u32,
+        ) -> DispatchResult {
+            let _who = ensure_signed(origin)?;
+
+            ensure!(
+                RegisteredParachains::<T>::contains_key(destination_para_id),
+                Error::<T>::ParachainNotRegistered
+            );
+
+            // Only credentials previously exported to this chain are mirrored there
+            ensure!(
+                ExportedCredentials::<T>::get(credential_hash, destination_para_id),
+                Error::<T>::CredentialNotExported
+            );
+
+            let (state, expires_at) = T::CredentialStatus::credential_status(&credential_hash)
+                .ok_or(Error::<T>::CredentialNotFound)?;
+
+            Self::send_mirror_status(destination_para_id, credential_hash, state.clone(), expires_at)?;
+
+            Self::deposit_event(Event::CredentialStatusPublished {
+                credential_hash,
+                destination_para_id,
+                state,
+            });
+
+            Ok(())
+        }
+
+        /// Handle an inbound credential status update (called by XCM)
+        /// Revoked and Expired are final: later updates cannot reactivate the mirror
+        #[pallet::call_index(7)]
+        #[pallet::weight(<T as Config>::WeightInfo::update_mirrored_credential())]
+        pub fn update_mirrored_credential(
+            origin: OriginFor<T>,
+            source_para_id: u32,
+            credential_hash: H256,
+            state: MirrorState,
+            expires_at: u64,
+        ) -> DispatchResult {
+            let actual_para_id = Self::ensure_sibling_para(origin)?;
+
+            // Ensure the sender isn't spoofing the ID
+            ensure!(actual_para_id == source_para_id, Error::<T>::InvalidXcmMessage);
+
+            let registry = RegisteredParachains::<T>::get(source_para_id)
+                .ok_or(Error::<T>::ParachainNotRegistered)?;
+            ensure!(registry.trusted, Error::<T>::ParachainNotTrusted);
+
+            MirroredCredentials::<T>::try_mutate(credential_hash, |mirror| -> DispatchResult {
+                if let Some(existing) = mirror {
+                    ensure!(existing.source_para_id == source_para_id, Error::<T>::MirrorSourceMismatch);
+                    ensure!(existing.state == MirrorState::Active, Error::<T>::MirrorStateFinal);
+                }
+
+                *mirror = Some(MirroredCredentialStatus {
+                    source_para_id,
+                    state: state.clone(),
+                    expires_at,
+                    updated_at: <T as Config>::TimeProvider::now().saturated_into::<u64>(),
+                });
+                Ok(())
+            })?;
+
+            Self::deposit_event(Event::MirroredCredentialUpdated {
+                credential_hash,
+                source_para_id,
+                state,
+            });
+
+            Ok(())
+        }
     }
 
     impl<T: Config> Pallet<T> {
@@ -426,7 +574,7 @@ pub mod pallet {
                 [Junction::Parachain(target_para_id)]
             );
 
-            let encoded_call = Self::encode_verification_request_call(credential_hash, request_hash);
+            let encoded_call = Self::encode_verification_request_call(credential_hash, request_hash)?;
 
             let double: xcm::DoubleEncoded<()> = encoded_call
                 .try_into()
@@ -467,7 +615,7 @@ pub mod pallet {
             );
 
             let source_para_id = Self::get_current_para_id();
-            let encoded_call = Self::encode_import_credential_call(source_para_id, credential_hash, credential_data);
+            let encoded_call = Self::encode_import_credential_call(source_para_id, credential_hash, credential_data)?;
 
             let double = encoded_call.try_into().map_err(|_| Error::<T>::EncodingError)?;
             let message = Xcm(vec![
@@ -493,23 +641,69 @@ pub mod pallet {
             Ok(())
         }
 
+        /// Send a credential status update via XCM
+        fn send_mirror_status(
+            destination_para_id: u32,
+            credential_hash: H256,
+            state: MirrorState,
+            expires_at: u64,
+        ) -> DispatchResult {
+            let destination = Location::new(
+                1,
+                [Junction::Parachain(destination_para_id)]
+            );
+
+            let source_para_id = Self::get_current_para_id();
+            let encoded_call = Self::encode_mirror_status_call(source_para_id, credential_hash, state, expires_at)?;
+
+            let double = encoded_call.try_into().map_err(|_| Error::<T>::EncodingError)?;
+            let message = Xcm(vec![
+                Instruction::Transact {
+                    origin_kind: OriginKind::Native,
+                    fallback_max_weight: Some(xcm::v5::Weight::from_parts(
+                        T::DefaultXcmFee::get().ref_time(),
+                        T::DefaultXcmFee::get().proof_size()
+                    )),
+                    call: double,
+                }
+            ]);
+
+            let mut dest = Some(destination);
+            let mut msg = Some(message);
+
+            let (ticket, _assets) = T::XcmRouter::validate(&mut dest, &mut msg)
+                .map_err(|_| Error::<T>::XcmValidationFailed)?;
+
+            T::XcmRouter::deliver(ticket)
+                .map_err(|_| Error::<T>::XcmDeliveryFailed)?;
+
+            Ok(())
+        }
+
         /// Get current parachain ID 
         fn get_current_para_id() -> u32 {
             <T as Config>::ParachainId::get().into()
         }
 
+        /// Index of this pallet in the runtime; counterpart chains deploy it at the same index
+        fn pallet_index() -> Result<u8, Error<T>> {
+            <T as frame_system::Config>::PalletInfo::index::<Self>()
+                .and_then(|index| u8::try_from(index).ok())
+                .ok_or(Error::<T>::EncodingError)
+        }
+
         /// Encode verification request call
         fn encode_verification_request_call(
             credential_hash: H256,
             request_hash: H256,
-        ) -> sp_std::vec::Vec<u8> {
-            (
-                1u8,  // Pallet index 
+        ) -> Result<sp_std::vec::Vec<u8>, Error<T>> {
+            Ok((
+                Self::pallet_index()?,
                 2u8, // Call index for handle_verification_request
                 credential_hash,
                 request_hash,
             )
-            .encode()
+            .encode())
         }
 
         /// Encode import credential call
@@ -517,16 +711,34 @@ pub mod pallet {
             source_para_id: u32,
             credential_hash: H256,
             credential_data: Vec<u8>,
-        ) -> sp_std::vec::Vec<u8> {
+        ) -> Result<sp_std::vec::Vec<u8>, Error<T>> {
             // Use SCALE encoding
-            (
-                1u8,  // Pallet index
+            Ok((
+                Self::pallet_index()?,
                 3u8,  // Call index for import_credential
                 source_para_id,
                 credential_hash,
                 credential_data,
             )
-            .encode()
+            .encode())
+        }
+
+        /// Encode mirrored credential update call
+        fn encode_mirror_status_call(
+            source_para_id: u32,
+            credential_hash: H256,
+            state: MirrorState,
+            expires_at: u64,
+        ) -> Result<sp_std::vec::Vec<u8>, Error<T>> {
+            Ok((
+                Self::pallet_index()?,
+                7u8,  // Call index for update_mirrored_credential
+                source_para_id,
+                credential_hash,
+                state,
+                expires_at,
+            )
+            .encode())
         }
 
         fn ensure_sibling_para(origin: OriginFor<T>) -> Result<u32, Error<T>> {
@@ -576,6 +788,16 @@ pub mod pallet {
             valid_responses.len() >= required_consensus as usize
         }
 
+        /// Check if a credential mirrored from its issuing chain is active and unexpired
+        pub fn is_mirrored_credential_valid(credential_hash: &H256) -> bool {
+            let Some(mirror) = MirroredCredentials::<T>::get(credential_hash) else {
+                return false;
+            };
+
+            let now = <T as Config>::TimeProvider::now().saturated_into::<u64>();
+            mirror.state == MirrorState::Active && (mirror.expires_at == 0 || now < mirror.expires_at)
+        }
+
         /// Get all verification responses for a credential
         pub fn get_verification_responses(
             credential_hash: &H256,
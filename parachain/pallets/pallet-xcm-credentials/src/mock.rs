@@ -0,0 +1,210 @@
+use crate as pallet_xcm_credentials;
+use crate::{pallet::MirrorState, CredentialStatusProvider};
+use codec::Encode;
+use cumulus_primitives_core::ParaId;
+use frame_support::{
+    derive_impl, parameter_types,
+    traits::{ConstU32, ConstU64, Disabled, Everything, Nothing},
+};
+use frame_system::EnsureRoot;
+use sp_core::H256;
+use sp_runtime::{traits::IdentityLookup, AccountId32, BuildStorage};
+use std::{cell::RefCell, collections::BTreeMap};
+use xcm::latest::prelude::*;
+use xcm_builder::{
+    AccountId32Aliases, EnsureXcmOrigin, FixedWeightBounds, FrameTransactionalProcessor,
+    SignedToAccountId32,
+};
+use xcm_executor::XcmExecutor;
+
+type Block = frame_system::mocking::MockBlock<Test>;
+pub type AccountId = AccountId32;
+pub type Balance = u64;
+
+pub const ALICE: AccountId32 = AccountId32::new([1u8; 32]);
+
+/// Para ID of the chain under test
+pub const SELF_PARA_ID: u32 = 2000;
+/// Para ID of the chain mirroring our credentials
+pub const MIRROR_PARA_ID: u32 = 2001;
+
+// XcmCredentials deliberately does not sit at pallet index 1
+frame_support::construct_runtime!(
+    pub enum Test {
+        System: frame_system,
+        Timestamp: pallet_timestamp,
+        Balances: pallet_balances,
+        PolkadotXcm: pallet_xcm,
+        XcmCredentials: pallet_xcm_credentials,
+    }
+);
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for Test {
+    type Block = Block;
+    type AccountId = AccountId;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type AccountData = pallet_balances::AccountData<Balance>;
+}
+
+#[derive_impl(pallet_balances::config_preludes::TestDefaultConfig)]
+impl pallet_balances::Config for Test {
+    type Balance = Balance;
+    type AccountStore = System;
+}
+
+impl pallet_timestamp::Config for Test {
+    type Moment = u64;
+    type OnTimestampSet = ();
+    type MinimumPeriod = ConstU64<1>;
+    type WeightInfo = ();
+}
+
+thread_local! {
+    /// Messages handed to the router, in send order
+    pub static SENT_XCM: RefCell<Vec<(Location, Xcm<()>)>> = RefCell::new(Vec::new());
+    /// Status each locally issued credential reports to `publish_credential_status`
+    pub static CREDENTIAL_STATUS: RefCell<BTreeMap<H256, (MirrorState, u64)>> = RefCell::new(BTreeMap::new());
+}
+
+/// Router that records outbound messages instead of delivering them
+pub struct TestXcmRouter;
+
+impl SendXcm for TestXcmRouter {
+    type Ticket = (Location, Xcm<()>);
+
+    fn validate(
+        destination: &mut Option<Location>,
+        message: &mut Option<Xcm<()>>,
+    ) -> SendResult<Self::Ticket> {
+        let ticket = (
+            destination.take().ok_or(SendError::MissingArgument)?,
+            message.take().ok_or(SendError::MissingArgument)?,
+        );
+        Ok((ticket, Assets::new()))
+    }
+
+    fn deliver(ticket: Self::Ticket) -> Result<XcmHash, SendError> {
+        let hash = ticket.1.using_encoded(sp_io::hashing::blake2_256);
+        SENT_XCM.with(|sent| sent.borrow_mut().push(ticket));
+        Ok(hash)
+    }
+}
+
+/// Stand-in for the credentials pallet that issues credentials on this chain
+pub struct TestCredentialStatus;
+
+impl CredentialStatusProvider for TestCredentialStatus {
+    fn credential_status(credential_hash: &H256) -> Option<(MirrorState, u64)> {
+        CREDENTIAL_STATUS.with(|status| status.borrow().get(credential_hash).cloned())
+    }
+}
+
+pub fn set_credential_status(credential_hash: H256, state: MirrorState, expires_at: u64) {
+    CREDENTIAL_STATUS.with(|status| status.borrow_mut().insert(credential_hash, (state, expires_at)));
+}
+
+pub fn take_sent_xcm() -> Vec<(Location, Xcm<()>)> {
+    SENT_XCM.with(|sent| sent.take())
+}
+
+parameter_types! {
+    pub const RelayNetwork: Option<NetworkId> = None;
+    pub UniversalLocation: InteriorLocation = [Parachain(SELF_PARA_ID)].into();
+    pub const UnitWeightCost: Weight = Weight::from_parts(1_000, 1_000);
+    pub const MaxInstructions: u32 = 100;
+    pub SelfParaId: ParaId = ParaId::from(SELF_PARA_ID);
+    pub const DefaultXcmFee: Weight = Weight::from_parts(1_000_000_000, 0);
+}
+
+pub type LocalOriginToLocation = SignedToAccountId32<RuntimeOrigin, AccountId, RelayNetwork>;
+
+/// Executor config for a chain that only needs to send; nothing inbound is executed
+pub struct XcmConfig;
+impl xcm_executor::Config for XcmConfig {
+    type RuntimeCall = RuntimeCall;
+    type XcmSender = TestXcmRouter;
+    type XcmEventEmitter = PolkadotXcm;
+    type AssetTransactor = ();
+    type OriginConverter = pallet_xcm::XcmPassthrough<RuntimeOrigin>;
+    type IsReserve = ();
+    type IsTeleporter = ();
+    type UniversalLocation = UniversalLocation;
+    type Barrier = ();
+    type Weigher = FixedWeightBounds<UnitWeightCost, RuntimeCall, MaxInstructions>;
+    type Trader = ();
+    type ResponseHandler = PolkadotXcm;
+    type AssetTrap = PolkadotXcm;
+    type AssetClaims = PolkadotXcm;
+    type SubscriptionService = PolkadotXcm;
+    type PalletInstancesInfo = AllPalletsWithSystem;
+    type MaxAssetsIntoHolding = MaxInstructions;
+    type AssetLocker = ();
+    type AssetExchanger = ();
+    type FeeManager = ();
+    type MessageExporter = ();
+    type UniversalAliases = Nothing;
+    type CallDispatcher = RuntimeCall;
+    type SafeCallFilter = Everything;
+    type Aliasers = Nothing;
+    type TransactionalProcessor = FrameTransactionalProcessor;
+    type HrmpNewChannelOpenRequestHandler = ();
+    type HrmpChannelAcceptedHandler = ();
+    type HrmpChannelClosingHandler = ();
+    type XcmRecorder = PolkadotXcm;
+}
+
+impl pallet_xcm::Config for Test {
+    type RuntimeEvent = RuntimeEvent;
+    type SendXcmOrigin = EnsureXcmOrigin<RuntimeOrigin, LocalOriginToLocation>;
+    type XcmRouter = TestXcmRouter;
+    type ExecuteXcmOrigin = EnsureXcmOrigin<RuntimeOrigin, LocalOriginToLocation>;
+    type XcmExecuteFilter = Nothing;
+    type XcmExecutor = XcmExecutor<XcmConfig>;
+    type XcmTeleportFilter = Nothing;
+    type XcmReserveTransferFilter = Nothing;
+    type Weigher = FixedWeightBounds<UnitWeightCost, RuntimeCall, MaxInstructions>;
+    type UniversalLocation = UniversalLocation;
+    type RuntimeOrigin = RuntimeOrigin;
+    type RuntimeCall = RuntimeCall;
+    const VERSION_DISCOVERY_QUEUE_SIZE: u32 = 100;
+    type AdvertisedXcmVersion = pallet_xcm::CurrentXcmVersion;
+    type Currency = Balances;
+    type CurrencyMatcher = ();
+    type TrustedLockers = ();
+    type SovereignAccountOf = AccountId32Aliases<RelayNetwork, AccountId>;
+    type MaxLockers = ConstU32<8>;
+    type WeightInfo = pallet_xcm::TestWeightInfo;
+    type AdminOrigin = EnsureRoot<AccountId>;
+    type MaxRemoteLockConsumers = ConstU32<0>;
+    type RemoteLockConsumerIdentifier = ();
+    type AuthorizedAliasConsideration = Disabled;
+}
+
+impl pallet_xcm_credentials::pallet::Config for Test {
+    type TimeProvider = Timestamp;
+    type WeightInfo = ();
+    type ParachainId = SelfParaId;
+    type XcmOriginToTransactDispatchOrigin = pallet_xcm::EnsureXcm<Everything>;
+    type ParachainIdentity = pallet_xcm::EnsureXcm<Everything>;
+    type DefaultXcmFee = DefaultXcmFee;
+    type CredentialStatus = TestCredentialStatus;
+}
+
+/// Origin of a Transact arriving from a sibling parachain
+pub fn sibling_origin(para_id: u32) -> RuntimeOrigin {
+    pallet_xcm::Origin::Xcm(Location::new(1, [Parachain(para_id)])).into()
+}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    let storage = frame_system::GenesisConfig::<Test>::default()
+        .build_storage()
+        .unwrap();
+
+    let mut ext: sp_io::TestExternalities = storage.into();
+    ext.execute_with(|| {
+        System::set_block_number(1);
+        Timestamp::set_timestamp(1_700_000_000);
+    });
+    ext
+}
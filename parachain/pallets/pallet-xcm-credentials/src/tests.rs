@@ -0,0 +1,111 @@
+use crate::{mock::*, pallet::*};
+use codec::Decode;
+use frame_support::{assert_noop, assert_ok, traits::PalletInfo};
+use sp_core::H256;
+use sp_runtime::traits::Dispatchable;
+use xcm::latest::prelude::*;
+
+/// Hand every message sent to `MIRROR_PARA_ID` to the mirror's call handler, as its
+/// XCM executor would after matching the Transact origin to our sibling location
+fn relay_to_mirror() {
+    for (destination, message) in take_sent_xcm() {
+        assert_eq!(destination, Location::new(1, [Parachain(MIRROR_PARA_ID)]));
+        for instruction in message.0 {
+            if let Transact { call, .. } = instruction {
+                let call = RuntimeCall::decode(&mut &call.into_encoded()[..]).unwrap();
+                assert_ok!(call.dispatch(sibling_origin(SELF_PARA_ID)));
+            }
+        }
+    }
+}
+
+/// Register both ends and export `credential_hash` to the mirror
+fn export_to_mirror(credential_hash: H256) {
+    assert_ok!(XcmCredentials::register_parachain(RuntimeOrigin::root(), MIRROR_PARA_ID, true));
+    // The mirror only accepts updates from chains it trusts
+    assert_ok!(XcmCredentials::register_parachain(RuntimeOrigin::root(), SELF_PARA_ID, true));
+    assert_ok!(XcmCredentials::export_credential(
+        RuntimeOrigin::signed(ALICE),
+        credential_hash,
+        MIRROR_PARA_ID,
+        b"credential".to_vec(),
+    ));
+    relay_to_mirror();
+}
+
+#[test]
+fn mirror_follows_issued_then_revoked_credential() {
+    new_test_ext().execute_with(|| {
+        let credential_hash = H256::repeat_byte(0x42);
+        export_to_mirror(credential_hash);
+
+        set_credential_status(credential_hash, MirrorState::Active, 0);
+        assert_ok!(XcmCredentials::publish_credential_status(
+            RuntimeOrigin::signed(ALICE),
+            credential_hash,
+            MIRROR_PARA_ID,
+        ));
+        relay_to_mirror();
+
+        let mirror = XcmCredentials::mirrored_credentials(credential_hash).unwrap();
+        assert_eq!(mirror.source_para_id, SELF_PARA_ID);
+        assert_eq!(mirror.state, MirrorState::Active);
+        assert!(XcmCredentials::is_mirrored_credential_valid(&credential_hash));
+
+        set_credential_status(credential_hash, MirrorState::Revoked, 0);
+        assert_ok!(XcmCredentials::publish_credential_status(
+            RuntimeOrigin::signed(ALICE),
+            credential_hash,
+            MIRROR_PARA_ID,
+        ));
+        relay_to_mirror();
+
+        assert_eq!(XcmCredentials::mirrored_credentials(credential_hash).unwrap().state, MirrorState::Revoked);
+        assert!(!XcmCredentials::is_mirrored_credential_valid(&credential_hash));
+        System::assert_last_event(
+            Event::MirroredCredentialUpdated {
+                credential_hash,
+                source_para_id: SELF_PARA_ID,
+                state: MirrorState::Revoked,
+            }
+            .into(),
+        );
+    });
+}
+
+#[test]
+fn published_status_comes_from_the_issuing_pallet() {
+    new_test_ext().execute_with(|| {
+        let credential_hash = H256::repeat_byte(0x42);
+        export_to_mirror(credential_hash);
+
+        // Nothing to publish for a credential the issuing pallet does not know
+        assert_noop!(
+            XcmCredentials::publish_credential_status(RuntimeOrigin::signed(ALICE), credential_hash, MIRROR_PARA_ID),
+            Error::<Test>::CredentialNotFound
+        );
+        assert!(take_sent_xcm().is_empty());
+    });
+}
+
+#[test]
+fn mirror_calls_target_this_pallets_index() {
+    new_test_ext().execute_with(|| {
+        let credential_hash = H256::repeat_byte(0x42);
+        assert_ok!(XcmCredentials::register_parachain(RuntimeOrigin::root(), MIRROR_PARA_ID, true));
+        assert_ok!(XcmCredentials::export_credential(
+            RuntimeOrigin::signed(ALICE),
+            credential_hash,
+            MIRROR_PARA_ID,
+            b"credential".to_vec(),
+        ));
+
+        let (_, message) = take_sent_xcm().pop().unwrap();
+        let Some(Transact { call, .. }) = message.0.into_iter().next() else {
+            panic!("expected a Transact");
+        };
+        let pallet_index = <Test as frame_system::Config>::PalletInfo::index::<XcmCredentials>().unwrap();
+        assert_ne!(pallet_index, 1);
+        assert_eq!(call.into_encoded()[0] as usize, pallet_index);
+    });
+}
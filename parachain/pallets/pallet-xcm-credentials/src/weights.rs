@@ -8,6 +8,8 @@ pub trait WeightInfo {
     fn import_credential() -> Weight;
     fn handle_verification_response() -> Weight;
     fn deregister_parachain() -> Weight;
+    fn publish_credential_status() -> Weight;
+    fn update_mirrored_credential() -> Weight;
 }
 
 pub struct SubstrateWeight<T>(core::marker::PhantomData<T>);
@@ -49,6 +51,18 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
             .saturating_add(T::DbWeight::get().reads(1))
             .saturating_add(T::DbWeight::get().writes(1))
     }
+    
+    fn publish_credential_status() -> Weight {
+        // Includes XCM message sending overhead
+        Weight::from_parts(200_000_000, 0)
+            .saturating_add(T::DbWeight::get().reads(3))
+    }
+    
+    fn update_mirrored_credential() -> Weight {
+        Weight::from_parts(60_000_000, 0)
+            .saturating_add(T::DbWeight::get().reads(2))
+            .saturating_add(T::DbWeight::get().writes(1))
+    }
 }
 
 impl WeightInfo for () {
@@ -58,4 +72,6 @@ impl WeightInfo for () {
     fn import_credential() -> Weight { Weight::from_parts(70_000_000, 0) }
     fn handle_verification_response() -> Weight { Weight::from_parts(60_000_000, 0) }
     fn deregister_parachain() -> Weight { Weight::from_parts(40_000_000, 0) }
+    fn publish_credential_status() -> Weight { Weight::from_parts(200_000_000, 0) }
+    fn update_mirrored_credential() -> Weight { Weight::from_parts(60_000_000, 0) }
 }
\ No newline at end of file
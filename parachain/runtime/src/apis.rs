@@ -46,16 +46,160 @@ impl Runtime {
 }
 
 decl_runtime_apis! {
-    pub trait PersonhoodApi {
+    pub trait PersonhoodApi<AccountId, GuardianRelationship> where
+        AccountId: codec::Codec,
+        GuardianRelationship: codec::Codec,
+    {
         fn verify_personhood_existence(nullifier: H256) -> bool;
+        fn is_session_token_used(session_id: H256) -> bool;
+        fn pending_recoveries_for_guardian(guardian: AccountId) -> Vec<H256>;
+        fn classify_score(did: H256, score: u8, now: u64) -> pallet_proof_of_personhood::pallet::AnomalyType;
+        fn guardians_of(did: H256) -> Vec<(AccountId, GuardianRelationship)>;
+        fn is_guardian(did: H256, account: AccountId) -> bool;
+        fn check_registration_conflicts(
+            requests: frame_support::BoundedVec<
+                (H256, H256),
+                frame_support::traits::ConstU32<{ pallet_proof_of_personhood::pallet::MAX_CONFLICT_CHECKS }>,
+            >,
+        ) -> Vec<pallet_proof_of_personhood::pallet::RegistrationConflict>;
+        fn ml_subsystem_health() -> pallet_proof_of_personhood::pallet::MlHealthReport;
+        fn score_percentiles() -> pallet_proof_of_personhood::pallet::ScorePercentiles;
+        fn export_behavioral_data(
+            did: H256,
+            controller: AccountId,
+            valid_until: u64,
+            nonce: u64,
+            signature: sp_runtime::MultiSignature,
+        ) -> Option<pallet_proof_of_personhood::pallet::BehavioralExport>;
+        fn bound_modalities(did: H256) -> Vec<(H256, pallet_proof_of_personhood::pallet::BiometricModality)>;
+    }
+
+    pub trait VerifiableCredentialsApi {
+        fn validate_issuance(
+            issuer_did: H256,
+            subject_did: H256,
+            credential_type: pallet_verifiable_credentials::pallet::CredentialType,
+            expires_at: u64,
+            fields: Vec<Vec<u8>>,
+            required_fields: Vec<bool>,
+            fields_to_reveal: Vec<u32>,
+            encrypted_fields: Vec<bool>,
+        ) -> Result<(), sp_runtime::DispatchError>;
+        fn statuses_of(
+            credential_ids: frame_support::BoundedVec<
+                H256,
+                frame_support::traits::ConstU32<{ pallet_verifiable_credentials::pallet::MAX_STATUS_QUERIES }>,
+            >,
+        ) -> Vec<(H256, Option<pallet_verifiable_credentials::pallet::CredentialStatus>)>;
     }
 }
 
 impl_runtime_apis! {
-	impl self::PersonhoodApi<Block> for Runtime {
+	impl self::PersonhoodApi<Block, AccountId, pallet_proof_of_personhood::pallet::GuardianRelationship<Runtime>> for Runtime {
 		fn verify_personhood_existence(nullifier: H256) -> bool {
 			pallet_proof_of_personhood::pallet::PersonhoodRegistry::<Runtime>::contains_key(nullifier)
 		}
+
+		fn is_session_token_used(session_id: H256) -> bool {
+			pallet_proof_of_personhood::pallet::Pallet::<Runtime>::is_session_token_used(&session_id)
+		}
+
+		fn pending_recoveries_for_guardian(guardian: AccountId) -> Vec<H256> {
+			pallet_proof_of_personhood::pallet::Pallet::<Runtime>::pending_recoveries_for_guardian(&guardian)
+		}
+
+		fn classify_score(did: H256, score: u8, now: u64) -> pallet_proof_of_personhood::pallet::AnomalyType {
+			pallet_proof_of_personhood::pallet::Pallet::<Runtime>::classify_score(&did, score, now)
+		}
+
+		fn guardians_of(
+			did: H256,
+		) -> Vec<(AccountId, pallet_proof_of_personhood::pallet::GuardianRelationship<Runtime>)> {
+			pallet_proof_of_personhood::pallet::Pallet::<Runtime>::guardians_of(&did)
+		}
+
+		fn is_guardian(did: H256, account: AccountId) -> bool {
+			pallet_proof_of_personhood::pallet::Pallet::<Runtime>::is_guardian(&did, &account)
+		}
+
+		fn check_registration_conflicts(
+			requests: frame_support::BoundedVec<
+				(H256, H256),
+				frame_support::traits::ConstU32<{ pallet_proof_of_personhood::pallet::MAX_CONFLICT_CHECKS }>,
+			>,
+		) -> Vec<pallet_proof_of_personhood::pallet::RegistrationConflict> {
+			pallet_proof_of_personhood::pallet::Pallet::<Runtime>::check_registration_conflicts(requests)
+		}
+
+		fn ml_subsystem_health() -> pallet_proof_of_personhood::pallet::MlHealthReport {
+			pallet_proof_of_personhood::pallet::Pallet::<Runtime>::ml_subsystem_health()
+		}
+
+		fn score_percentiles() -> pallet_proof_of_personhood::pallet::ScorePercentiles {
+			pallet_proof_of_personhood::pallet::Pallet::<Runtime>::score_percentiles()
+		}
+
+		fn export_behavioral_data(
+			did: H256,
+			controller: AccountId,
+			valid_until: u64,
+			nonce: u64,
+			signature: sp_runtime::MultiSignature,
+		) -> Option<pallet_proof_of_personhood::pallet::BehavioralExport> {
+			use sp_runtime::traits::Verify;
+
+			// Only release the export to the controller that signed for it, and only while
+			// the signed request is fresh
+			let payload = pallet_proof_of_personhood::pallet::Pallet::<Runtime>::behavioral_export_payload(
+				&did,
+				valid_until,
+				nonce,
+			);
+			if !signature.verify(&payload[..], &controller) {
+				return None;
+			}
+			pallet_proof_of_personhood::pallet::Pallet::<Runtime>::export_behavioral_data_for(&did, &controller, valid_until)
+				.ok()
+				.flatten()
+		}
+
+		fn bound_modalities(did: H256) -> Vec<(H256, pallet_proof_of_personhood::pallet::BiometricModality)> {
+			pallet_proof_of_personhood::pallet::Pallet::<Runtime>::get_bound_modalities(&did)
+		}
+	}
+
+	impl self::VerifiableCredentialsApi<Block> for Runtime {
+		fn validate_issuance(
+			issuer_did: H256,
+			subject_did: H256,
+			credential_type: pallet_verifiable_credentials::pallet::CredentialType,
+			expires_at: u64,
+			fields: Vec<Vec<u8>>,
+			required_fields: Vec<bool>,
+			fields_to_reveal: Vec<u32>,
+			encrypted_fields: Vec<bool>,
+		) -> Result<(), sp_runtime::DispatchError> {
+			pallet_verifiable_credentials::pallet::Pallet::<Runtime>::validate_issuance(
+				&issuer_did,
+				&subject_did,
+				&credential_type,
+				expires_at,
+				fields,
+				required_fields,
+				fields_to_reveal,
+				encrypted_fields,
+			)
+			.map_err(Into::into)
+		}
+
+		fn statuses_of(
+			credential_ids: frame_support::BoundedVec<
+				H256,
+				frame_support::traits::ConstU32<{ pallet_verifiable_credentials::pallet::MAX_STATUS_QUERIES }>,
+			>,
+		) -> Vec<(H256, Option<pallet_verifiable_credentials::pallet::CredentialStatus>)> {
+			pallet_verifiable_credentials::pallet::Pallet::<Runtime>::statuses_of(credential_ids)
+		}
 	}
 
 	impl sp_consensus_aura::AuraApi<Block, AuraId> for Runtime {
@@ -29,7 +29,7 @@ use polkadot_runtime_common::{
 	xcm_sender::NoPriceForMessageDelivery, BlockHashCount, SlowAdjustingFeeUpdate,
 };
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
-use sp_runtime::Perbill;
+use sp_runtime::{traits::AccountIdConversion, Perbill, Percent};
 use sp_version::RuntimeVersion;
 use xcm::latest::prelude::BodyId;
 
@@ -327,6 +327,12 @@ impl pallet_verifiable_credentials::pallet::Config for Runtime {
     type MaxFields = ConstU32<16>;
     type MaxFieldsToReveal = ConstU32<16>;
     type MaxCredentialCleanupPerBlock = ConstU32<10>; 
+    type StrictFieldPrivacy = ConstBool<false>;
+    type MaxPlaintextFieldSize = ConstU32<64>;
+    type MinCiphertextLength = ConstU32<28>;
+    type MaxCredentialValidity = ConstU64<{ 100 * 365 * 24 * 60 * 60 }>; // 100 years
+    type MaxRevokedPerChunk = ConstU32<32>;
+    type MaxRevocationsPerCall = ConstU32<50>;
 }
 
 impl pallet_zk_credentials::pallet::Config for Runtime {
@@ -352,6 +358,28 @@ parameter_types! {
     pub const DefaultXcmFee: Weight = Weight::from_parts(500_000_000_000, 0);
 }
 
+/// Publishes the status recorded by pallet-verifiable-credentials to mirroring chains
+pub struct VerifiableCredentialStatus;
+
+impl pallet_xcm_credentials::CredentialStatusProvider for VerifiableCredentialStatus {
+    fn credential_status(
+        credential_hash: &sp_core::H256,
+    ) -> Option<(pallet_xcm_credentials::pallet::MirrorState, u64)> {
+        use pallet_verifiable_credentials::pallet::{CredentialStatus, Credentials};
+        use pallet_xcm_credentials::pallet::MirrorState;
+
+        let credential = Credentials::<Runtime>::get(credential_hash)?;
+        let state = match credential.status {
+            CredentialStatus::Active => MirrorState::Active,
+            CredentialStatus::Revoked => MirrorState::Revoked,
+            CredentialStatus::Expired => MirrorState::Expired,
+            // Suspension can be lifted, but Revoked and Expired are final on the mirror
+            CredentialStatus::Suspended => return None,
+        };
+        Some((state, credential.expires_at))
+    }
+}
+
 impl pallet_xcm_credentials::pallet::Config for Runtime {
     type TimeProvider = pallet_timestamp::Pallet<Runtime>;
     type ParachainId = parachain_info::Pallet<Runtime>;
@@ -359,11 +387,20 @@ impl pallet_xcm_credentials::pallet::Config for Runtime {
 	type ParachainIdentity = pallet_xcm::EnsureXcm<frame_support::traits::Everything>;
     type DefaultXcmFee = DefaultXcmFee;
     type WeightInfo = pallet_xcm_credentials::weights::SubstrateWeight<Runtime>;
+    type CredentialStatus = VerifiableCredentialStatus;
 }
 
 parameter_types! {
     pub const RegistrationDeposit: Balance = 100 * UNIT;
     pub const RecoveryDeposit: Balance = 50 * UNIT;
+    // Room for a guardian to back up to 100 DIDs at the 500 UNIT minimum bond
+    pub const MaxGuardianBondPerAccount: Option<Balance> = Some(50_000 * UNIT);
+    pub const BehavioralSampleConfidenceTiers: [u8; 5] = [50, 70, 85, 95, 100];
+    pub const FraudRewardPercent: Percent = Percent::from_percent(50);
+    pub const ChallengeVoteMargin: Percent = Percent::from_percent(20);
+    pub const GuardianStrengthThreshold: Percent = Percent::from_percent(67);
+    pub const PersonhoodInsurancePotId: PalletId = PalletId(*b"pop/insu");
+    pub PersonhoodInsurancePot: AccountId = PersonhoodInsurancePotId::get().into_account_truncating();
 }
 
 impl pallet_proof_of_personhood::pallet::Config for Runtime {
@@ -376,4 +413,48 @@ impl pallet_proof_of_personhood::pallet::Config for Runtime {
     type AuthorityId = pallet_proof_of_personhood::crypto::TestAuthId;
     type MinBehavioralConfidence = ConstU8<80>;
     type MinHistoricalStrength = ConstU8<90>;
+    type GuardianAgeBonusThreshold = ConstU64<{ 365 * 24 * 60 * 60 }>;
+    type GuardianAgeBonus = ConstU32<2>;
+    type MaxMLPatternsPerRun = ConstU32<10>;
+    type MaxBiometricBatchSize = ConstU32<5>;
+    type BehavioralStoreThreshold = ConstU8<80>;
+    type BehavioralDriftStoreThreshold = ConstU8<70>;
+    type MaxActiveOracles = ConstU32<16>;
+    type BehavioralSimilarityWeight = ConstU8<70>;
+    type BehavioralZeroConfidenceDistance = ConstU32<100>;
+    type BehavioralSampleConfidenceTiers = BehavioralSampleConfidenceTiers;
+    type MaxScoreStatSamples = ConstU32<1_000>;
+    type MinGuardianRelationshipAge = ConstU64<{ 7 * 24 * 60 * 60 }>;
+    #[cfg(not(feature = "dev-skip-zk-verification"))]
+    type DevSkipZkVerification = ConstBool<false>;
+    #[cfg(feature = "dev-skip-zk-verification")]
+    type DevSkipZkVerification = ConstBool<true>;
+    type MinGuardianBond = frame_support::traits::ConstU128<{ 500 * UNIT }>;
+    type MaxGuardianBondPerAccount = MaxGuardianBondPerAccount;
+    // Compressed Groth16 proof over BN254
+    type MinRecoveryProofLength = ConstU32<128>;
+    type MaxReputationGainPerWindow = ConstU8<10>;
+    type ReputationGainWindow = ConstU64<{ 24 * 60 * 60 }>;
+    type SessionValidityWindow = ConstU64<300>;
+    type ConsensusOutcomeWindow = ConstU64<{ 24 * 60 * 60 }>;
+    type TrendDeviationTolerancePercent = ConstU8<20>;
+    type MinSamplesForEnvelopeRejection = ConstU32<5>;
+    type MaxRegistrationAttemptsPerWindow = ConstU32<3>;
+    type RegistrationAttemptWindow = ConstU64<{ 24 * 60 * 60 }>;
+    type FraudRewardPercent = FraudRewardPercent;
+    type InsurancePot = PersonhoodInsurancePot;
+    type MaxPendingMLPatterns = ConstU32<1_000>;
+    type RecoveryDelay = ConstU64<{ 6 * 30 * 24 * 60 * 60 }>;
+    type RecoveryProofNonceLifetime = ConstU64<{ 6 * 30 * 24 * 60 * 60 }>;
+    type RegistrationCooldownPeriod = ConstU64<{ 6 * 30 * 24 * 60 * 60 }>;
+    type BaseRecoveryDelay = ConstU64<{ 6 * 30 * 24 * 60 * 60 }>;
+    type MinRecoveryDelay = ConstU64<{ 7 * 24 * 60 * 60 }>;
+    type RequiredRecoveryScore = ConstU32<100>;
+    type MaxFraudProofAge = ConstU64<{ 7 * 24 * 60 * 60 }>;
+    type ChallengeVoteQuorum = ConstU32<10>;
+    type ChallengeVoteMargin = ChallengeVoteMargin;
+    type MLResponseValidity = ConstU64<60>;
+    type MaxGuardiansPerBatch = ConstU32<10>;
+    type GuardianStrengthThreshold = GuardianStrengthThreshold;
+    type OracleResponseWindow = ConstU64<{ 60 * 60 }>;
 }
\ No newline at end of file
@@ -163,6 +163,7 @@ impl frame_system::offchain::CreateSignedTransaction<pallet_proof_of_personhood:
 			frame_system::CheckWeight::<Runtime>::new(),
 			pallet_transaction_payment::ChargeTransactionPayment::<Runtime>::from(0u128),
 			frame_metadata_hash_extension::CheckMetadataHash::<Runtime>::new(true),
+			pallet_proof_of_personhood::CheckRegistrationRate::<Runtime>::new(),
 		);
 
         let extension = TxExtension::new(signed_extra);
@@ -198,6 +199,7 @@ pub type TxExtension = cumulus_pallet_weight_reclaim::StorageWeightReclaim<
 		frame_system::CheckWeight<Runtime>,
 		pallet_transaction_payment::ChargeTransactionPayment<Runtime>,
 		frame_metadata_hash_extension::CheckMetadataHash<Runtime>,
+		pallet_proof_of_personhood::CheckRegistrationRate<Runtime>,
 	),
 >;
 
@@ -208,7 +210,16 @@ pub type UncheckedExtrinsic = generic::UncheckedExtrinsic<Address, RuntimeCall,
 ///
 /// This can be a tuple of types, each implementing `OnRuntimeUpgrade`.
 #[allow(unused_parens)]
-type Migrations = ();
+type Migrations = (
+	pallet_proof_of_personhood::migrations::v1::MigrateToV1<Runtime>,
+	pallet_proof_of_personhood::migrations::v2::MigrateToV2<Runtime>,
+	pallet_proof_of_personhood::migrations::v3::MigrateToV3<Runtime>,
+	pallet_proof_of_personhood::migrations::v4::MigrateToV4<Runtime>,
+	pallet_proof_of_personhood::migrations::v5::MigrateToV5<Runtime>,
+	pallet_verifiable_credentials::migrations::v1::MigrateToV1<Runtime>,
+	pallet_verifiable_credentials::migrations::v2::MigrateToV2<Runtime>,
+	pallet_verifiable_credentials::migrations::v3::MigrateToV3<Runtime>,
+);
 
 /// Executive: handles dispatch to the various modules.
 pub type Executive = frame_executive::Executive<
@@ -363,4 +374,39 @@ pub fn native_version() -> NativeVersion {
 cumulus_pallet_parachain_system::register_validate_block! {
 	Runtime = Runtime,
 	BlockExecutor = cumulus_pallet_aura_ext::BlockExecutor::<Runtime, Executive>,
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use frame_support::traits::{Hooks, PalletInfo as _};
+
+	fn assert_offchain_signing<T>()
+	where
+		T: pallet_proof_of_personhood::Config
+			+ frame_system::offchain::CreateSignedTransaction<pallet_proof_of_personhood::Call<T>>,
+	{
+	}
+
+	#[test]
+	fn proof_of_personhood_offchain_worker_is_wired() {
+		assert_eq!(<Runtime as frame_system::Config>::PalletInfo::index::<ProofOfPersonhood>(), Some(56));
+
+		// Resolves only if the AuthorityId/MultiSigner bounds are satisfied by the runtime
+		assert_offchain_signing::<Runtime>();
+		let _worker: fn(BlockNumber) = <ProofOfPersonhood as Hooks<BlockNumber>>::offchain_worker;
+	}
+
+	#[test]
+	fn proof_of_personhood_calls_are_in_metadata() {
+		let metadata = Runtime::metadata_ir();
+		let pallet = metadata
+			.pallets
+			.iter()
+			.find(|pallet| pallet.name == "ProofOfPersonhood")
+			.expect("ProofOfPersonhood is part of the runtime");
+
+		assert_eq!(pallet.index, 56);
+		let calls = pallet.calls.as_ref().expect("ProofOfPersonhood exposes calls");
+		assert_eq!(calls.ty, scale_info::meta_type::<pallet_proof_of_personhood::Call<Runtime>>());
+	}
+}